@@ -0,0 +1,198 @@
+//! Permission bits and delegated access for accounts and their assets.
+//!
+//! Like an inode, every account carries a mode giving view, debit, credit and transfer to its
+//! owner, its group and everyone else, where the group is the token manager behind the account
+//! along with its operators. An asset may carry a mode of its own, otherwise it follows the one of
+//! its account. On top of the mode, the owner grants permissions to other UA addresses, optionally
+//! capping the value they may take out.
+//!
+//! Bits are written symbolically as `vdct`, with `-` for the permissions that aren't given.
+
+use error_stack::{report, ResultExt};
+
+use crate::auth::Principal;
+use crate::error::{AclError, SResult, StorageError};
+use crate::ledger;
+use crate::state::AppState;
+use crate::storage::types::{Acl, GrantCharge, Permission};
+use crate::utils::unix_timestamp;
+
+/// Parses symbolic bits such as `vd-t`, in any order.
+pub fn parse_bits(bits: &str) -> Option<u8> {
+    bits.chars().try_fold(0, |acc, symbol| match symbol {
+        '-' => Some(acc),
+        symbol => Permission::ALL
+            .into_iter()
+            .find(|permission| permission.symbol() == symbol)
+            .map(|permission| acc | permission.bit()),
+    })
+}
+
+pub fn format_bits(bits: u8) -> String {
+    Permission::ALL
+        .into_iter()
+        .map(|permission| {
+            if bits & permission.bit() != 0 {
+                permission.symbol()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// ACL in effect for the account, or for one of its assets, along with the resource it is kept
+/// under. `inherited` is set when an asset follows its account, and the ACL is the default one
+/// when the account has none.
+pub struct Effective {
+    pub resource: String,
+    pub acl: Acl,
+    pub inherited: bool,
+}
+
+pub async fn effective(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: Option<&str>,
+) -> SResult<Effective, AclError> {
+    let acl_interface = app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(AclError::StorageError)?;
+
+    if let Some(asset_id) = asset_id {
+        let resource = ledger::asset_resource(user_id, account_id, asset_id);
+
+        if let Ok(acl) = acl_interface.get_acl(&resource).await {
+            return Ok(Effective {
+                resource,
+                acl,
+                inherited: false,
+            });
+        }
+    }
+
+    let resource = ledger::account_resource(user_id, account_id);
+    let acl = match acl_interface.get_acl(&resource).await {
+        Ok(acl) => acl,
+        Err(error) if matches!(error.current_context(), StorageError::AclNotFoundError) => {
+            Acl::default()
+        }
+        Err(error) => return Err(error.change_context(AclError::StorageError)),
+    };
+
+    Ok(Effective {
+        resource,
+        acl,
+        inherited: asset_id.is_some(),
+    })
+}
+
+/// Makes sure the caller holds `permission` on the account or asset. Callers holding it only
+/// through a grant have the value taken out, `amount`, counted against their debit limit.
+pub async fn check(
+    app_state: &AppState,
+    principal: &Principal,
+    user_id: &str,
+    account_id: &str,
+    asset_id: Option<&str>,
+    permission: Permission,
+    amount: u64,
+) -> SResult<Option<GrantCharge>, AclError> {
+    if principal.is_admin() {
+        return Ok(None);
+    }
+
+    let (account, _) = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(AclError::StorageError)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(AclError::NotFound)?
+        .get_account(account_id)
+        .await
+        .change_context(AclError::NotFound)?;
+
+    let effective = effective(app_state, user_id, account_id, asset_id).await?;
+    let mode = effective.acl.mode;
+
+    // the first class the caller falls in decides, as with an inode
+    let bits = if principal.acts_for(user_id) {
+        mode.owner
+    } else if principal.operates(&account.token_manager_id) {
+        mode.group
+    } else {
+        mode.other
+    };
+
+    if bits & permission.bit() != 0 {
+        return Ok(None);
+    }
+
+    let Some(caller_id) = principal.user_id() else {
+        return Err(report!(AclError::Denied));
+    };
+
+    // suspended users lose their grants along with their own resources
+    principal
+        .acts_for(caller_id)
+        .then_some(())
+        .ok_or(AclError::Denied)?;
+
+    let ua_addr = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(AclError::StorageError)?
+        .get_user(caller_id)
+        .await
+        .change_context(AclError::Denied)?
+        .ua_addr;
+
+    let now = unix_timestamp();
+    effective
+        .acl
+        .grants
+        .get(&ua_addr)
+        .filter(|grant| grant.permissions & permission.bit() != 0)
+        .filter(|grant| grant.expires_at.is_none_or(|at| at > now))
+        .ok_or(AclError::Denied)?;
+
+    if !matches!(permission, Permission::Debit | Permission::Transfer) {
+        return Ok(None);
+    }
+
+    app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(AclError::StorageError)?
+        .charge_grant(&effective.resource, &ua_addr, amount)
+        .await
+        .change_context(AclError::LimitExceeded)?;
+
+    Ok(Some(GrantCharge {
+        resource: effective.resource,
+        ua_addr,
+        amount,
+    }))
+}
+
+pub async fn refund(app_state: &AppState, charge: Option<GrantCharge>) -> SResult<(), AclError> {
+    let Some(charge) = charge else {
+        return Ok(());
+    };
+
+    app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(AclError::StorageError)?
+        .refund_grant(&charge.resource, &charge.ua_addr, charge.amount)
+        .await
+        .change_context(AclError::StorageError)
+}
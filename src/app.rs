@@ -6,18 +6,62 @@ use crate::error::{ConfigurationError, SResult};
 use crate::logging::prelude::*;
 use crate::state::AppState;
 
+mod approvals;
+mod audit;
+mod auth;
+mod docs;
+mod intents;
+mod namespace;
+mod pagination;
+mod policies;
+mod proofs;
+mod roles;
+mod sagas;
+mod swaps;
 mod token_managers;
 mod users;
+mod workflows;
+
+pub use docs::openapi;
 
 pub fn router() -> SResult<axum::Router<AppState>, ConfigurationError> {
     let router = axum::Router::new()
+        .nest("/v1/auth", auth::router()?)
         .nest("/v1/users", users::router()?)
         .nest("/v1/token_managers", token_managers::router()?)
+        .nest("/v1/proofs", proofs::router()?)
+        .nest("/v1/workflows", workflows::router()?)
+        .nest("/v1/intents", intents::router()?)
+        .nest("/v1/namespace", namespace::router()?)
+        .nest("/v1/sagas", sagas::router()?)
+        .nest("/v1/swaps", swaps::router()?)
+        .nest("/v1/roles", roles::router()?)
+        .nest("/v1/policies", policies::router()?)
+        .nest("/v1/approvals", approvals::router()?)
+        .nest("/v1/audit", audit::router()?)
+        .merge(docs::router()?)
         .route("/health", get(|| async { "Health is Good!" }));
 
     Ok(router)
 }
 
+/// Puts the authentication layers in front of the routes, they need the state to look up keys.
+/// Mutations are recorded in the audit trail on their way through, also when authentication
+/// turns them away.
+pub fn with_auth(router: axum::Router<AppState>, app_state: AppState) -> axum::Router {
+    router
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::auth::authenticate,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::audit::record,
+        ))
+        .layer(axum::middleware::from_fn(crate::request::assign_id))
+        .with_state(app_state)
+}
+
 pub async fn start_server(
     router: axum::Router,
     listener: TcpListener,
@@ -0,0 +1,185 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError, MultisigError};
+use crate::multisig;
+use crate::state::AppState;
+use crate::storage::types::Approval;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", get(list_approvals))
+        .route("/:approval_id", get(get_approval))
+        .route("/:approval_id/approve", post(approve))
+        .route("/:approval_id/reject", post(reject));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(list_approvals, get_approval, approve, reject))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "approvals",
+    params(types::ListApprovalsQuery),
+    responses((status = OK, body = Vec<types::ApprovalResponse>)),
+)]
+async fn list_approvals(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<types::ListApprovalsQuery>,
+) -> Result<Json<Vec<types::ApprovalResponse>>, ApiError> {
+    let ua_addr = signer(&app_state, &principal).await?;
+
+    let approvals = app_state
+        .storage
+        .get_multisig_interface()
+        .await
+        .change_context(ApiError::FetchApprovalError)
+        .map_err(log_convert)?
+        .list_approvals()
+        .await
+        .change_context(ApiError::FetchApprovalError)
+        .map_err(log_convert)?;
+
+    let mut output = Vec::new();
+
+    for (approval_id, approval) in approvals {
+        if !is_visible(&principal, ua_addr.as_deref(), &approval) {
+            continue;
+        }
+
+        let approval = multisig::refresh(&app_state, &approval_id, approval)
+            .await
+            .change_context(ApiError::FetchApprovalError)
+            .map_err(log_convert)?;
+
+        if query.status.is_some_and(|status| status != approval.status) {
+            continue;
+        }
+
+        output.push((approval_id, approval).into());
+    }
+
+    Ok(Json(output))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{approval_id}",
+    tag = "approvals",
+    responses((status = OK, body = types::ApprovalResponse)),
+)]
+async fn get_approval(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(approval_id): Path<String>,
+) -> Result<Json<types::ApprovalResponse>, ApiError> {
+    let ua_addr = signer(&app_state, &principal).await?;
+
+    let approval = multisig::get(&app_state, &approval_id)
+        .await
+        .change_context(ApiError::FetchApprovalError)
+        .map_err(log_convert)?;
+
+    is_visible(&principal, ua_addr.as_deref(), &approval)
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    Ok(Json((approval_id, approval).into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{approval_id}/approve",
+    tag = "approvals",
+    responses((status = OK, body = types::ApprovalResponse)),
+)]
+async fn approve(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(approval_id): Path<String>,
+) -> Result<Json<types::ApprovalResponse>, ApiError> {
+    let ua_addr = signer(&app_state, &principal)
+        .await?
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let approval = multisig::approve(&app_state, &approval_id, &ua_addr)
+        .await
+        .map_err(convert)?;
+
+    Ok(Json((approval_id, approval).into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{approval_id}/reject",
+    tag = "approvals",
+    responses((status = OK, body = types::ApprovalResponse)),
+)]
+async fn reject(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(approval_id): Path<String>,
+) -> Result<Json<types::ApprovalResponse>, ApiError> {
+    let ua_addr = signer(&app_state, &principal)
+        .await?
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let approval = multisig::reject(&app_state, &approval_id, &ua_addr)
+        .await
+        .map_err(convert)?;
+
+    Ok(Json((approval_id, approval).into()))
+}
+
+/// Universal address signers are designated by, for callers acting as a user.
+async fn signer(app_state: &AppState, principal: &Principal) -> Result<Option<String>, ApiError> {
+    let Some(user_id) = principal
+        .user_id()
+        .filter(|user_id| principal.acts_for(user_id))
+    else {
+        return Ok(None);
+    };
+
+    let user = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::FetchApprovalError)
+        .map_err(log_convert)?
+        .get_user(user_id)
+        .await
+        .change_context(ApiError::FetchApprovalError)
+        .map_err(log_convert)?;
+
+    Ok(Some(user.ua_addr))
+}
+
+fn is_visible(principal: &Principal, ua_addr: Option<&str>, approval: &Approval) -> bool {
+    principal.is_auditor()
+        || principal.acts_for(&approval.user_id)
+        || ua_addr.is_some_and(|ua_addr| approval.signers.iter().any(|signer| signer == ua_addr))
+}
+
+/// Tells apart votes the approval can't take in its current state from actual failures.
+fn convert(error: error_stack::Report<MultisigError>) -> ApiError {
+    let context = match error.current_context() {
+        MultisigError::NotFound => ApiError::FetchApprovalError,
+        MultisigError::NotSigner => ApiError::ForbiddenError,
+        MultisigError::NotPending | MultisigError::AlreadyVoted | MultisigError::Busy => {
+            ApiError::ApprovalStateError
+        }
+        _ => ApiError::VoteApprovalError,
+    };
+
+    log_convert(error.change_context(context))
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::storage::types::{Approval, ApprovalAction, ApprovalStatus};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListApprovalsQuery {
+    pub status: Option<ApprovalStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApprovalResponse {
+    pub approval_id: String,
+    pub user_id: String,
+    pub account_id: String,
+    pub action: ApprovalAction,
+    pub requested_by: Option<String>,
+    pub signers: Vec<String>,
+    pub required: u32,
+    pub approvals: Vec<String>,
+    pub rejections: Vec<String>,
+    pub status: ApprovalStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub decided_at: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl From<(String, Approval)> for ApprovalResponse {
+    fn from((approval_id, approval): (String, Approval)) -> Self {
+        Self {
+            approval_id,
+            user_id: approval.user_id,
+            account_id: approval.account_id,
+            action: approval.action,
+            requested_by: approval.requested_by,
+            signers: approval.signers,
+            required: approval.required,
+            approvals: approval.approvals,
+            rejections: approval.rejections,
+            status: approval.status,
+            created_at: approval.created_at,
+            expires_at: approval.expires_at,
+            decided_at: approval.decided_at,
+            last_error: approval.last_error,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::hash_chain;
+use crate::state::AppState;
+use crate::storage::types::AuditRecord;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", get(list_records))
+        .route("/verify", get(verify_records));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(list_records, verify_records))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "audit",
+    params(types::ListAuditQuery),
+    responses((status = OK, body = Vec<AuditRecord>)),
+)]
+async fn list_records(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<types::ListAuditQuery>,
+) -> Result<Json<Vec<AuditRecord>>, ApiError> {
+    principal
+        .is_auditor()
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let records = app_state
+        .audit
+        .list_records()
+        .await
+        .change_context(ApiError::FetchAuditError)
+        .map_err(log_convert)?;
+
+    let output = records
+        .into_iter()
+        .filter(|record| {
+            let entry = &record.entry;
+
+            query
+                .principal
+                .as_ref()
+                .is_none_or(|principal| entry.principal.as_ref() == Some(principal))
+                && query
+                    .route
+                    .as_ref()
+                    .is_none_or(|route| &entry.route == route)
+                && query
+                    .method
+                    .as_ref()
+                    .is_none_or(|method| entry.method.eq_ignore_ascii_case(method))
+                && query
+                    .entity_id
+                    .as_ref()
+                    .is_none_or(|id| entry.entity_ids.values().any(|value| value == id))
+                && query.outcome.is_none_or(|outcome| entry.outcome == outcome)
+                && query.since.is_none_or(|since| record.recorded_at >= since)
+                && query.until.is_none_or(|until| record.recorded_at < until)
+        })
+        .collect();
+
+    Ok(Json(output))
+}
+
+#[utoipa::path(
+    get,
+    path = "/verify",
+    tag = "audit",
+    responses((status = OK, body = types::VerifyAuditResponse)),
+)]
+async fn verify_records(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<types::VerifyAuditResponse>, ApiError> {
+    principal
+        .is_auditor()
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let records = app_state
+        .audit
+        .list_records()
+        .await
+        .change_context(ApiError::FetchAuditError)
+        .map_err(log_convert)?;
+
+    let violation = hash_chain::verify(&records).err();
+
+    Ok(Json(types::VerifyAuditResponse {
+        valid: violation.is_none(),
+        length: records.len() as u64,
+        head_hash: hash_chain::head(&records),
+        violation,
+    }))
+}
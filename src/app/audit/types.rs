@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::hash_chain::ChainViolation;
+use crate::storage::types::AuditOutcome;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListAuditQuery {
+    /// Subject of the principal, e.g. `users/<user_id>`.
+    pub principal: Option<String>,
+    pub route: Option<String>,
+    pub method: Option<String>,
+    /// Records touching the entity with this id.
+    pub entity_id: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerifyAuditResponse {
+    pub valid: bool,
+    pub length: u64,
+    pub head_hash: String,
+    pub violation: Option<ChainViolation>,
+}
@@ -0,0 +1,182 @@
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+use rand::RngCore;
+
+use crate::auth::{self, Principal, Subject};
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::state::AppState;
+use crate::storage::types::{Challenge, Session};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/challenge", post(challenge))
+        .route("/login", post(login))
+        .route("/logout", post(logout))
+        .route("/session", get(session));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(challenge, login, logout, session))]
+pub struct ApiDoc;
+
+fn login_message(challenge_id: &str, challenge: &Challenge) -> String {
+    format!(
+        "finternet-login\n{challenge_id}\n{}\n{}\n{}",
+        challenge.subject, challenge.nonce, challenge.expires_at
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/challenge",
+    tag = "auth",
+    security(()),
+    responses((status = OK, body = types::ChallengeResponse)),
+)]
+async fn challenge(
+    State(app_state): State<AppState>,
+    Json(req): Json<types::ChallengeRequest>,
+) -> Result<Json<types::ChallengeResponse>, ApiError> {
+    let subject = Subject::parse(&req.subject).ok_or(ApiError::ChallengeError)?;
+
+    // only subjects with a registered key can ever answer the challenge
+    auth::public_key(&app_state, &subject)
+        .await
+        .map_err(|_| ApiError::ChallengeError)?;
+
+    let mut nonce = [0_u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let challenge = Challenge {
+        subject: subject.to_string(),
+        nonce: hex::encode(nonce),
+        expires_at: unix_timestamp() + app_state.config.auth.challenge_ttl_secs,
+    };
+
+    let challenge_id = app_state
+        .storage
+        .get_session_interface()
+        .await
+        .change_context(ApiError::ChallengeError)
+        .map_err(log_convert)?
+        .create_challenge(challenge.clone())
+        .await
+        .change_context(ApiError::ChallengeError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::ChallengeResponse {
+        message: login_message(&challenge_id, &challenge),
+        challenge_id,
+        expires_at: challenge.expires_at,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/login",
+    tag = "auth",
+    security(()),
+    responses((status = OK, body = types::LoginResponse)),
+)]
+async fn login(
+    State(app_state): State<AppState>,
+    Json(req): Json<types::LoginRequest>,
+) -> Result<Json<types::LoginResponse>, ApiError> {
+    let session_interface = app_state
+        .storage
+        .get_session_interface()
+        .await
+        .change_context(ApiError::LoginError)
+        .map_err(log_convert)?;
+
+    let challenge = session_interface
+        .take_challenge(&req.challenge_id)
+        .await
+        .change_context(ApiError::LoginError)
+        .map_err(log_convert)?;
+
+    let now = unix_timestamp();
+    let subject = Subject::parse(&challenge.subject)
+        .filter(|_| challenge.expires_at > now)
+        .ok_or(ApiError::LoginError)?;
+
+    let public_key = auth::public_key(&app_state, &subject)
+        .await
+        .map_err(|_| ApiError::LoginError)?;
+
+    auth::verify(
+        &public_key,
+        &req.signature,
+        &login_message(&req.challenge_id, &challenge),
+    )
+    .then_some(())
+    .ok_or(ApiError::LoginError)?;
+
+    let session = Session {
+        subject: challenge.subject,
+        created_at: now,
+        expires_at: now + app_state.config.auth.session_ttl_secs,
+    };
+
+    let session_id = session_interface
+        .create_session(session.clone())
+        .await
+        .change_context(ApiError::LoginError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::LoginResponse {
+        token: app_state.session_key.token(&session_id, session.expires_at),
+        subject: session.subject,
+        expires_at: session.expires_at,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/logout",
+    tag = "auth",
+    responses((status = OK, body = types::SessionResponse)),
+)]
+async fn logout(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<types::SessionResponse>, ApiError> {
+    let token = auth::bearer(&headers).ok_or(ApiError::InvalidSessionError)?;
+    let (session_id, subject) = auth::session(&app_state, token).await?;
+
+    app_state
+        .storage
+        .get_session_interface()
+        .await
+        .change_context(ApiError::LogoutError)
+        .map_err(log_convert)?
+        .delete_session(&session_id)
+        .await
+        .change_context(ApiError::LogoutError)
+        .map_err(log_convert)?;
+
+    let principal = Principal {
+        roles: auth::roles(&app_state, &subject).await?,
+        subject: Some(subject),
+    };
+
+    Ok(Json(principal.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/session",
+    tag = "auth",
+    responses((status = OK, body = types::SessionResponse)),
+)]
+async fn session(Extension(principal): Extension<Principal>) -> Json<types::SessionResponse> {
+    Json(principal.into())
+}
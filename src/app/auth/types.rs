@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::auth::Principal;
+use crate::storage::types::Role;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChallengeRequest {
+    /// Principal logging in, as `users/<id>` or `token_managers/<id>`.
+    pub subject: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChallengeResponse {
+    pub challenge_id: String,
+    /// Message to sign with the registered key of the subject.
+    pub message: String,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    pub challenge_id: String,
+    /// Hex encoded ed25519 signature of the challenge message.
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoginResponse {
+    pub token: String,
+    pub subject: String,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub subject: Option<String>,
+    pub admin: bool,
+    pub roles: Vec<Role>,
+}
+
+impl From<Principal> for SessionResponse {
+    fn from(value: Principal) -> Self {
+        Self {
+            subject: value.subject.as_ref().map(ToString::to_string),
+            admin: value.is_admin(),
+            roles: value.roles,
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use axum::http::header;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Json;
+use utoipa::openapi::path::{Operation, ParameterBuilder, ParameterIn, PathItem};
+use utoipa::openapi::request_body::RequestBodyBuilder;
+use utoipa::openapi::schema::{ObjectBuilder, Type};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme};
+use utoipa::openapi::{ContentBuilder, Ref, RefOr, Required, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+
+use crate::auth::{NONCE_HEADER, SIGNATURE_HEADER, SIGNER_HEADER, TIMESTAMP_HEADER};
+use crate::error::{ConfigurationError, ErrorResponse};
+use crate::state::AppState;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/docs", get(docs_page))
+        .route("/docs/swagger-ui.css", get(swagger_ui_css))
+        .route("/docs/swagger-ui-bundle.js", get(swagger_ui_bundle));
+
+    Ok(router)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Finternet App API",
+        description = "Ledger backend of the Finternet, holding the accounts and assets of users with their token managers."
+    ),
+    nest(
+        (path = "/v1/auth", api = super::auth::ApiDoc),
+        (path = "/v1/users", api = super::users::ApiDoc),
+        (path = "/v1/token_managers", api = super::token_managers::ApiDoc),
+        (path = "/v1/proofs", api = super::proofs::ApiDoc),
+        (path = "/v1/workflows", api = super::workflows::ApiDoc),
+        (path = "/v1/intents", api = super::intents::ApiDoc),
+        (path = "/v1/namespace", api = super::namespace::ApiDoc),
+        (path = "/v1/sagas", api = super::sagas::ApiDoc),
+        (path = "/v1/swaps", api = super::swaps::ApiDoc),
+        (path = "/v1/roles", api = super::roles::ApiDoc),
+        (path = "/v1/policies", api = super::policies::ApiDoc),
+        (path = "/v1/approvals", api = super::approvals::ApiDoc),
+        (path = "/v1/audit", api = super::audit::ApiDoc),
+    ),
+    components(schemas(ErrorResponse)),
+    modifiers(&PrefixParameters, &Authentication, &Errors, &EmptyBodies),
+    security(("session" = []), ("signature" = [])),
+)]
+struct ApiDoc;
+
+/// Specification of the routes, generated from the handlers and the types they take and answer.
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    // the package declares no license, which would otherwise show up as an empty one
+    openapi.info.license = None;
+
+    openapi
+}
+
+/// Ways requests authenticate, as checked by [`crate::auth::authenticate`].
+struct Authentication;
+
+impl Modify for Authentication {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "session",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+        components.add_security_scheme(
+            "signature",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                SIGNATURE_HEADER.to_string(),
+                format!(
+                    "Hex encoded ed25519 signature of the request, sent along with the \
+                     `{SIGNER_HEADER}`, `{TIMESTAMP_HEADER}` and `{NONCE_HEADER}` headers."
+                ),
+            ))),
+        );
+    }
+}
+
+/// Every route answers failures with the same envelope, see [`crate::error::ApiError`].
+struct Errors;
+
+impl Modify for Errors {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.responses.insert(
+            "Error".to_string(),
+            RefOr::T(
+                ResponseBuilder::new()
+                    .description("Request failed, the status tells the kind of failure.")
+                    .content(
+                        "application/json",
+                        ContentBuilder::new()
+                            .schema(Some(Ref::from_schema_name("ErrorResponse")))
+                            .build(),
+                    )
+                    .build(),
+            ),
+        );
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in operations(item) {
+                operation.responses.responses.insert(
+                    "default".to_string(),
+                    RefOr::Ref(Ref::from_response_name("Error")),
+                );
+            }
+        }
+    }
+}
+
+/// Actions like approving or cancelling are posted without a body. Their operations say so
+/// explicitly, rather than leaving clients to guess whether a body was forgotten in the spec.
+struct EmptyBodies;
+
+impl Modify for EmptyBodies {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            for operation in [&mut item.post, &mut item.put, &mut item.patch]
+                .into_iter()
+                .flatten()
+            {
+                operation.request_body.get_or_insert_with(|| {
+                    RequestBodyBuilder::new()
+                        .description(Some("Takes no body."))
+                        .required(Some(Required::False))
+                        .build()
+                });
+            }
+        }
+    }
+}
+
+/// Handlers of nested routers only describe the parameters of their own part of the route, those
+/// of the prefixes they are nested under are added here.
+struct PrefixParameters;
+
+impl Modify for PrefixParameters {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let names = path
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+                .collect::<Vec<_>>();
+
+            for operation in operations(item) {
+                let parameters = operation.parameters.get_or_insert_with(Vec::new);
+
+                let missing = names
+                    .iter()
+                    .filter(|name| !parameters.iter().any(|parameter| parameter.name == **name))
+                    .map(|name| {
+                        ParameterBuilder::new()
+                            .name(*name)
+                            .parameter_in(ParameterIn::Path)
+                            .required(Required::True)
+                            .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+                            .build()
+                    })
+                    .collect::<Vec<_>>();
+
+                parameters.splice(0..0, missing);
+            }
+        }
+    }
+}
+
+fn operations(item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.patch,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi())
+}
+
+/// Interactive documentation of the routes, rendering the specification at `/openapi.json`.
+async fn docs_page() -> Html<&'static str> {
+    Html(DOCS_PAGE)
+}
+
+/// Swagger UI 5.17.14, vendored under `docs/swagger-ui` so that the page doesn't run whatever a
+/// CDN happens to serve.
+const SWAGGER_UI_CSS: &str = include_str!("../../docs/swagger-ui/swagger-ui.css");
+const SWAGGER_UI_BUNDLE: &str = include_str!("../../docs/swagger-ui/swagger-ui-bundle.js");
+
+async fn swagger_ui_css() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/css; charset=utf-8")],
+        SWAGGER_UI_CSS,
+    )
+}
+
+async fn swagger_ui_bundle() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/javascript; charset=utf-8")],
+        SWAGGER_UI_BUNDLE,
+    )
+}
+
+const DOCS_PAGE: &str = r##"<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8" />
+    <title>Finternet App API</title>
+    <link rel="stylesheet" href="/docs/swagger-ui.css" />
+  </head>
+  <body>
+    <div id="docs"></div>
+    <script src="/docs/swagger-ui-bundle.js"></script>
+    <script>
+      window.onload = () => {
+        window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#docs" });
+      };
+    </script>
+  </body>
+</html>
+"##;
@@ -0,0 +1,376 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{
+    ledger_convert, log_convert, ApiError, ConfigurationError, SResult, StorageError,
+};
+use crate::ledger;
+use crate::state::AppState;
+use crate::storage::types::{Descriptor, Money, Purpose};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(intend))
+        .route("/:descriptor", get(get_descriptor).delete(done))
+        .route("/:descriptor/view", get(view))
+        .route("/:descriptor/debit", post(debit))
+        .route("/:descriptor/credit", post(credit))
+        .route("/:descriptor/transfer", post(transfer));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(intend, get_descriptor, done, view, debit, credit, transfer))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "intents",
+    responses((status = OK, body = types::DescriptorResponse)),
+)]
+async fn intend(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<types::IntendRequest>,
+) -> Result<Json<types::DescriptorResponse>, ApiError> {
+    (!req.purposes.is_empty())
+        .then_some(())
+        .ok_or(ApiError::DescriptorPurposeError)?;
+
+    let (user_id, account_id) = req
+        .account
+        .resolve(&app_state)
+        .await
+        .change_context(ApiError::ResolvePathError)
+        .map_err(log_convert)?;
+
+    principal.ensure_user(&user_id)?;
+
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::IntendError)
+        .map_err(log_convert)?
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::IntendError)
+        .map_err(log_convert)?
+        .get_account(&account_id)
+        .await
+        .change_context(ApiError::IntendError)
+        .map_err(log_convert)?;
+
+    let settings = &app_state.config.intents;
+    let ttl = req
+        .ttl_secs
+        .unwrap_or(settings.default_ttl_secs)
+        .min(settings.max_ttl_secs);
+
+    let now = unix_timestamp();
+    let descriptor = Descriptor {
+        user_id,
+        account_id,
+        purposes: req.purposes,
+        created_at: now,
+        expires_at: now + ttl,
+    };
+
+    let descriptor_interface = app_state
+        .storage
+        .get_descriptor_interface()
+        .await
+        .change_context(ApiError::IntendError)
+        .map_err(log_convert)?;
+
+    let descriptor_id = descriptor_interface
+        .create_descriptor(descriptor.clone())
+        .await
+        .change_context(ApiError::IntendError)
+        .map_err(log_convert)?;
+
+    // only one descriptor at a time may take value out of an account
+    if descriptor.allows(Purpose::Debit) {
+        let acquired = app_state
+            .storage
+            .get_lock_interface()
+            .await
+            .change_context(ApiError::IntendError)
+            .map_err(log_convert)?
+            .acquire(
+                &ledger::account_resource(&descriptor.user_id, &descriptor.account_id),
+                &descriptor_id,
+                descriptor.expires_at,
+            )
+            .await;
+
+        if acquired.is_err() {
+            descriptor_interface
+                .delete_descriptor(&descriptor_id)
+                .await
+                .change_context(ApiError::IntendError)
+                .map_err(log_convert)?;
+
+            return Err(ApiError::AccountLockedError);
+        }
+    }
+
+    Ok(Json((descriptor_id, descriptor).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{descriptor_id}",
+    tag = "intents",
+    responses((status = OK, body = types::DescriptorResponse)),
+)]
+async fn get_descriptor(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(descriptor_id): Path<String>,
+) -> Result<Json<types::DescriptorResponse>, ApiError> {
+    let descriptor = active_descriptor(&app_state, &descriptor_id, None).await?;
+
+    (principal.is_auditor() || principal.acts_for(&descriptor.user_id))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    Ok(Json((descriptor_id, descriptor).into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{descriptor_id}",
+    tag = "intents",
+    responses((status = OK, body = types::DescriptorResponse)),
+)]
+async fn done(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(descriptor_id): Path<String>,
+) -> Result<Json<types::DescriptorResponse>, ApiError> {
+    let descriptor_interface = app_state
+        .storage
+        .get_descriptor_interface()
+        .await
+        .change_context(ApiError::DescriptorNotFoundError)
+        .map_err(log_convert)?;
+
+    // expired descriptors can still be closed, releasing whatever they hold
+    let descriptor = descriptor_interface
+        .get_descriptor(&descriptor_id)
+        .await
+        .change_context(ApiError::DescriptorNotFoundError)
+        .map_err(log_convert)?;
+
+    principal.ensure_user(&descriptor.user_id)?;
+
+    revoke(&app_state, &descriptor_id, &descriptor)
+        .await
+        .change_context(ApiError::DescriptorOperationError)
+        .map_err(log_convert)?;
+
+    Ok(Json((descriptor_id, descriptor).into()))
+}
+
+/// Drops the descriptor, releasing the lock it holds on the account.
+pub(crate) async fn revoke(
+    app_state: &AppState,
+    descriptor_id: &str,
+    descriptor: &Descriptor,
+) -> SResult<(), StorageError> {
+    app_state
+        .storage
+        .get_descriptor_interface()
+        .await?
+        .delete_descriptor(descriptor_id)
+        .await?;
+
+    app_state
+        .storage
+        .get_lock_interface()
+        .await?
+        .release(
+            &ledger::account_resource(&descriptor.user_id, &descriptor.account_id),
+            descriptor_id,
+        )
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/{descriptor_id}/view",
+    tag = "intents",
+    responses((status = OK, body = Money)),
+)]
+async fn view(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(descriptor_id): Path<String>,
+) -> Result<Json<Money>, ApiError> {
+    let descriptor = active_descriptor(&app_state, &descriptor_id, Some(Purpose::Read)).await?;
+    principal.ensure_user(&descriptor.user_id)?;
+
+    let balance = ledger::balance(&app_state, &descriptor.user_id, &descriptor.account_id)
+        .await
+        .change_context(ApiError::DescriptorOperationError)
+        .map_err(log_convert)?;
+
+    Ok(Json(balance))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{descriptor_id}/debit",
+    tag = "intents",
+    responses((status = OK, body = types::DebitResponse)),
+)]
+async fn debit(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(descriptor_id): Path<String>,
+    Json(req): Json<types::AmountRequest>,
+) -> Result<Json<types::DebitResponse>, ApiError> {
+    let descriptor = active_descriptor(&app_state, &descriptor_id, Some(Purpose::Debit)).await?;
+    principal.ensure_user(&descriptor.user_id)?;
+
+    ledger::debit(
+        &app_state,
+        &descriptor.user_id,
+        &descriptor.account_id,
+        req.amount,
+        Some(&descriptor_id),
+    )
+    .await
+    .map_err(|error| ledger_convert(error, ApiError::DescriptorOperationError))?;
+
+    Ok(Json(types::DebitResponse {
+        descriptor: descriptor_id,
+        amount: req.amount,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{descriptor_id}/credit",
+    tag = "intents",
+    responses((status = OK, body = types::CreditResponse)),
+)]
+async fn credit(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(descriptor_id): Path<String>,
+    Json(req): Json<types::AmountRequest>,
+) -> Result<Json<types::CreditResponse>, ApiError> {
+    let descriptor = active_descriptor(&app_state, &descriptor_id, Some(Purpose::Credit)).await?;
+
+    // crediting creates cash, which only the token manager behind the account does
+    let token_manager_id =
+        ledger::token_manager_of(&app_state, &descriptor.user_id, &descriptor.account_id)
+            .await
+            .change_context(ApiError::DescriptorOperationError)
+            .map_err(log_convert)?;
+
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let asset_id = ledger::credit(
+        &app_state,
+        &descriptor.user_id,
+        &descriptor.account_id,
+        req.amount,
+    )
+    .await
+    .map_err(|error| ledger_convert(error, ApiError::DescriptorOperationError))?;
+
+    Ok(Json(types::CreditResponse {
+        descriptor: descriptor_id,
+        amount: req.amount,
+        asset_id,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{descriptor_id}/transfer",
+    tag = "intents",
+    responses((status = OK, body = types::TransferResponse)),
+)]
+async fn transfer(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(descriptor_id): Path<String>,
+    Json(req): Json<types::TransferRequest>,
+) -> Result<Json<types::TransferResponse>, ApiError> {
+    let from = active_descriptor(&app_state, &descriptor_id, Some(Purpose::Debit)).await?;
+    principal.ensure_user(&from.user_id)?;
+
+    // the credit descriptor of the peer is the capability to pay into its account
+    let to = active_descriptor(&app_state, &req.to, Some(Purpose::Credit)).await?;
+
+    let peer = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::DescriptorOperationError)
+        .map_err(log_convert)?
+        .get_user(&to.user_id)
+        .await
+        .change_context(ApiError::DescriptorOperationError)
+        .map_err(log_convert)?;
+
+    let peer_asset_ids = ledger::transfer(
+        &app_state,
+        &from.user_id,
+        &from.account_id,
+        &peer.ua_addr,
+        &to.account_id,
+        req.amount,
+        Some(&descriptor_id),
+    )
+    .await
+    .map_err(|error| ledger_convert(error, ApiError::DescriptorOperationError))?;
+
+    Ok(Json(types::TransferResponse {
+        from: descriptor_id,
+        to: req.to,
+        amount: req.amount,
+        peer_asset_ids,
+    }))
+}
+
+/// Fetches the descriptor, making sure it hasn't expired and was opened for `purpose`.
+async fn active_descriptor(
+    app_state: &AppState,
+    descriptor_id: &str,
+    purpose: Option<Purpose>,
+) -> Result<Descriptor, ApiError> {
+    let descriptor = app_state
+        .storage
+        .get_descriptor_interface()
+        .await
+        .change_context(ApiError::DescriptorNotFoundError)
+        .map_err(log_convert)?
+        .get_descriptor(descriptor_id)
+        .await
+        .change_context(ApiError::DescriptorNotFoundError)
+        .map_err(log_convert)?;
+
+    (descriptor.expires_at > unix_timestamp())
+        .then_some(())
+        .ok_or(ApiError::DescriptorNotFoundError)?;
+
+    purpose
+        .is_none_or(|purpose| descriptor.allows(purpose))
+        .then_some(())
+        .ok_or(ApiError::DescriptorPurposeError)?;
+
+    Ok(descriptor)
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::storage::types::{AccountRef, Descriptor, Purpose};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IntendRequest {
+    /// Either `user_id` and `account_id`, or a `path` in the namespace.
+    #[serde(flatten)]
+    pub account: AccountRef,
+    pub purposes: Vec<Purpose>,
+    /// Lifetime of the descriptor, capped by the configured maximum.
+    pub ttl_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DescriptorResponse {
+    pub descriptor: String,
+    pub account_id: String,
+    pub purposes: Vec<Purpose>,
+    pub expires_at: u64,
+}
+
+impl From<(String, Descriptor)> for DescriptorResponse {
+    fn from((descriptor, value): (String, Descriptor)) -> Self {
+        Self {
+            descriptor,
+            account_id: value.account_id,
+            purposes: value.purposes,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AmountRequest {
+    pub amount: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TransferRequest {
+    /// Descriptor of the receiving account, must be opened with `O_CREDIT`.
+    pub to: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DebitResponse {
+    pub descriptor: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreditResponse {
+    pub descriptor: String,
+    pub amount: u64,
+    pub asset_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransferResponse {
+    pub from: String,
+    pub to: String,
+    pub amount: u64,
+    pub peer_asset_ids: Vec<String>,
+}
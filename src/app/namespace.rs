@@ -0,0 +1,148 @@
+use axum::extract::{Path, State};
+use axum::routing::{delete, get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::namespace;
+use crate::state::AppState;
+use crate::storage::types::Mount;
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/mounts", post(create_mount).get(list_mounts))
+        .route("/mounts/*path", delete(delete_mount))
+        .route("/resolve/*path", get(resolve_path));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(create_mount, list_mounts, delete_mount, resolve_path))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "/mounts",
+    tag = "namespace",
+    responses((status = OK, body = types::MountResponse)),
+)]
+async fn create_mount(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<types::CreateMountRequest>,
+) -> Result<Json<types::MountResponse>, ApiError> {
+    // a mount routes paths of the shared namespace, only platform admins may claim one
+    principal.ensure_admin()?;
+
+    let path = namespace::normalize(&req.path).map_err(|_| ApiError::InvalidPathError)?;
+
+    app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::CreateMountError)
+        .map_err(log_convert)?
+        .get_token_manager(&req.token_manager_id)
+        .await
+        .change_context(ApiError::CreateMountError)
+        .map_err(log_convert)?;
+
+    let mount = Mount {
+        token_manager_id: req.token_manager_id,
+        created_at: unix_timestamp(),
+    };
+
+    app_state
+        .storage
+        .get_mount_interface()
+        .await
+        .change_context(ApiError::CreateMountError)
+        .map_err(log_convert)?
+        .mount(&path, mount.clone())
+        .await
+        .change_context(ApiError::CreateMountError)
+        .map_err(log_convert)?;
+
+    Ok(Json((path, mount).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/mounts",
+    tag = "namespace",
+    responses((status = OK, body = Vec<types::MountResponse>)),
+)]
+async fn list_mounts(
+    State(app_state): State<AppState>,
+) -> Result<Json<Vec<types::MountResponse>>, ApiError> {
+    let mut mounts = app_state
+        .storage
+        .get_mount_interface()
+        .await
+        .change_context(ApiError::FetchMountError)
+        .map_err(log_convert)?
+        .list_mounts()
+        .await
+        .change_context(ApiError::FetchMountError)
+        .map_err(log_convert)?;
+
+    mounts.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(Json(mounts.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/mounts/{path}",
+    tag = "namespace",
+    responses((status = OK, body = types::MountResponse)),
+)]
+async fn delete_mount(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(path): Path<String>,
+) -> Result<Json<types::MountResponse>, ApiError> {
+    principal.ensure_admin()?;
+
+    let path = namespace::normalize(&format!("/{}", path.trim_start_matches('/')))
+        .map_err(|_| ApiError::InvalidPathError)?;
+
+    let mount = app_state
+        .storage
+        .get_mount_interface()
+        .await
+        .change_context(ApiError::DeleteMountError)
+        .map_err(log_convert)?
+        .unmount(&path)
+        .await
+        .change_context(ApiError::DeleteMountError)
+        .map_err(log_convert)?;
+
+    Ok(Json((path, mount).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/resolve/{path}",
+    tag = "namespace",
+    responses((status = OK, body = types::ResolvedPathResponse)),
+)]
+async fn resolve_path(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(path): Path<String>,
+) -> Result<Json<types::ResolvedPathResponse>, ApiError> {
+    principal.ensure_authenticated()?;
+
+    let resolved = namespace::resolve(&app_state, &format!("/{}", path.trim_start_matches('/')))
+        .await
+        .change_context(ApiError::ResolvePathError)
+        .map_err(log_convert)?;
+
+    Ok(Json(resolved.into()))
+}
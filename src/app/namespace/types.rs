@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::namespace::ResolvedPath;
+use crate::storage::types::Mount;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateMountRequest {
+    pub path: String,
+    pub token_manager_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MountResponse {
+    pub path: String,
+    pub token_manager_id: String,
+    pub created_at: u64,
+}
+
+impl From<(String, Mount)> for MountResponse {
+    fn from((path, mount): (String, Mount)) -> Self {
+        Self {
+            path,
+            token_manager_id: mount.token_manager_id,
+            created_at: mount.created_at,
+        }
+    }
+}
+
+/// Account a path leads to, addressed by its UA address rather than the internal user id.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResolvedPathResponse {
+    pub mount: String,
+    pub token_manager_id: String,
+    pub ua_addr: String,
+    pub account_id: String,
+    pub asset_id: Option<String>,
+}
+
+impl From<ResolvedPath> for ResolvedPathResponse {
+    fn from(resolved: ResolvedPath) -> Self {
+        Self {
+            mount: resolved.mount,
+            token_manager_id: resolved.token_manager_id,
+            ua_addr: resolved.ua_addr,
+            account_id: resolved.account_id,
+            asset_id: resolved.asset_id,
+        }
+    }
+}
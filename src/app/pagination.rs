@@ -0,0 +1,44 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::error::ApiError;
+use crate::storage::types::{PageQuery, SortOrder};
+use crate::validation::Violations;
+
+/// Entries of a page unless the request asks for another number of them.
+const DEFAULT_LIMIT: usize = 50;
+
+/// Most entries a single page holds.
+const MAX_LIMIT: usize = 200;
+
+/// Position and size of the page taken by every list route, next to the filters of the listing.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PageParams {
+    /// `next_cursor` of the previous page, the listing starts over without one.
+    pub cursor: Option<String>,
+    /// Number of entries of the page, 50 unless given and at most 200.
+    pub limit: Option<usize>,
+    /// Direction of the listing along its sort key, ascending unless given.
+    pub order: Option<SortOrder>,
+}
+
+impl PageParams {
+    pub fn query<S>(self, sort: S) -> Result<PageQuery<S>, ApiError> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+
+        let mut violations = Violations::default();
+        violations.check(
+            (1..=MAX_LIMIT).contains(&limit),
+            "limit",
+            "must be between 1 and 200",
+        );
+        violations.into_result()?;
+
+        Ok(PageQuery {
+            cursor: self.cursor,
+            limit,
+            sort,
+            order: self.order.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,307 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::state::AppState;
+use crate::storage::types::{Policy, PolicyRule, PolicyTarget};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(create_policy).get(list_policies))
+        .route("/:policy_id", get(get_policy).delete(delete_policy));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(create_policy, list_policies, get_policy, delete_policy))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "policies",
+    responses((status = OK, body = types::PolicyResponse)),
+)]
+async fn create_policy(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<types::CreatePolicyRequest>,
+) -> Result<Json<types::PolicyResponse>, ApiError> {
+    (!req.name.trim().is_empty() && is_valid(&req.rule))
+        .then_some(())
+        .ok_or(ApiError::InvalidPolicyError)?;
+
+    let target = match req.target {
+        types::PolicyTargetRequest::SupportedAsset {
+            token_manager_id,
+            supported_asset_id,
+        } => {
+            principal.ensure_token_manager(&token_manager_id)?;
+
+            let supported_asset = app_state
+                .storage
+                .get_token_manager_interface()
+                .await
+                .change_context(ApiError::CreatePolicyError)
+                .map_err(log_convert)?
+                .get_supported_asset_interface(&token_manager_id)
+                .await
+                .change_context(ApiError::InvalidPolicyError)
+                .map_err(log_convert)?
+                .get_supported_asset(&supported_asset_id)
+                .await
+                .change_context(ApiError::InvalidPolicyError)
+                .map_err(log_convert)?;
+
+            PolicyTarget::SupportedAsset {
+                token_manager_id,
+                supported_asset_id,
+                asset_type: supported_asset.item.asset_type,
+            }
+        }
+        types::PolicyTargetRequest::Account {
+            user_id,
+            account_id,
+        } => {
+            let token_manager_id = account_token_manager(&app_state, &user_id, &account_id)
+                .await
+                .map_err(|_| ApiError::InvalidPolicyError)?;
+
+            principal.ensure_token_manager(&token_manager_id)?;
+
+            PolicyTarget::Account {
+                user_id,
+                account_id,
+            }
+        }
+    };
+
+    let policy = Policy {
+        name: req.name,
+        target,
+        rule: req.rule,
+        operations: req.operations,
+        created_by: principal.subject.as_ref().map(ToString::to_string),
+        created_at: unix_timestamp(),
+    };
+
+    let policy_id = app_state
+        .storage
+        .get_policy_interface()
+        .await
+        .change_context(ApiError::CreatePolicyError)
+        .map_err(log_convert)?
+        .create_policy(policy.clone())
+        .await
+        .change_context(ApiError::CreatePolicyError)
+        .map_err(log_convert)?;
+
+    Ok(Json((policy_id, policy).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "policies",
+    params(types::ListPoliciesQuery),
+    responses((status = OK, body = Vec<types::PolicyResponse>)),
+)]
+async fn list_policies(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<types::ListPoliciesQuery>,
+) -> Result<Json<Vec<types::PolicyResponse>>, ApiError> {
+    let policies = app_state
+        .storage
+        .get_policy_interface()
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?
+        .list_policies()
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?;
+
+    let mut output = Vec::new();
+
+    for (policy_id, policy) in policies {
+        let matches = match &policy.target {
+            PolicyTarget::SupportedAsset {
+                token_manager_id, ..
+            } => {
+                query.user_id.is_none()
+                    && query.account_id.is_none()
+                    && query
+                        .token_manager_id
+                        .as_ref()
+                        .is_none_or(|id| id == token_manager_id)
+            }
+            PolicyTarget::Account {
+                user_id,
+                account_id,
+            } => {
+                query.token_manager_id.is_none()
+                    && query.user_id.as_ref().is_none_or(|id| id == user_id)
+                    && query.account_id.as_ref().is_none_or(|id| id == account_id)
+            }
+        };
+
+        if matches && is_visible(&app_state, &principal, &policy).await {
+            output.push((policy_id, policy).into());
+        }
+    }
+
+    Ok(Json(output))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{policy_id}",
+    tag = "policies",
+    responses((status = OK, body = types::PolicyResponse)),
+)]
+async fn get_policy(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(policy_id): Path<String>,
+) -> Result<Json<types::PolicyResponse>, ApiError> {
+    let policy = app_state
+        .storage
+        .get_policy_interface()
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?
+        .get_policy(&policy_id)
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?;
+
+    is_visible(&app_state, &principal, &policy)
+        .await
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    Ok(Json((policy_id, policy).into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{policy_id}",
+    tag = "policies",
+    responses((status = OK, body = types::PolicyResponse)),
+)]
+async fn delete_policy(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(policy_id): Path<String>,
+) -> Result<Json<types::PolicyResponse>, ApiError> {
+    let policy_interface = app_state
+        .storage
+        .get_policy_interface()
+        .await
+        .change_context(ApiError::DeletePolicyError)
+        .map_err(log_convert)?;
+
+    let policy = policy_interface
+        .get_policy(&policy_id)
+        .await
+        .change_context(ApiError::DeletePolicyError)
+        .map_err(log_convert)?;
+
+    principal.ensure_token_manager(&target_token_manager(&app_state, &policy.target).await?)?;
+
+    let policy = policy_interface
+        .delete_policy(&policy_id)
+        .await
+        .change_context(ApiError::DeletePolicyError)
+        .map_err(log_convert)?;
+
+    Ok(Json((policy_id, policy).into()))
+}
+
+fn is_valid(rule: &PolicyRule) -> bool {
+    match rule {
+        PolicyRule::MaxAmount { .. } => true,
+        PolicyRule::Jurisdictions { allowed } => !allowed.is_empty(),
+        PolicyRule::BusinessHours {
+            start_hour,
+            end_hour,
+            days,
+            utc_offset_minutes,
+        } => {
+            *start_hour < 24
+                && *end_hour < 24
+                && start_hour != end_hour
+                && !days.is_empty()
+                && utc_offset_minutes.abs() <= 14 * 60
+        }
+        PolicyRule::Velocity {
+            window_secs,
+            max_amount,
+            max_count,
+        } => *window_secs > 0 && (max_amount.is_some() || max_count.is_some()),
+    }
+}
+
+/// Policies are visible to whoever may act for the token manager behind them, and to the holder
+/// of the account they are attached to.
+async fn is_visible(app_state: &AppState, principal: &Principal, policy: &Policy) -> bool {
+    if principal.is_auditor() {
+        return true;
+    }
+
+    if let PolicyTarget::Account { user_id, .. } = &policy.target {
+        if principal.acts_for(user_id) {
+            return true;
+        }
+    }
+
+    target_token_manager(app_state, &policy.target)
+        .await
+        .is_ok_and(|token_manager_id| principal.operates(&token_manager_id))
+}
+
+async fn target_token_manager(
+    app_state: &AppState,
+    target: &PolicyTarget,
+) -> Result<String, ApiError> {
+    match target {
+        PolicyTarget::SupportedAsset {
+            token_manager_id, ..
+        } => Ok(token_manager_id.clone()),
+        PolicyTarget::Account {
+            user_id,
+            account_id,
+        } => account_token_manager(app_state, user_id, account_id).await,
+    }
+}
+
+async fn account_token_manager(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+) -> Result<String, ApiError> {
+    let (account, _) = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?
+        .get_account(account_id)
+        .await
+        .change_context(ApiError::FetchPolicyError)
+        .map_err(log_convert)?;
+
+    Ok(account.token_manager_id)
+}
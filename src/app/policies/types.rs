@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::storage::types::{Policy, PolicyOperation, PolicyRule, PolicyTarget};
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyTargetRequest {
+    SupportedAsset {
+        token_manager_id: String,
+        supported_asset_id: String,
+    },
+    Account {
+        user_id: String,
+        account_id: String,
+    },
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    pub target: PolicyTargetRequest,
+    #[serde(flatten)]
+    pub rule: PolicyRule,
+    /// Operations to evaluate the policy before, all of them when left out.
+    #[serde(default)]
+    pub operations: Vec<PolicyOperation>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListPoliciesQuery {
+    pub token_manager_id: Option<String>,
+    pub user_id: Option<String>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PolicyResponse {
+    pub policy_id: String,
+    pub name: String,
+    pub target: PolicyTarget,
+    #[serde(flatten)]
+    pub rule: PolicyRule,
+    pub operations: Vec<PolicyOperation>,
+    pub created_by: Option<String>,
+    pub created_at: u64,
+}
+
+impl From<(String, Policy)> for PolicyResponse {
+    fn from((policy_id, policy): (String, Policy)) -> Self {
+        Self {
+            policy_id,
+            name: policy.name,
+            target: policy.target,
+            rule: policy.rule,
+            operations: policy.operations,
+            created_by: policy.created_by,
+            created_at: policy.created_at,
+        }
+    }
+}
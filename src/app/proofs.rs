@@ -0,0 +1,178 @@
+use axum::extract::{Path, Query, State};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::hash_chain;
+use crate::merkle::SignedRoot;
+use crate::proofs::audit_holdings;
+use crate::state::AppState;
+use crate::storage::types::{Event, ProofEntry};
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", get(list_proofs))
+        .route("/verify", get(verify_proofs))
+        .route("/merkle/root", get(get_merkle_root))
+        .route("/:transaction_id", get(get_proof));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(list_proofs, get_proof, verify_proofs, get_merkle_root))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "proofs",
+    params(types::ListProofsQuery),
+    responses((status = OK, body = Vec<ProofEntry>)),
+)]
+async fn list_proofs(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<types::ListProofsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let proofs = app_state
+        .proofs
+        .list_proofs()
+        .await
+        .change_context(ApiError::FetchProofError)
+        .map_err(log_convert)?;
+
+    let proofs = proofs
+        .into_iter()
+        .filter(|entry| can_read(&principal, &entry.event));
+
+    let output: Vec<ProofEntry> = match query.asset_id {
+        Some(asset_id) => proofs
+            .filter(|entry| {
+                entry.event.asset_id == asset_id
+                    || entry
+                        .event
+                        .counterparty
+                        .as_ref()
+                        .is_some_and(|counterparty| counterparty.asset_id == asset_id)
+            })
+            .collect(),
+        None => proofs.collect(),
+    };
+
+    Ok(Json(output))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{transaction_id}",
+    tag = "proofs",
+    responses((status = OK, body = types::ProofResponse)),
+)]
+async fn get_proof(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(transaction_id): Path<String>,
+) -> Result<Json<types::ProofResponse>, ApiError> {
+    let proof = app_state
+        .proofs
+        .get_proof(&transaction_id)
+        .await
+        .change_context(ApiError::FetchProofError)
+        .map_err(log_convert)?;
+
+    can_read(&principal, &proof.event)
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let chain = app_state
+        .proofs
+        .list_proofs()
+        .await
+        .change_context(ApiError::FetchProofError)
+        .map_err(log_convert)?;
+
+    let prefix = chain
+        .get(..=proof.index as usize)
+        .unwrap_or(chain.as_slice());
+
+    Ok(Json(types::ProofResponse {
+        chain_valid: hash_chain::verify(prefix).is_ok(),
+        proof,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/verify",
+    tag = "proofs",
+    responses((status = OK, body = types::VerifyChainResponse)),
+)]
+async fn verify_proofs(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<types::VerifyChainResponse>, ApiError> {
+    // the audit covers the holdings of every account, which only auditors get to see
+    principal
+        .is_auditor()
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let chain = app_state
+        .proofs
+        .list_proofs()
+        .await
+        .change_context(ApiError::VerifyProofError)
+        .map_err(log_convert)?;
+
+    let holdings = crate::ledger::holdings(&app_state)
+        .await
+        .change_context(ApiError::VerifyProofError)
+        .map_err(log_convert)?;
+
+    // the ledger starts out empty after a restart, only what this process recorded is replayed
+    let audited_from = app_state.proofs.session_start();
+    let violation = hash_chain::verify(&chain).err();
+    let mismatched_assets = audit_holdings(
+        chain.get(audited_from as usize..).unwrap_or_default(),
+        &holdings,
+    );
+
+    Ok(Json(types::VerifyChainResponse {
+        valid: violation.is_none() && mismatched_assets.is_empty(),
+        length: chain.len() as u64,
+        head_hash: hash_chain::head(&chain),
+        violation,
+        audited_from,
+        mismatched_assets,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/merkle/root",
+    tag = "proofs",
+    responses((status = OK, body = SignedRoot)),
+)]
+async fn get_merkle_root(State(app_state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let signed_root = app_state
+        .merkle
+        .latest_root()
+        .await
+        .ok_or(ApiError::MerkleRootUnavailableError)?;
+
+    Ok(Json(signed_root))
+}
+
+/// Entries are visible to the user whose asset changed, the token manager that issued it and
+/// auditors.
+fn can_read(principal: &Principal, event: &Event) -> bool {
+    principal.is_auditor()
+        || principal.acts_for(&event.user_id)
+        || principal.operates(&event.token_manager_id)
+}
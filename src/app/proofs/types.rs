@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::hash_chain::ChainViolation;
+use crate::storage::types::ProofEntry;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListProofsQuery {
+    pub asset_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProofResponse {
+    pub proof: ProofEntry,
+    /// Whether the chain from genesis up to and including this entry verifies.
+    pub chain_valid: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerifyChainResponse {
+    pub valid: bool,
+    pub length: u64,
+    pub head_hash: String,
+    pub violation: Option<ChainViolation>,
+    /// Index of the first entry replayed against the stored holdings, the entries recorded
+    /// before the last restart are only checked for their links.
+    pub audited_from: u64,
+    /// Assets whose stored state no longer matches the state committed to by the chain.
+    pub mismatched_assets: Vec<String>,
+}
@@ -0,0 +1,157 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{delete, get};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::{self, Principal, Subject};
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::state::AppState;
+use crate::storage::types::{Role, RoleGrant};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", get(list_roles).post(grant_role))
+        .route("/:grant_id", delete(revoke_role));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(list_roles, grant_role, revoke_role))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "roles",
+    params(types::ListRolesQuery),
+    responses((status = OK, body = Vec<types::RoleGrantResponse>)),
+)]
+async fn list_roles(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Query(query): Query<types::ListRolesQuery>,
+) -> Result<Json<Vec<types::RoleGrantResponse>>, ApiError> {
+    principal
+        .is_auditor()
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let grants = app_state
+        .storage
+        .get_role_interface()
+        .await
+        .change_context(ApiError::ListRolesError)
+        .map_err(log_convert)?
+        .list_roles(query.subject.as_deref())
+        .await
+        .change_context(ApiError::ListRolesError)
+        .map_err(log_convert)?;
+
+    Ok(Json(grants.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "roles",
+    responses((status = OK, body = types::RoleGrantResponse)),
+)]
+async fn grant_role(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<types::GrantRoleRequest>,
+) -> Result<Json<types::RoleGrantResponse>, ApiError> {
+    principal.ensure_admin()?;
+
+    let subject = Subject::parse(&req.subject).ok_or(ApiError::InvalidRoleError)?;
+
+    // roles can only be held by principals that are able to authenticate
+    auth::public_key(&app_state, &subject)
+        .await
+        .map_err(|_| ApiError::InvalidRoleError)?;
+
+    if let Role::TokenManagerOperator { token_manager_id } = &req.role {
+        app_state
+            .storage
+            .get_token_manager_interface()
+            .await
+            .change_context(ApiError::GrantRoleError)
+            .map_err(log_convert)?
+            .get_token_manager(token_manager_id)
+            .await
+            .change_context(ApiError::InvalidRoleError)
+            .map_err(log_convert)?;
+    }
+
+    let grant = RoleGrant {
+        subject: subject.to_string(),
+        role: req.role,
+        granted_by: principal.subject.as_ref().map(ToString::to_string),
+        granted_at: unix_timestamp(),
+    };
+
+    let grant_id = app_state
+        .storage
+        .get_role_interface()
+        .await
+        .change_context(ApiError::GrantRoleError)
+        .map_err(log_convert)?
+        .grant_role(grant.clone())
+        .await
+        .change_context(ApiError::GrantRoleError)
+        .map_err(log_convert)?;
+
+    Ok(Json((grant_id, grant).into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{grant_id}",
+    tag = "roles",
+    responses((status = OK, body = types::RoleGrantResponse)),
+)]
+async fn revoke_role(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(grant_id): Path<String>,
+) -> Result<Json<types::RoleGrantResponse>, ApiError> {
+    principal.ensure_admin()?;
+
+    let role_interface = app_state
+        .storage
+        .get_role_interface()
+        .await
+        .change_context(ApiError::RevokeRoleError)
+        .map_err(log_convert)?;
+
+    let (_, grant) = role_interface
+        .list_roles(None)
+        .await
+        .change_context(ApiError::RevokeRoleError)
+        .map_err(log_convert)?
+        .into_iter()
+        .find(|(id, _)| id == &grant_id)
+        .ok_or(ApiError::RevokeRoleError)?;
+
+    // admins can't lock themselves out, another admin has to take the role away
+    let own = principal
+        .subject
+        .as_ref()
+        .is_some_and(|subject| subject.to_string() == grant.subject);
+
+    (!(own && grant.role == Role::PlatformAdmin))
+        .then_some(())
+        .ok_or(ApiError::InvalidRoleError)?;
+
+    let grant = role_interface
+        .revoke_role(&grant_id)
+        .await
+        .change_context(ApiError::RevokeRoleError)
+        .map_err(log_convert)?;
+
+    Ok(Json((grant_id, grant).into()))
+}
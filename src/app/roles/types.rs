@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::storage::types::{Role, RoleGrant};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListRolesQuery {
+    /// Only list the grants held by this principal.
+    pub subject: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GrantRoleRequest {
+    /// Principal to grant the role to, as `users/<id>` or `token_managers/<id>`.
+    pub subject: String,
+    #[serde(flatten)]
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoleGrantResponse {
+    pub grant_id: String,
+    pub subject: String,
+    #[serde(flatten)]
+    pub role: Role,
+    pub granted_by: Option<String>,
+    pub granted_at: u64,
+}
+
+impl From<(String, RoleGrant)> for RoleGrantResponse {
+    fn from((grant_id, grant): (String, RoleGrant)) -> Self {
+        Self {
+            grant_id,
+            subject: grant.subject,
+            role: grant.role,
+            granted_by: grant.granted_by,
+            granted_at: grant.granted_at,
+        }
+    }
+}
@@ -0,0 +1,460 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::{Principal, Subject};
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::namespace;
+use crate::state::AppState;
+use crate::storage::types::{
+    AccountRef, SagaDefinition, SagaInstance, SagaStatus, SagaStep, StepState,
+};
+use crate::utils::unix_timestamp;
+use crate::{ledger, saga};
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(create_saga).get(list_sagas))
+        .route("/:saga_id", get(get_saga))
+        .route(
+            "/:saga_id/instances",
+            post(start_instance).get(list_instances),
+        )
+        .route("/:saga_id/instances/:instance_id", get(get_instance))
+        .route(
+            "/:saga_id/instances/:instance_id/retry",
+            post(retry_instance),
+        )
+        .route(
+            "/:saga_id/instances/:instance_id/cancel",
+            post(cancel_instance),
+        );
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    create_saga,
+    list_sagas,
+    get_saga,
+    start_instance,
+    list_instances,
+    get_instance,
+    retry_instance,
+    cancel_instance,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "sagas",
+    responses((status = OK, body = types::SagaResponse)),
+)]
+async fn create_saga(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<types::CreateSagaRequest>,
+) -> Result<Json<types::SagaResponse>, ApiError> {
+    let valid = !req.steps.is_empty()
+        && req.steps.iter().all(|step| match step {
+            SagaStep::Debit { account, amount } | SagaStep::Credit { account, amount } => {
+                *amount > 0 && is_valid_ref(account)
+            }
+            SagaStep::Transfer { from, to, amount } => {
+                *amount > 0 && is_valid_ref(from) && is_valid_ref(to)
+            }
+        });
+
+    valid.then_some(()).ok_or(ApiError::InvalidSagaError)?;
+
+    authorize(&app_state, &principal, &req.steps).await?;
+
+    let saga = SagaDefinition {
+        name: req.name,
+        owner: principal.subject.as_ref().map(ToString::to_string),
+        steps: req.steps,
+        created_at: unix_timestamp(),
+    };
+
+    let saga_id = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::CreateSagaError)
+        .map_err(log_convert)?
+        .create_saga(saga.clone())
+        .await
+        .change_context(ApiError::CreateSagaError)
+        .map_err(log_convert)?;
+
+    Ok(Json((saga_id, saga).into()))
+}
+
+/// Value may only be taken out of accounts of the caller, and only minted into accounts the
+/// caller issues cash for as their token manager. Paths are resolved anew on every call, so a
+/// saga is checked again whenever it is started.
+async fn authorize(
+    app_state: &AppState,
+    principal: &Principal,
+    steps: &[SagaStep],
+) -> Result<(), ApiError> {
+    for step in steps {
+        let (account, mints) = match step {
+            SagaStep::Debit { account, .. } => (account, false),
+            SagaStep::Credit { account, .. } => (account, true),
+            SagaStep::Transfer { from, .. } => (from, false),
+        };
+
+        let (user_id, account_id) = account
+            .resolve(app_state)
+            .await
+            .change_context(ApiError::InvalidSagaError)
+            .map_err(log_convert)?;
+
+        if mints {
+            let token_manager_id = ledger::token_manager_of(app_state, &user_id, &account_id)
+                .await
+                .change_context(ApiError::InvalidSagaError)
+                .map_err(log_convert)?;
+
+            principal.ensure_token_manager(&token_manager_id)?;
+        } else {
+            principal.ensure_user(&user_id)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn is_valid_ref(account: &AccountRef) -> bool {
+    match account {
+        AccountRef::Path { path } => namespace::segments(path).is_ok(),
+        AccountRef::Ids { .. } => true,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "sagas",
+    responses((status = OK, body = Vec<types::SagaResponse>)),
+)]
+async fn list_sagas(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<Vec<types::SagaResponse>>, ApiError> {
+    let sagas = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?
+        .list_sagas()
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?;
+
+    Ok(Json(
+        sagas
+            .into_iter()
+            .filter(|(_, saga)| principal.is_auditor() || is_owner(&principal, saga))
+            .map(Into::into)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{saga_id}",
+    tag = "sagas",
+    responses((status = OK, body = types::SagaResponse)),
+)]
+async fn get_saga(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(saga_id): Path<String>,
+) -> Result<Json<types::SagaResponse>, ApiError> {
+    let saga = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?
+        .get_saga(&saga_id)
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?;
+
+    (principal.is_auditor() || is_owner(&principal, &saga))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    Ok(Json((saga_id, saga).into()))
+}
+
+/// Sagas are only visible to, and driven by, the user or token manager that defined them.
+fn is_owner(principal: &Principal, saga: &SagaDefinition) -> bool {
+    principal.is_admin()
+        || match saga.owner.as_deref().and_then(Subject::parse) {
+            Some(Subject::User(user_id)) => principal.acts_for(&user_id),
+            Some(Subject::TokenManager(token_manager_id)) => principal.operates(&token_manager_id),
+            None => false,
+        }
+}
+
+async fn ensure_owner(
+    app_state: &AppState,
+    principal: &Principal,
+    saga_id: &str,
+) -> Result<(), ApiError> {
+    let saga = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?
+        .get_saga(saga_id)
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?;
+
+    is_owner(principal, &saga)
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)
+}
+
+#[utoipa::path(
+    post,
+    path = "/{saga_id}/instances",
+    tag = "sagas",
+    responses((status = OK, body = types::SagaInstanceResponse)),
+)]
+async fn start_instance(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(saga_id): Path<String>,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    let saga_interface = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::StartSagaError)
+        .map_err(log_convert)?;
+
+    let saga = saga_interface
+        .get_saga(&saga_id)
+        .await
+        .change_context(ApiError::StartSagaError)
+        .map_err(log_convert)?;
+
+    is_owner(&principal, &saga)
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    // access to the accounts may have been revoked, or a path remounted, since the definition
+    authorize(&app_state, &principal, &saga.steps).await?;
+
+    let instance = saga::new_instance(saga_id, &saga);
+
+    let instance_id = saga_interface
+        .create_instance(instance.clone())
+        .await
+        .change_context(ApiError::StartSagaError)
+        .map_err(log_convert)?;
+
+    Ok(Json((instance_id, instance).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{saga_id}/instances",
+    tag = "sagas",
+    responses((status = OK, body = Vec<types::SagaInstanceResponse>)),
+)]
+async fn list_instances(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(saga_id): Path<String>,
+) -> Result<Json<Vec<types::SagaInstanceResponse>>, ApiError> {
+    if !principal.is_auditor() {
+        ensure_owner(&app_state, &principal, &saga_id).await?;
+    }
+
+    let mut instances = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?
+        .list_instances(&saga_id)
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?;
+
+    instances.sort_by_key(|(_, instance)| instance.created_at);
+
+    Ok(Json(instances.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{saga_id}/instances/{instance_id}",
+    tag = "sagas",
+    responses((status = OK, body = types::SagaInstanceResponse)),
+)]
+async fn get_instance(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((saga_id, instance_id)): Path<(String, String)>,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    if !principal.is_auditor() {
+        ensure_owner(&app_state, &principal, &saga_id).await?;
+    }
+
+    let instance = fetch_instance(&app_state, &saga_id, &instance_id).await?;
+
+    Ok(Json((instance_id, instance).into()))
+}
+
+/// Restarts an instance that was rolled back, or resumes compensation that failed midway.
+#[utoipa::path(
+    post,
+    path = "/{saga_id}/instances/{instance_id}/retry",
+    tag = "sagas",
+    responses((status = OK, body = types::SagaInstanceResponse)),
+)]
+async fn retry_instance(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((saga_id, instance_id)): Path<(String, String)>,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    ensure_owner(&app_state, &principal, &saga_id).await?;
+
+    let guard = enter(&app_state, &instance_id).await?;
+    let output = restart(&app_state, &saga_id, &instance_id).await;
+    leave(&app_state, &instance_id, &guard).await?;
+
+    output
+}
+
+async fn restart(
+    app_state: &AppState,
+    saga_id: &str,
+    instance_id: &str,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    let mut instance = fetch_instance(app_state, saga_id, instance_id).await?;
+
+    match instance.status {
+        SagaStatus::Compensated | SagaStatus::Cancelled => {
+            instance.status = SagaStatus::Running;
+            instance.steps = vec![StepState::default(); instance.steps.len()];
+            instance.cancel_requested = false;
+            instance.attempts += 1;
+            instance.last_error = None;
+        }
+        SagaStatus::Failed => instance.status = SagaStatus::Compensating,
+        SagaStatus::Running | SagaStatus::Compensating | SagaStatus::Completed => {
+            return Err(ApiError::SagaStateError)
+        }
+    }
+
+    save_instance(app_state, instance_id, instance).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/{saga_id}/instances/{instance_id}/cancel",
+    tag = "sagas",
+    responses((status = OK, body = types::SagaInstanceResponse)),
+)]
+async fn cancel_instance(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((saga_id, instance_id)): Path<(String, String)>,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    ensure_owner(&app_state, &principal, &saga_id).await?;
+
+    let guard = enter(&app_state, &instance_id).await?;
+    let output = request_cancel(&app_state, &saga_id, &instance_id).await;
+    leave(&app_state, &instance_id, &guard).await?;
+
+    output
+}
+
+async fn request_cancel(
+    app_state: &AppState,
+    saga_id: &str,
+    instance_id: &str,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    let mut instance = fetch_instance(app_state, saga_id, instance_id).await?;
+
+    (instance.status == SagaStatus::Running)
+        .then_some(())
+        .ok_or(ApiError::SagaStateError)?;
+
+    instance.cancel_requested = true;
+
+    save_instance(app_state, instance_id, instance).await
+}
+
+/// Takes the instance from the worker, which holds it while a step is in flight.
+async fn enter(app_state: &AppState, instance_id: &str) -> Result<String, ApiError> {
+    saga::enter(app_state, instance_id)
+        .await
+        .change_context(ApiError::SagaStateError)
+        .map_err(log_convert)
+}
+
+async fn leave(app_state: &AppState, instance_id: &str, guard: &str) -> Result<(), ApiError> {
+    saga::leave(app_state, instance_id, guard)
+        .await
+        .change_context(ApiError::UpdateSagaError)
+        .map_err(log_convert)
+}
+
+async fn fetch_instance(
+    app_state: &AppState,
+    saga_id: &str,
+    instance_id: &str,
+) -> Result<SagaInstance, ApiError> {
+    let instance = app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?
+        .get_instance(instance_id)
+        .await
+        .change_context(ApiError::FetchSagaError)
+        .map_err(log_convert)?;
+
+    (instance.saga_id == saga_id)
+        .then_some(instance)
+        .ok_or(ApiError::FetchSagaError)
+}
+
+async fn save_instance(
+    app_state: &AppState,
+    instance_id: &str,
+    mut instance: SagaInstance,
+) -> Result<Json<types::SagaInstanceResponse>, ApiError> {
+    instance.updated_at = unix_timestamp();
+
+    app_state
+        .storage
+        .get_saga_interface()
+        .await
+        .change_context(ApiError::UpdateSagaError)
+        .map_err(log_convert)?
+        .update_instance(instance_id, instance.clone())
+        .await
+        .change_context(ApiError::UpdateSagaError)
+        .map_err(log_convert)?;
+
+    Ok(Json((instance_id.to_string(), instance).into()))
+}
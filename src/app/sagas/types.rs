@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::storage::types::{SagaDefinition, SagaInstance, SagaStatus, SagaStep, StepState};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSagaRequest {
+    pub name: String,
+    pub steps: Vec<SagaStep>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SagaResponse {
+    pub saga_id: String,
+    pub name: String,
+    pub owner: Option<String>,
+    pub steps: Vec<SagaStep>,
+    pub created_at: u64,
+}
+
+impl From<(String, SagaDefinition)> for SagaResponse {
+    fn from((saga_id, saga): (String, SagaDefinition)) -> Self {
+        Self {
+            saga_id,
+            name: saga.name,
+            owner: saga.owner,
+            steps: saga.steps,
+            created_at: saga.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SagaInstanceResponse {
+    pub instance_id: String,
+    pub saga_id: String,
+    pub status: SagaStatus,
+    pub steps: Vec<StepState>,
+    pub cancel_requested: bool,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl From<(String, SagaInstance)> for SagaInstanceResponse {
+    fn from((instance_id, instance): (String, SagaInstance)) -> Self {
+        Self {
+            instance_id,
+            saga_id: instance.saga_id,
+            status: instance.status,
+            steps: instance.steps,
+            cancel_requested: instance.cancel_requested,
+            attempts: instance.attempts,
+            last_error: instance.last_error,
+            created_at: instance.created_at,
+            updated_at: instance.updated_at,
+        }
+    }
+}
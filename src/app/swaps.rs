@@ -0,0 +1,296 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError, LedgerError, SwapError};
+use crate::state::AppState;
+use crate::storage::types::{Swap, SwapLeg, SwapOffer, SwapStatus};
+use crate::swap::{self, Side};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(create_swap).get(list_swaps))
+        .route("/:swap_id", get(get_swap))
+        .route("/:swap_id/legs/:side/fund", post(fund_swap))
+        .route("/:swap_id/cancel", post(cancel_swap));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(create_swap, list_swaps, get_swap, fund_swap, cancel_swap),
+    components(schemas(Side))
+)]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "swaps",
+    responses((status = OK, body = types::SwapResponse)),
+)]
+async fn create_swap(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<types::CreateSwapRequest>,
+) -> Result<Json<types::SwapResponse>, ApiError> {
+    let maker = leg(&app_state, req.maker).await?;
+    let taker = leg(&app_state, req.taker).await?;
+
+    // swaps are offered by the maker, the taker agrees by funding its leg
+    principal.ensure_user(&maker.user_id)?;
+
+    (maker.user_id != taker.user_id)
+        .then_some(())
+        .ok_or(ApiError::InvalidSwapError)?;
+
+    let settings = &app_state.config.swaps;
+    let ttl = req
+        .ttl_secs
+        .unwrap_or(settings.default_ttl_secs)
+        .min(settings.max_ttl_secs);
+
+    let now = unix_timestamp();
+    let swap = Swap {
+        maker,
+        taker,
+        status: SwapStatus::Open,
+        created_at: now,
+        expires_at: now + ttl,
+        settled_at: None,
+        last_error: None,
+    };
+
+    let swap_id = app_state
+        .storage
+        .get_swap_interface()
+        .await
+        .change_context(ApiError::CreateSwapError)
+        .map_err(log_convert)?
+        .create_swap(swap.clone())
+        .await
+        .change_context(ApiError::CreateSwapError)
+        .map_err(log_convert)?;
+
+    Ok(Json((swap_id, swap).into()))
+}
+
+/// Resolves the accounts of the leg, which have to belong to the same user.
+async fn leg(app_state: &AppState, req: types::SwapLegRequest) -> Result<SwapLeg, ApiError> {
+    let valid = match &req.offer {
+        SwapOffer::Asset { asset_id } => !asset_id.is_empty(),
+        SwapOffer::Cash { amount } => *amount > 0,
+    };
+
+    valid.then_some(()).ok_or(ApiError::InvalidSwapError)?;
+
+    let (user_id, account_id) = req
+        .account
+        .resolve(app_state)
+        .await
+        .change_context(ApiError::ResolvePathError)
+        .map_err(log_convert)?;
+
+    let receive_account_id = match req.receive_into {
+        None => account_id.clone(),
+        Some(account) => {
+            let (receive_user_id, receive_account_id) = account
+                .resolve(app_state)
+                .await
+                .change_context(ApiError::ResolvePathError)
+                .map_err(log_convert)?;
+
+            (receive_user_id == user_id)
+                .then_some(())
+                .ok_or(ApiError::InvalidSwapError)?;
+
+            receive_account_id
+        }
+    };
+
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::CreateSwapError)
+        .map_err(log_convert)?;
+
+    let user = user_interface
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::CreateSwapError)
+        .map_err(log_convert)?;
+
+    let account_interface = user_interface
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::CreateSwapError)
+        .map_err(log_convert)?;
+
+    for account_id in [&account_id, &receive_account_id] {
+        account_interface
+            .get_account(account_id)
+            .await
+            .change_context(ApiError::CreateSwapError)
+            .map_err(log_convert)?;
+    }
+
+    Ok(SwapLeg {
+        user_id,
+        ua_addr: user.ua_addr,
+        account_id,
+        receive_account_id,
+        offer: req.offer,
+        funded: false,
+        asset_ids: Vec::new(),
+        delivered_asset_ids: Vec::new(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "swaps",
+    responses((status = OK, body = Vec<types::SwapResponse>)),
+)]
+async fn list_swaps(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<Vec<types::SwapResponse>>, ApiError> {
+    let swaps = app_state
+        .storage
+        .get_swap_interface()
+        .await
+        .change_context(ApiError::FetchSwapError)
+        .map_err(log_convert)?
+        .list_swaps()
+        .await
+        .change_context(ApiError::FetchSwapError)
+        .map_err(log_convert)?;
+
+    let mut output = Vec::with_capacity(swaps.len());
+
+    for (swap_id, swap) in swaps {
+        if !(principal.is_auditor() || is_party(&principal, &swap)) {
+            continue;
+        }
+
+        let swap = swap::refresh(&app_state, &swap_id, swap)
+            .await
+            .change_context(ApiError::FetchSwapError)
+            .map_err(log_convert)?;
+
+        output.push((swap_id, swap).into());
+    }
+
+    Ok(Json(output))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{swap_id}",
+    tag = "swaps",
+    responses((status = OK, body = types::SwapResponse)),
+)]
+async fn get_swap(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(swap_id): Path<String>,
+) -> Result<Json<types::SwapResponse>, ApiError> {
+    let swap = swap::get(&app_state, &swap_id)
+        .await
+        .change_context(ApiError::FetchSwapError)
+        .map_err(log_convert)?;
+
+    (principal.is_auditor() || is_party(&principal, &swap))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    Ok(Json((swap_id, swap).into()))
+}
+
+fn is_party(principal: &Principal, swap: &Swap) -> bool {
+    principal.acts_for(&swap.maker.user_id) || principal.acts_for(&swap.taker.user_id)
+}
+
+#[utoipa::path(
+    post,
+    path = "/{swap_id}/legs/{side}/fund",
+    tag = "swaps",
+    responses((status = OK, body = types::SwapResponse)),
+)]
+async fn fund_swap(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((swap_id, side)): Path<(String, Side)>,
+) -> Result<Json<types::SwapResponse>, ApiError> {
+    let swap = swap::get(&app_state, &swap_id)
+        .await
+        .change_context(ApiError::FetchSwapError)
+        .map_err(log_convert)?;
+
+    let leg = match side {
+        Side::Maker => &swap.maker,
+        Side::Taker => &swap.taker,
+    };
+
+    principal.ensure_user(&leg.user_id)?;
+
+    let swap = swap::fund(&app_state, &swap_id, side)
+        .await
+        .map_err(|error| convert(error, ApiError::FundSwapError))?;
+
+    Ok(Json((swap_id, swap).into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{swap_id}/cancel",
+    tag = "swaps",
+    responses((status = OK, body = types::SwapResponse)),
+)]
+async fn cancel_swap(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(swap_id): Path<String>,
+) -> Result<Json<types::SwapResponse>, ApiError> {
+    let swap = swap::get(&app_state, &swap_id)
+        .await
+        .change_context(ApiError::FetchSwapError)
+        .map_err(log_convert)?;
+
+    is_party(&principal, &swap)
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let swap = swap::cancel(&app_state, &swap_id)
+        .await
+        .map_err(|error| convert(error, ApiError::CancelSwapError))?;
+
+    Ok(Json((swap_id, swap).into()))
+}
+
+/// Tells apart requests the swap can't take in its current state from actual failures.
+fn convert(error: error_stack::Report<SwapError>, fallback: ApiError) -> ApiError {
+    let context = match error.current_context() {
+        SwapError::NotFound => ApiError::FetchSwapError,
+        SwapError::NotOpen | SwapError::AlreadyFunded | SwapError::Busy => ApiError::SwapStateError,
+        // the signers of the account have to approve moving this much out of it
+        _ if matches!(
+            error.downcast_ref::<LedgerError>(),
+            Some(LedgerError::ApprovalRequired)
+        ) =>
+        {
+            ApiError::ApprovalRequiredError
+        }
+        _ => fallback,
+    };
+
+    log_convert(error.change_context(context))
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::storage::types::{AccountRef, Swap, SwapLeg, SwapOffer, SwapStatus};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SwapLegRequest {
+    /// Account the offer is taken from, either through its ids or a `path` in the namespace.
+    #[serde(flatten)]
+    pub account: AccountRef,
+    /// Account receiving the other leg, defaults to the account the offer is taken from.
+    pub receive_into: Option<AccountRef>,
+    pub offer: SwapOffer,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSwapRequest {
+    pub maker: SwapLegRequest,
+    pub taker: SwapLegRequest,
+    /// Time both legs have to be funded in, capped by the configured maximum.
+    pub ttl_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SwapResponse {
+    pub swap_id: String,
+    pub maker: SwapLeg,
+    pub taker: SwapLeg,
+    pub status: SwapStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub settled_at: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl From<(String, Swap)> for SwapResponse {
+    fn from((swap_id, swap): (String, Swap)) -> Self {
+        Self {
+            swap_id,
+            maker: swap.maker,
+            taker: swap.taker,
+            status: swap.status,
+            created_at: swap.created_at,
+            expires_at: swap.expires_at,
+            settled_at: swap.settled_at,
+            last_error: swap.last_error,
+        }
+    }
+}
@@ -1,14 +1,24 @@
-use axum::extract::{Path, State};
+use std::collections::HashSet;
+
+use axum::extract::{Path, Query, State};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
-use axum::Json;
+use axum::{Extension, Json};
 use error_stack::ResultExt;
 
+use crate::app::pagination::PageParams;
+use crate::auth::{Principal, Subject};
 use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::ledger;
+use crate::logging::prelude::*;
 use crate::state::AppState;
+use crate::storage::types::{AssetInfo, Money, RetiredKey, SyncStatus, TokenManagerStatus};
+use crate::utils::unix_timestamp;
+use crate::validation::{ValidJson, Violations};
 
 mod supported_assets;
 mod types;
+mod webhooks;
 
 pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
     let router = axum::Router::new()
@@ -22,17 +32,47 @@ pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
         .nest(
             "/:token_manager_id/supported_assets",
             supported_assets::router()?,
-        );
+        )
+        .nest("/:token_manager_id/webhooks", webhooks::router()?)
+        .route("/:token_manager_id/sync", get(get_sync_status))
+        .route("/:token_manager_id/sync/resync", post(resync));
 
     Ok(router)
 }
 
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(
+        create_token_manager,
+        list_token_managers,
+        get_token_manager,
+        update_token_manager,
+        delete_token_manager,
+        get_sync_status,
+        resync
+    ),
+    nest(
+        (path = "/{token_manager_id}/supported_assets", api = supported_assets::ApiDoc),
+        (path = "/{token_manager_id}/webhooks", api = webhooks::ApiDoc),
+    ),
+)]
+pub struct ApiDoc;
+
 // async fn create_token_manager(State(app_state): State<AppState>, Json(req): Json<types::CreateTokenManagerRequest>) {}
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "token_managers",
+    responses((status = OK, body = types::CreateTokenManagerResponse)),
+)]
 async fn create_token_manager(
     State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Json(req): Json<types::CreateTokenManagerRequest>,
 ) -> Result<Json<types::CreateTokenManagerResponse>, ApiError> {
+    principal.ensure_admin()?;
+
     let token_manager_name = req.token_manager_name.clone();
 
     let token_manager_id = app_state
@@ -52,39 +92,330 @@ async fn create_token_manager(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "token_managers",
+    params(PageParams, types::ListTokenManagersQuery),
+    responses((status = OK, body = types::ListTokenManagersResponse)),
+)]
 async fn list_token_managers(
     State(app_state): State<AppState>,
-) -> Result<impl IntoResponse, ApiError> {
-    let token_managers = app_state
+    Query(page): Query<PageParams>,
+    Query(query): Query<types::ListTokenManagersQuery>,
+) -> Result<Json<types::ListTokenManagersResponse>, ApiError> {
+    let page = app_state
         .storage
         .get_token_manager_interface()
         .await
         .change_context(ApiError::ListTokenManagersError)
         .map_err(log_convert)?
-        .list_token_manager()
+        .query_token_managers(
+            &page.query(query.sort.unwrap_or_default())?,
+            &query.filter(),
+        )
         .await
         .change_context(ApiError::ListTokenManagersError)
         .map_err(log_convert)?;
 
-    Ok(Json(
-        token_managers
-            .into_iter()
-            .map(types::CreateTokenManagerResponse::from)
-            .collect::<Vec<_>>(),
+    Ok(Json(types::ListTokenManagersResponse {
+        items: page.items.into_iter().map(Into::into).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{token_manager_id}",
+    tag = "token_managers",
+    responses((status = OK, body = types::TokenManagerDetails)),
+)]
+async fn get_token_manager(
+    State(app_state): State<AppState>,
+    Path(token_manager_id): Path<String>,
+) -> Result<Json<types::TokenManagerDetails>, ApiError> {
+    details(
+        &app_state,
+        token_manager_id,
+        ApiError::FetchTokenManagerError,
     )
-    .into_response())
+    .await
+    .map(Json)
 }
 
-async fn get_token_manager(Path(_token_manager_id): Path<String>) -> impl IntoResponse {
-    ApiError::NotImplemented
+#[utoipa::path(
+    put,
+    path = "/{token_manager_id}",
+    tag = "token_managers",
+    request_body = types::UpdateTokenManagerRequest,
+    responses((status = OK, body = types::TokenManagerDetails)),
+)]
+async fn update_token_manager(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(token_manager_id): Path<String>,
+    ValidJson(req): ValidJson<types::UpdateTokenManagerRequest>,
+) -> Result<Json<types::TokenManagerDetails>, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let token_manager_interface = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::UpdateTokenManagerError)
+        .map_err(log_convert)?;
+
+    let mut token_manager = token_manager_interface
+        .get_token_manager(&token_manager_id)
+        .await
+        .change_context(ApiError::UpdateTokenManagerError)
+        .map_err(log_convert)?;
+
+    let rotated = match req.public_key {
+        Some(public_key) if !public_key.eq_ignore_ascii_case(&token_manager.public_key) => {
+            let mut violations = Violations::default();
+            violations.check(
+                !token_manager
+                    .previous_keys
+                    .iter()
+                    .any(|key| key.public_key.eq_ignore_ascii_case(&public_key)),
+                "public_key",
+                "must not be a key the token manager rotated away from",
+            );
+            violations.into_result()?;
+
+            let retired = std::mem::replace(&mut token_manager.public_key, public_key);
+            token_manager.previous_keys.push(RetiredKey {
+                public_key: retired,
+                retired_at: unix_timestamp(),
+            });
+
+            true
+        }
+        _ => false,
+    };
+
+    if let Some(token_manager_name) = req.token_manager_name {
+        token_manager.token_manager_name = token_manager_name;
+    }
+    if let Some(sync_url) = req.sync_url {
+        token_manager.sync_url = Some(sync_url);
+    }
+
+    token_manager_interface
+        .update_token_manager(&token_manager_id, token_manager)
+        .await
+        .change_context(ApiError::UpdateTokenManagerError)
+        .map_err(log_convert)?;
+
+    // sessions were opened by proving possession of the retired key
+    if rotated {
+        app_state
+            .storage
+            .get_session_interface()
+            .await
+            .change_context(ApiError::UpdateTokenManagerError)
+            .map_err(log_convert)?
+            .revoke_sessions(&Subject::TokenManager(token_manager_id.clone()).to_string())
+            .await
+            .change_context(ApiError::UpdateTokenManagerError)
+            .map_err(log_convert)?;
+    }
+
+    details(
+        &app_state,
+        token_manager_id,
+        ApiError::UpdateTokenManagerError,
+    )
+    .await
+    .map(Json)
 }
 
-async fn update_token_manager(Path(_token_manager_id): Path<String>) -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+/// Deactivates the token manager. Accounts it backs keep what they hold, and it keeps acting on
+/// them, but it takes no new accounts nor mints.
+#[utoipa::path(
+    delete,
+    path = "/{token_manager_id}",
+    tag = "token_managers",
+    responses((status = OK, body = types::TokenManagerDetails)),
+)]
+async fn delete_token_manager(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(token_manager_id): Path<String>,
+) -> Result<Json<types::TokenManagerDetails>, ApiError> {
+    principal.ensure_admin()?;
+
+    let token_manager_interface = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::DeactivateTokenManagerError)
+        .map_err(log_convert)?;
+
+    let token_manager = token_manager_interface
+        .get_token_manager(&token_manager_id)
+        .await
+        .change_context(ApiError::DeactivateTokenManagerError)
+        .map_err(log_convert)?;
+
+    (token_manager.status == TokenManagerStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::TokenManagerDeactivatedError)?;
+
+    token_manager_interface
+        .deactivate_token_manager(&token_manager_id, unix_timestamp())
+        .await
+        .change_context(ApiError::DeactivateTokenManagerError)
+        .map_err(log_convert)?;
+
+    info!("Deactivated token manager {token_manager_id}");
+
+    details(
+        &app_state,
+        token_manager_id,
+        ApiError::DeactivateTokenManagerError,
+    )
+    .await
+    .map(Json)
+}
+
+/// Token manager along with its supported assets and what its accounts hold.
+async fn details(
+    app_state: &AppState,
+    token_manager_id: String,
+    fallback: ApiError,
+) -> Result<types::TokenManagerDetails, ApiError> {
+    let token_manager_interface = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let token_manager = token_manager_interface
+        .get_token_manager(&token_manager_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let supported_assets = token_manager_interface
+        .get_supported_asset_interface(&token_manager_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .list_supported_assets()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .into_iter()
+        .map(|(supported_asset_id, asset)| types::SupportedAssetSummary {
+            supported_asset_id,
+            asset_type: asset.asset_type,
+            custody: asset.custody,
+            status: asset.status,
+        })
+        .collect();
+
+    let holdings = ledger::holdings(app_state)
+        .await
+        .change_context(fallback)
+        .map_err(log_convert)?
+        .into_iter()
+        .filter(|holding| holding.token_manager_id == token_manager_id)
+        .collect::<Vec<_>>();
+
+    let mut issuance = types::IssuanceStats {
+        holders: holdings
+            .iter()
+            .map(|holding| &holding.user_id)
+            .collect::<HashSet<_>>()
+            .len(),
+        assets: holdings.len(),
+        outstanding: Vec::new(),
+    };
+
+    for holding in &holdings {
+        let AssetInfo::Cash { currency, amount } = &holding.asset_info;
+
+        match issuance
+            .outstanding
+            .iter_mut()
+            .find(|money| &money.currency == currency)
+        {
+            Some(money) => money.amount += amount,
+            None => issuance.outstanding.push(Money {
+                currency: currency.clone(),
+                amount: *amount,
+            }),
+        }
+    }
+
+    Ok(types::TokenManagerDetails {
+        token_manager_id,
+        token_manager_name: token_manager.token_manager_name,
+        public_key: token_manager.public_key,
+        previous_keys: token_manager.previous_keys,
+        sync_url: token_manager.sync_url,
+        status: token_manager.status,
+        deactivated_at: token_manager.deactivated_at,
+        supported_assets,
+        issuance,
+    })
 }
 
-async fn delete_token_manager(Path(_token_manager_id): Path<String>) -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+#[utoipa::path(
+    get,
+    path = "/{token_manager_id}/sync",
+    tag = "token_managers",
+    responses((status = OK, body = SyncStatus)),
+)]
+async fn get_sync_status(
+    State(app_state): State<AppState>,
+    Path(token_manager_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let status = app_state
+        .storage
+        .get_sync_interface()
+        .await
+        .change_context(ApiError::FetchSyncStatusError)
+        .map_err(log_convert)?
+        .status(&token_manager_id)
+        .await
+        .change_context(ApiError::FetchSyncStatusError)
+        .map_err(log_convert)?;
+
+    Ok(Json(status))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{token_manager_id}/sync/resync",
+    tag = "token_managers",
+    responses((status = OK, body = types::ResyncResponse)),
+)]
+async fn resync(
+    State(app_state): State<AppState>,
+    Path(token_manager_id): Path<String>,
+) -> Result<Json<types::ResyncResponse>, ApiError> {
+    app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::ResyncError)
+        .map_err(log_convert)?
+        .get_token_manager(&token_manager_id)
+        .await
+        .change_context(ApiError::ResyncError)
+        .map_err(log_convert)?;
+
+    let sequence = crate::sync::resync(&app_state, &token_manager_id)
+        .await
+        .change_context(ApiError::ResyncError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::ResyncResponse {
+        token_manager_id,
+        sequence,
+    }))
 }
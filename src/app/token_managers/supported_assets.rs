@@ -1,11 +1,16 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
-use axum::Json;
+use axum::{Extension, Json};
 use error_stack::ResultExt;
 
+use crate::app::pagination::PageParams;
+use crate::auth::Principal;
 use crate::error::{log_convert, ApiError, ConfigurationError};
 use crate::state::AppState;
+use crate::storage::types::SupportedAssetStatus;
+use crate::utils::unix_timestamp;
+use crate::validation::ValidJson;
 
 mod types;
 
@@ -22,11 +27,31 @@ pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
     Ok(router)
 }
 
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    create_supported_asset,
+    list_supported_assets,
+    get_supported_asset,
+    update_supported_asset,
+    delete_supported_asset
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "supported_assets",
+    request_body = types::CreateSARequest,
+    responses((status = OK, body = types::CreateSAResponse)),
+)]
 async fn create_supported_asset(
     State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path(token_manager_id): Path<String>,
-    Json(req): Json<types::CreateSARequest>,
+    ValidJson(req): ValidJson<types::CreateSARequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
     let output = app_state
         .storage
         .get_token_manager_interface()
@@ -39,7 +64,10 @@ async fn create_supported_asset(
         .map_err(log_convert)?
         .create_supported_asset(crate::storage::types::SupportedAsset {
             asset_type: req.asset_type.clone(),
-            smart_contract_refs: req.smart_contract_refs.as_bytes().to_vec(),
+            smart_contract_refs: req.smart_contract_refs,
+            custody: req.custody,
+            status: SupportedAssetStatus::Active,
+            deprecated_at: None,
         })
         .await
         .change_context(ApiError::CreateSupportedAssetError)
@@ -51,42 +79,177 @@ async fn create_supported_asset(
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "supported_assets",
+    params(PageParams, types::ListSAQuery),
+    responses((status = OK, body = types::ListSAResponse)),
+)]
 async fn list_supported_assets(
     State(app_state): State<AppState>,
     Path(token_manager_id): Path<String>,
-) -> Result<impl IntoResponse, ApiError> {
-    let output = app_state
+    Query(page): Query<PageParams>,
+    Query(query): Query<types::ListSAQuery>,
+) -> Result<Json<types::ListSAResponse>, ApiError> {
+    let page = app_state
         .storage
         .get_token_manager_interface()
         .await
-        .change_context(ApiError::CreateSupportedAssetError)
+        .change_context(ApiError::ListSupportedAssetsError)
         .map_err(log_convert)?
         .get_supported_asset_interface(&token_manager_id)
         .await
-        .change_context(ApiError::CreateSupportedAssetError)
+        .change_context(ApiError::ListSupportedAssetsError)
         .map_err(log_convert)?
-        .list_supported_assets()
+        .query_supported_assets(
+            &page.query(query.sort.unwrap_or_default())?,
+            &query.filter(),
+        )
         .await
-        .change_context(ApiError::CreateSupportedAssetError)
+        .change_context(ApiError::ListSupportedAssetsError)
         .map_err(log_convert)?;
 
-    Ok(Json(output))
+    Ok(Json(types::ListSAResponse {
+        items: page.items.into_iter().map(Into::into).collect(),
+        next_cursor: page.next_cursor,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{supported_asset_id}",
+    tag = "supported_assets",
+    responses((status = OK, body = types::SAResponse)),
+)]
 async fn get_supported_asset(
-    Path((_token_manager_id, _supported_asset_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    // ...
+    State(app_state): State<AppState>,
+    Path((token_manager_id, supported_asset_id)): Path<(String, String)>,
+) -> Result<Json<types::SAResponse>, ApiError> {
+    let supported_asset = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::FetchSupportedAssetError)
+        .map_err(log_convert)?
+        .get_supported_asset_interface(&token_manager_id)
+        .await
+        .change_context(ApiError::FetchSupportedAssetError)
+        .map_err(log_convert)?
+        .get_supported_asset(&supported_asset_id)
+        .await
+        .change_context(ApiError::FetchSupportedAssetError)
+        .map_err(log_convert)?;
+
+    Ok(Json(supported_asset.into()))
 }
 
+#[utoipa::path(
+    put,
+    path = "/{supported_asset_id}",
+    tag = "supported_assets",
+    request_body = types::UpdateSARequest,
+    responses((status = OK, body = types::SAResponse)),
+)]
 async fn update_supported_asset(
-    Path((_token_manager_id, _supported_asset_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    // ...
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, supported_asset_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<types::UpdateSARequest>,
+) -> Result<Json<types::SAResponse>, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let supported_asset_interface = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::UpdateSupportedAssetError)
+        .map_err(log_convert)?
+        .get_supported_asset_interface(&token_manager_id)
+        .await
+        .change_context(ApiError::UpdateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    let mut supported_asset = supported_asset_interface
+        .get_supported_asset(&supported_asset_id)
+        .await
+        .change_context(ApiError::UpdateSupportedAssetError)
+        .map_err(log_convert)?
+        .item;
+
+    (supported_asset.status == SupportedAssetStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::SupportedAssetDeprecatedError)?;
+
+    if let Some(smart_contract_refs) = req.smart_contract_refs {
+        supported_asset.smart_contract_refs = smart_contract_refs;
+    }
+    if let Some(custody) = req.custody {
+        supported_asset.custody = custody;
+    }
+
+    supported_asset_interface
+        .update_supported_asset(&supported_asset_id, supported_asset)
+        .await
+        .change_context(ApiError::UpdateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    let supported_asset = supported_asset_interface
+        .get_supported_asset(&supported_asset_id)
+        .await
+        .change_context(ApiError::UpdateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    Ok(Json(supported_asset.into()))
 }
 
+/// Deprecates the supported asset. Accounts already opened for it keep working, but no new ones
+/// are opened for its asset type.
+#[utoipa::path(
+    delete,
+    path = "/{supported_asset_id}",
+    tag = "supported_assets",
+    responses((status = OK, body = types::SAResponse)),
+)]
 async fn delete_supported_asset(
-    Path((_token_manager_id, _supported_asset_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    // ...
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, supported_asset_id)): Path<(String, String)>,
+) -> Result<Json<types::SAResponse>, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let supported_asset_interface = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::DeprecateSupportedAssetError)
+        .map_err(log_convert)?
+        .get_supported_asset_interface(&token_manager_id)
+        .await
+        .change_context(ApiError::DeprecateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    let supported_asset = supported_asset_interface
+        .get_supported_asset(&supported_asset_id)
+        .await
+        .change_context(ApiError::DeprecateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    (supported_asset.item.status == SupportedAssetStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::SupportedAssetDeprecatedError)?;
+
+    supported_asset_interface
+        .deprecate_supported_asset(&supported_asset_id, unix_timestamp())
+        .await
+        .change_context(ApiError::DeprecateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    let supported_asset = supported_asset_interface
+        .get_supported_asset(&supported_asset_id)
+        .await
+        .change_context(ApiError::DeprecateSupportedAssetError)
+        .map_err(log_convert)?;
+
+    Ok(Json(supported_asset.into()))
 }
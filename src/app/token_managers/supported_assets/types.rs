@@ -1,15 +1,157 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::storage::types::AssetType;
+use crate::storage::types::{
+    AssetCustody, AssetType, ContractRef, Entry, SupportedAsset, SupportedAssetFilter,
+    SupportedAssetSort, SupportedAssetStatus,
+};
+use crate::validation::{self, Validate, Violations};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateSARequest {
     pub asset_type: AssetType,
-    pub smart_contract_refs: String,
+    #[serde(default)]
+    pub smart_contract_refs: Vec<ContractRef>,
+    #[serde(default)]
+    pub custody: AssetCustody,
+}
+
+impl Validate for CreateSARequest {
+    fn validate(&self, violations: &mut Violations) {
+        check_contract_refs(&self.smart_contract_refs, violations);
+    }
+}
+
+/// Fields of the supported asset to change, at least one of them. The asset type stays as
+/// accounts are opened for it.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateSARequest {
+    #[serde(default)]
+    pub smart_contract_refs: Option<Vec<ContractRef>>,
+    #[serde(default)]
+    pub custody: Option<AssetCustody>,
+}
+
+impl Validate for UpdateSARequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            self.smart_contract_refs.is_some() || self.custody.is_some(),
+            ".",
+            "must change at least one field",
+        );
+
+        if let Some(smart_contract_refs) = &self.smart_contract_refs {
+            check_contract_refs(smart_contract_refs, violations);
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Every reference has to name a contract on a chain by a CAIP-2 id, each contract at most once.
+fn check_contract_refs(refs: &[ContractRef], violations: &mut Violations) {
+    let mut seen = HashSet::new();
+
+    for (index, contract) in refs.iter().enumerate() {
+        let field = |name: &str| format!("smart_contract_refs[{index}].{name}");
+
+        violations.check(
+            validation::is_chain_id(&contract.chain_id),
+            &field("chain_id"),
+            "must be a CAIP-2 chain id such as `eip155:1`",
+        );
+
+        // EVM addresses have a fixed shape, those of other chains are only required to be there
+        if contract.chain_id.starts_with("eip155:") {
+            violations.check(
+                contract.address.len() == 42
+                    && contract.address.starts_with("0x")
+                    && contract.address[2..].chars().all(|c| c.is_ascii_hexdigit()),
+                &field("address"),
+                "must be a 0x prefixed 20 byte hex address",
+            );
+        } else {
+            violations.check(
+                !contract.address.is_empty() && !contract.address.chars().any(char::is_whitespace),
+                &field("address"),
+                "must be non-empty without whitespace",
+            );
+        }
+
+        violations.check(
+            !contract.interface_id.is_empty()
+                && !contract.interface_id.chars().any(char::is_whitespace),
+            &field("interface_id"),
+            "must be non-empty without whitespace",
+        );
+
+        violations.check(
+            seen.insert((
+                contract.chain_id.as_str(),
+                contract.address.to_ascii_lowercase(),
+            )),
+            &field("address"),
+            "must not reference the same contract twice",
+        );
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateSAResponse {
     pub supported_asset_id: String,
     pub asset_type: AssetType,
 }
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListSAQuery {
+    /// Key the supported assets are ordered by, their creation time unless given.
+    pub sort: Option<SupportedAssetSort>,
+    pub asset_type: Option<AssetType>,
+    pub custody: Option<AssetCustody>,
+    pub status: Option<SupportedAssetStatus>,
+    /// Supported assets created strictly after this unix timestamp.
+    pub created_after: Option<u64>,
+}
+
+impl ListSAQuery {
+    pub fn filter(&self) -> SupportedAssetFilter {
+        SupportedAssetFilter {
+            asset_type: self.asset_type.clone(),
+            custody: self.custody,
+            status: self.status,
+            created_after: self.created_after,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SAResponse {
+    pub supported_asset_id: String,
+    pub asset_type: AssetType,
+    pub smart_contract_refs: Vec<ContractRef>,
+    pub custody: AssetCustody,
+    pub status: SupportedAssetStatus,
+    pub deprecated_at: Option<u64>,
+    pub created_at: u64,
+}
+
+impl From<Entry<SupportedAsset>> for SAResponse {
+    fn from(entry: Entry<SupportedAsset>) -> Self {
+        Self {
+            supported_asset_id: entry.id,
+            asset_type: entry.item.asset_type,
+            smart_contract_refs: entry.item.smart_contract_refs,
+            custody: entry.item.custody,
+            status: entry.item.status,
+            deprecated_at: entry.item.deprecated_at,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListSAResponse {
+    pub items: Vec<SAResponse>,
+    /// Cursor of the next page, missing on the last one.
+    pub next_cursor: Option<String>,
+}
@@ -1,31 +1,151 @@
 use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Deserialize)]
+use crate::storage::types::{
+    AssetCustody, AssetType, Entry, Money, RetiredKey, SupportedAssetStatus, TokenManager,
+    TokenManagerFilter, TokenManagerSort, TokenManagerStatus,
+};
+use crate::validation::{Validate, Violations};
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub(super) struct CreateTokenManagerRequest {
     pub token_manager_name: String,
     pub public_key: String,
+    #[serde(default)]
+    pub sync_url: Option<String>,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, ToSchema)]
 pub(super) struct CreateTokenManagerResponse {
     pub token_manager_id: String,
     pub token_manager_name: String,
 }
 
-impl From<crate::storage::types::TokenManagerInfo> for CreateTokenManagerResponse {
-    fn from(value: crate::storage::types::TokenManagerInfo) -> Self {
+#[derive(Debug, Deserialize, IntoParams)]
+pub(super) struct ListTokenManagersQuery {
+    /// Key the token managers are ordered by, their creation time unless given.
+    pub sort: Option<TokenManagerSort>,
+    /// Token managers created strictly after this unix timestamp.
+    pub created_after: Option<u64>,
+}
+
+impl ListTokenManagersQuery {
+    pub fn filter(&self) -> TokenManagerFilter {
+        TokenManagerFilter {
+            created_after: self.created_after,
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, ToSchema)]
+pub(super) struct TokenManagerResponse {
+    pub token_manager_id: String,
+    pub token_manager_name: String,
+    pub created_at: u64,
+}
+
+impl From<Entry<TokenManager>> for TokenManagerResponse {
+    fn from(entry: Entry<TokenManager>) -> Self {
         Self {
-            token_manager_id: value.token_manager_id,
-            token_manager_name: value.token_manager_name,
+            token_manager_id: entry.id,
+            token_manager_name: entry.item.token_manager_name,
+            created_at: entry.created_at,
         }
     }
 }
 
+#[derive(Debug, serde::Serialize, ToSchema)]
+pub(super) struct ListTokenManagersResponse {
+    pub items: Vec<TokenManagerResponse>,
+    /// Cursor of the next page, missing on the last one.
+    pub next_cursor: Option<String>,
+}
+
+/// Fields of the token manager to change, at least one of them. A new public key replaces the
+/// current one, which is kept to verify what it signed.
+#[derive(Debug, Deserialize, ToSchema)]
+pub(super) struct UpdateTokenManagerRequest {
+    #[serde(default)]
+    pub token_manager_name: Option<String>,
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub sync_url: Option<String>,
+}
+
+impl Validate for UpdateTokenManagerRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            self.token_manager_name.is_some()
+                || self.public_key.is_some()
+                || self.sync_url.is_some(),
+            ".",
+            "must change at least one field",
+        );
+
+        if let Some(token_manager_name) = &self.token_manager_name {
+            violations.non_empty(token_manager_name, "token_manager_name");
+        }
+        if let Some(public_key) = &self.public_key {
+            violations.check(
+                crate::auth::verifying_key(public_key).is_some(),
+                "public_key",
+                "must be a hex encoded ed25519 public key",
+            );
+        }
+        if let Some(sync_url) = &self.sync_url {
+            violations.non_empty(sync_url, "sync_url");
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, ToSchema)]
+pub(super) struct SupportedAssetSummary {
+    pub supported_asset_id: String,
+    pub asset_type: AssetType,
+    pub custody: AssetCustody,
+    pub status: SupportedAssetStatus,
+}
+
+/// What the accounts backed by the token manager currently hold.
+#[derive(Debug, Default, serde::Serialize, ToSchema)]
+pub(super) struct IssuanceStats {
+    /// Users holding at least one asset issued by the token manager.
+    pub holders: usize,
+    pub assets: usize,
+    /// Amount outstanding, per currency.
+    pub outstanding: Vec<Money>,
+}
+
+#[derive(Debug, serde::Serialize, ToSchema)]
+pub(super) struct TokenManagerDetails {
+    pub token_manager_id: String,
+    pub token_manager_name: String,
+    pub public_key: String,
+    /// Keys the token manager rotated away from, oldest first.
+    pub previous_keys: Vec<RetiredKey>,
+    pub sync_url: Option<String>,
+    pub status: TokenManagerStatus,
+    pub deactivated_at: Option<u64>,
+    pub supported_assets: Vec<SupportedAssetSummary>,
+    pub issuance: IssuanceStats,
+}
+
 impl From<CreateTokenManagerRequest> for crate::storage::types::TokenManager {
     fn from(value: CreateTokenManagerRequest) -> Self {
         Self {
             token_manager_name: value.token_manager_name,
             public_key: value.public_key,
+            sync_url: value.sync_url,
+            previous_keys: Vec::new(),
+            status: Default::default(),
+            deactivated_at: None,
         }
     }
 }
+
+#[derive(Debug, serde::Serialize, ToSchema)]
+pub(super) struct ResyncResponse {
+    pub token_manager_id: String,
+    pub sequence: u64,
+}
@@ -0,0 +1,309 @@
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::Extension;
+use axum::Json;
+use error_stack::{report, ResultExt};
+use nanoid::nanoid;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::state::AppState;
+use crate::storage::types::{Delivery, DeliveryStatus, Webhook};
+use crate::utils::unix_timestamp;
+use crate::webhooks;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(create_webhook).get(list_webhooks))
+        .route("/:webhook_id", get(get_webhook).delete(delete_webhook))
+        .route("/:webhook_id/deliveries", get(list_deliveries))
+        .route("/:webhook_id/deliveries/:delivery_id", get(get_delivery))
+        .route(
+            "/:webhook_id/deliveries/:delivery_id/replay",
+            post(replay_delivery),
+        );
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    create_webhook,
+    list_webhooks,
+    get_webhook,
+    delete_webhook,
+    list_deliveries,
+    get_delivery,
+    replay_delivery,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "webhooks",
+    responses((status = OK, body = types::CreateWebhookResponse)),
+)]
+async fn create_webhook(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(token_manager_id): Path<String>,
+    Json(req): Json<types::CreateWebhookRequest>,
+) -> Result<Json<types::CreateWebhookResponse>, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let url = reqwest::Url::parse(&req.url).map_err(|_| ApiError::InvalidWebhookUrlError)?;
+
+    matches!(url.scheme(), "http" | "https")
+        .then_some(())
+        .ok_or(ApiError::InvalidWebhookUrlError)?;
+
+    webhooks::ensure_public(&url)
+        .await
+        .map_err(|reason| report!(ApiError::InvalidWebhookUrlError).attach_printable(reason))
+        .map_err(log_convert)?;
+
+    app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::CreateWebhookError)
+        .map_err(log_convert)?
+        .get_token_manager(&token_manager_id)
+        .await
+        .change_context(ApiError::CreateWebhookError)
+        .map_err(log_convert)?;
+
+    let webhook = Webhook {
+        token_manager_id,
+        url: req.url,
+        secret: nanoid!(32),
+        events: req.events,
+        created_at: unix_timestamp(),
+    };
+
+    let webhook_id = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::CreateWebhookError)
+        .map_err(log_convert)?
+        .create_webhook(webhook.clone())
+        .await
+        .change_context(ApiError::CreateWebhookError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::CreateWebhookResponse {
+        webhook_id,
+        url: webhook.url,
+        events: webhook.events,
+        secret: webhook.secret,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "webhooks",
+    responses((status = OK, body = Vec<types::WebhookResponse>)),
+)]
+async fn list_webhooks(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(token_manager_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_reader(&principal, &token_manager_id)?;
+
+    let webhooks = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::FetchWebhookError)
+        .map_err(log_convert)?
+        .list_webhooks(&token_manager_id)
+        .await
+        .change_context(ApiError::FetchWebhookError)
+        .map_err(log_convert)?;
+
+    Ok(Json(
+        webhooks
+            .into_iter()
+            .map(types::WebhookResponse::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_id}",
+    tag = "webhooks",
+    responses((status = OK, body = types::WebhookResponse)),
+)]
+async fn get_webhook(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, webhook_id)): Path<(String, String)>,
+) -> Result<Json<types::WebhookResponse>, ApiError> {
+    ensure_reader(&principal, &token_manager_id)?;
+
+    let webhook = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::FetchWebhookError)
+        .map_err(log_convert)?
+        .get_webhook(&token_manager_id, &webhook_id)
+        .await
+        .change_context(ApiError::FetchWebhookError)
+        .map_err(log_convert)?;
+
+    Ok(Json((webhook_id, webhook).into()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{webhook_id}",
+    tag = "webhooks",
+    responses((status = OK, body = types::WebhookResponse)),
+)]
+async fn delete_webhook(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, webhook_id)): Path<(String, String)>,
+) -> Result<Json<types::WebhookResponse>, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let webhook = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::DeleteWebhookError)
+        .map_err(log_convert)?
+        .delete_webhook(&token_manager_id, &webhook_id)
+        .await
+        .change_context(ApiError::DeleteWebhookError)
+        .map_err(log_convert)?;
+
+    Ok(Json((webhook_id, webhook).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_id}/deliveries",
+    tag = "webhooks",
+    responses((status = OK, body = Vec<Delivery>)),
+)]
+async fn list_deliveries(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, webhook_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_reader(&principal, &token_manager_id)?;
+
+    let webhook_interface = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::FetchDeliveryError)
+        .map_err(log_convert)?;
+
+    webhook_interface
+        .get_webhook(&token_manager_id, &webhook_id)
+        .await
+        .change_context(ApiError::FetchDeliveryError)
+        .map_err(log_convert)?;
+
+    let deliveries = webhook_interface
+        .list_deliveries(&webhook_id)
+        .await
+        .change_context(ApiError::FetchDeliveryError)
+        .map_err(log_convert)?;
+
+    Ok(Json(deliveries))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{webhook_id}/deliveries/{delivery_id}",
+    tag = "webhooks",
+    responses((status = OK, body = Delivery)),
+)]
+async fn get_delivery(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, webhook_id, delivery_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    ensure_reader(&principal, &token_manager_id)?;
+
+    let delivery = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::FetchDeliveryError)
+        .map_err(log_convert)?
+        .get_delivery(&delivery_id)
+        .await
+        .change_context(ApiError::FetchDeliveryError)
+        .map_err(log_convert)?;
+
+    (delivery.token_manager_id == token_manager_id && delivery.webhook_id == webhook_id)
+        .then_some(())
+        .ok_or(ApiError::FetchDeliveryError)?;
+
+    Ok(Json(delivery))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{webhook_id}/deliveries/{delivery_id}/replay",
+    tag = "webhooks",
+    responses((status = OK, body = Delivery)),
+)]
+async fn replay_delivery(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((token_manager_id, webhook_id, delivery_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    let webhook_interface = app_state
+        .storage
+        .get_webhook_interface()
+        .await
+        .change_context(ApiError::ReplayDeliveryError)
+        .map_err(log_convert)?;
+
+    let mut delivery = webhook_interface
+        .get_delivery(&delivery_id)
+        .await
+        .change_context(ApiError::ReplayDeliveryError)
+        .map_err(log_convert)?;
+
+    (delivery.token_manager_id == token_manager_id && delivery.webhook_id == webhook_id)
+        .then_some(())
+        .ok_or(ApiError::ReplayDeliveryError)?;
+
+    delivery.status = DeliveryStatus::Pending;
+    delivery.attempts = 0;
+    delivery.next_attempt_at = unix_timestamp();
+    delivery.last_error = None;
+
+    webhook_interface
+        .update_delivery(delivery.clone())
+        .await
+        .change_context(ApiError::ReplayDeliveryError)
+        .map_err(log_convert)?;
+
+    Ok(Json(delivery))
+}
+
+/// Webhooks and their deliveries carry the events of the token manager, only its operators and
+/// auditors may read them.
+fn ensure_reader(principal: &Principal, token_manager_id: &str) -> Result<(), ApiError> {
+    (principal.is_auditor() || principal.operates(token_manager_id))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)
+}
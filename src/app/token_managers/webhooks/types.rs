@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::storage::types::{EventType, Webhook};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<EventType>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreateWebhookResponse {
+    pub webhook_id: String,
+    pub url: String,
+    pub events: Vec<EventType>,
+    /// Shared secret used to sign deliveries, only returned while registering the webhook.
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub webhook_id: String,
+    pub url: String,
+    pub events: Vec<EventType>,
+    pub created_at: u64,
+}
+
+impl From<(String, Webhook)> for WebhookResponse {
+    fn from((webhook_id, webhook): (String, Webhook)) -> Self {
+        Self {
+            webhook_id,
+            url: webhook.url,
+            events: webhook.events,
+            created_at: webhook.created_at,
+        }
+    }
+}
@@ -1,15 +1,20 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
-use axum::Json;
+use axum::{Extension, Json};
 use error_stack::ResultExt;
 
+use crate::auth::{self, Principal, Subject};
 use crate::error::{log_convert, ApiError, ConfigurationError};
 use crate::state::AppState;
+use crate::storage::types::{RetiredKey, UserStatus};
+use crate::utils::unix_timestamp;
+use crate::validation::{ValidJson, Violations};
 
 use crate::logging::prelude::*;
 
 mod accounts;
+mod schedules;
 mod types;
 
 pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
@@ -19,14 +24,34 @@ pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
             "/:user_id",
             get(get_user).put(update_user).delete(delete_user),
         )
-        .nest("/:user_id/accounts", accounts::router()?);
+        .route("/:user_id/keys", get(get_user_keys))
+        .nest("/:user_id/accounts", accounts::router()?)
+        .nest("/:user_id/schedules", schedules::router()?);
 
     Ok(router)
 }
 
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(create_user, get_user, update_user, get_user_keys, delete_user),
+    nest(
+        (path = "/{user_id}/accounts", api = accounts::ApiDoc),
+        (path = "/{user_id}/schedules", api = schedules::ApiDoc),
+    ),
+)]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "users",
+    request_body = types::CreateUserRequest,
+    security(()),
+    responses((status = OK, body = types::CreateUserResponse)),
+)]
 async fn create_user(
     State(app_state): State<AppState>,
-    Json(user): Json<types::CreateUserRequest>,
+    ValidJson(user): ValidJson<types::CreateUserRequest>,
 ) -> Result<Json<types::CreateUserResponse>, ApiError> {
     let ua_addr = user.ua_addr.clone();
     let user_id = app_state
@@ -40,6 +65,11 @@ async fn create_user(
         .change_context(ApiError::CreateUserError)
         .map_err(log_convert)?;
 
+    auth::enroll(&app_state, &user_id, &user.public_key)
+        .await
+        .change_context(ApiError::CreateUserError)
+        .map_err(log_convert)?;
+
     let output = types::CreateUserResponse { user_id, ua_addr };
 
     info!("Created user: {:?}", output);
@@ -47,6 +77,12 @@ async fn create_user(
     Ok(Json(output))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{user_id}",
+    tag = "users",
+    responses((status = OK, body = types::GetUserResponse)),
+)]
 async fn get_user(
     State(app_state): State<AppState>,
     Path(user_id): Path<String>,
@@ -67,12 +103,354 @@ async fn get_user(
     Ok(Json(output))
 }
 
-async fn update_user(Path(_user_id): Path<String>) -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+#[utoipa::path(
+    put,
+    path = "/{user_id}",
+    tag = "users",
+    request_body = types::UpdateUserRequest,
+    responses((status = OK, body = types::GetUserResponse)),
+)]
+async fn update_user(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(user_id): Path<String>,
+    ValidJson(req): ValidJson<types::UpdateUserRequest>,
+) -> Result<Json<types::GetUserResponse>, ApiError> {
+    principal.ensure_user(&user_id)?;
+
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::UpdateUserError)
+        .map_err(log_convert)?;
+
+    let mut user = user_interface
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::UpdateUserError)
+        .map_err(log_convert)?;
+
+    (user.status == UserStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::UserClosedError)?;
+
+    let rotated = match req.public_key {
+        Some(public_key) if !public_key.eq_ignore_ascii_case(&user.public_key) => {
+            let mut violations = Violations::default();
+            violations.check(
+                !user
+                    .previous_keys
+                    .iter()
+                    .any(|key| key.public_key.eq_ignore_ascii_case(&public_key)),
+                "public_key",
+                "must not be a key the user rotated away from",
+            );
+            violations.into_result()?;
+
+            let retired = std::mem::replace(&mut user.public_key, public_key);
+            user.previous_keys.push(RetiredKey {
+                public_key: retired,
+                retired_at: unix_timestamp(),
+            });
+
+            true
+        }
+        _ => false,
+    };
+
+    if let Some(email) = req.email {
+        user.email = email;
+    }
+    if let Some(name) = req.name {
+        user.name = name;
+    }
+
+    user_interface
+        .update_user(&user_id, user)
+        .await
+        .change_context(ApiError::UpdateUserError)
+        .map_err(log_convert)?;
+
+    // sessions were opened by proving possession of the retired key
+    if rotated {
+        app_state
+            .storage
+            .get_session_interface()
+            .await
+            .change_context(ApiError::UpdateUserError)
+            .map_err(log_convert)?
+            .revoke_sessions(&Subject::User(user_id.clone()).to_string())
+            .await
+            .change_context(ApiError::UpdateUserError)
+            .map_err(log_convert)?;
+    }
+
+    let user = user_interface
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::UpdateUserError)
+        .map_err(log_convert)?;
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{user_id}/keys",
+    tag = "users",
+    params(types::UserKeysQuery),
+    responses((status = OK, body = types::UserKeysResponse)),
+)]
+async fn get_user_keys(
+    State(app_state): State<AppState>,
+    Path(user_id): Path<String>,
+    Query(query): Query<types::UserKeysQuery>,
+) -> Result<Json<types::UserKeysResponse>, ApiError> {
+    let user = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::GetUserError)
+        .map_err(log_convert)?
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::GetUserError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::UserKeysResponse {
+        key_at: query.at.map(|at| user.key_at(at).to_string()),
+        public_key: user.public_key,
+        previous_keys: user.previous_keys,
+    }))
 }
 
-async fn delete_user(Path(_user_id): Path<String>) -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+#[utoipa::path(
+    delete,
+    path = "/{user_id}",
+    tag = "users",
+    params(types::CloseUserQuery),
+    responses((status = OK, body = types::CloseUserResponse)),
+)]
+async fn delete_user(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(user_id): Path<String>,
+    Query(query): Query<types::CloseUserQuery>,
+) -> Result<Json<types::CloseUserResponse>, ApiError> {
+    principal.ensure_user(&user_id)?;
+
+    let sweep_to = query
+        .target()?
+        .map(|(ua_addr, account_id)| accounts::SweepTarget {
+            ua_addr,
+            account_id,
+        });
+
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?;
+
+    let user = user_interface
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?;
+
+    (user.status == UserStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::UserClosedError)?;
+
+    // what the user holds goes to someone else, their own accounts are all being closed
+    sweep_to
+        .as_ref()
+        .is_none_or(|target| target.ua_addr != user.ua_addr)
+        .then_some(())
+        .ok_or(ApiError::InvalidSweepTargetError)?;
+
+    let account_ids = user_interface
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?
+        .list_accounts()
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?
+        .into_iter()
+        .map(|(account_id, _)| account_id)
+        .collect::<Vec<_>>();
+
+    for account_id in &account_ids {
+        accounts::ensure_closable(
+            &app_state,
+            &user_id,
+            account_id,
+            sweep_to.as_ref(),
+            ApiError::CloseUserError,
+        )
+        .await?;
+    }
+
+    let mut closed_accounts = Vec::new();
+
+    for account_id in account_ids {
+        let swept_asset_ids = accounts::close(
+            &app_state,
+            &user_id,
+            &account_id,
+            sweep_to.as_ref(),
+            ApiError::CloseUserError,
+        )
+        .await?;
+
+        closed_accounts.push(types::ClosedAccount {
+            account_id,
+            swept_asset_ids,
+        });
+    }
+
+    // the user can't act anymore, neither through an open session nor through a role
+    let subject = Subject::User(user_id.clone()).to_string();
+
+    app_state
+        .storage
+        .get_session_interface()
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?
+        .revoke_sessions(&subject)
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?;
+
+    let role_interface = app_state
+        .storage
+        .get_role_interface()
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?;
+
+    let grants = role_interface
+        .list_roles(Some(&subject))
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?;
+
+    for (grant_id, _) in grants {
+        role_interface
+            .revoke_role(&grant_id)
+            .await
+            .change_context(ApiError::CloseUserError)
+            .map_err(log_convert)?;
+    }
+
+    let closed_at = unix_timestamp();
+
+    user_interface
+        .close_user(&user_id, closed_at)
+        .await
+        .change_context(ApiError::CloseUserError)
+        .map_err(log_convert)?;
+
+    info!(
+        "Closed user {user_id} along with {} accounts",
+        closed_accounts.len()
+    );
+
+    Ok(Json(types::CloseUserResponse {
+        user_id,
+        ua_addr: user.ua_addr,
+        closed_at,
+        closed_accounts,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::accounts::tests::{balance, open, register, token_manager};
+    use super::*;
+    use crate::storage::types::Multisig;
+
+    async fn close_user(
+        app_state: &AppState,
+        user_id: &str,
+        sweep_to_account: &str,
+    ) -> Result<Json<types::CloseUserResponse>, ApiError> {
+        delete_user(
+            State(app_state.clone()),
+            Extension(Principal::unrestricted()),
+            Path(user_id.to_string()),
+            Query(types::CloseUserQuery {
+                sweep_to_ua: Some("bob@ua".to_string()),
+                sweep_to_account: Some(sweep_to_account.to_string()),
+            }),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn sweeps_every_account_to_the_target() {
+        let app_state = AppState::for_tests();
+        let token_manager_id = token_manager(&app_state).await;
+        let alice = register(&app_state, "alice@ua").await;
+        let bob = register(&app_state, "bob@ua").await;
+        open(&app_state, &alice, &token_manager_id, 50).await;
+        open(&app_state, &alice, &token_manager_id, 20).await;
+        let target = open(&app_state, &bob, &token_manager_id, 0).await;
+
+        let Json(closed) = close_user(&app_state, &alice, &target).await.unwrap();
+
+        assert_eq!(closed.closed_accounts.len(), 2);
+        assert_eq!(balance(&app_state, &bob, &target).await, 70);
+
+        let user = app_state
+            .storage
+            .get_user_interface()
+            .await
+            .unwrap()
+            .get_user(&alice)
+            .await
+            .unwrap();
+        assert_eq!(user.status, UserStatus::Closed);
+    }
+
+    #[tokio::test]
+    async fn moves_nothing_when_any_account_can_not_be_swept() {
+        let app_state = AppState::for_tests();
+        let token_manager_id = token_manager(&app_state).await;
+        let alice = register(&app_state, "alice@ua").await;
+        let bob = register(&app_state, "bob@ua").await;
+        let first = open(&app_state, &alice, &token_manager_id, 50).await;
+        let treasury = open(&app_state, &alice, &token_manager_id, 20).await;
+        let target = open(&app_state, &bob, &token_manager_id, 0).await;
+
+        let multisig = Multisig {
+            threshold: 10,
+            required: 1,
+            signers: vec!["carol@ua".to_string()],
+            updated_at: 0,
+        };
+
+        app_state
+            .storage
+            .get_multisig_interface()
+            .await
+            .unwrap()
+            .set_multisig(
+                &crate::ledger::account_resource(&alice, &treasury),
+                multisig,
+            )
+            .await
+            .unwrap();
+
+        let refused = close_user(&app_state, &alice, &target).await.unwrap_err();
+
+        assert!(matches!(refused, ApiError::ApprovalRequiredError));
+        assert_eq!(balance(&app_state, &alice, &first).await, 50);
+        assert_eq!(balance(&app_state, &bob, &target).await, 0);
+    }
 }
@@ -1,13 +1,21 @@
-use axum::extract::{Path, State};
-use axum::response::IntoResponse;
+use axum::extract::{Path, Query, State};
 use axum::routing::{get, post};
-use axum::Json;
+use axum::{Extension, Json};
 use error_stack::ResultExt;
 
-use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::app::pagination::PageParams;
+use crate::auth::Principal;
+use crate::error::{ledger_convert, log_convert, AclError, ApiError, ConfigurationError};
 use crate::state::AppState;
+use crate::storage::types::{
+    ApprovalStatus, AssetType, Permission, SupportedAssetStatus, TokenManagerStatus, UserStatus,
+};
+use crate::validation::ValidJson;
+use crate::{acl, ledger};
 
 mod assets;
+mod multisig;
+mod permissions;
 mod types;
 
 pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
@@ -17,23 +25,70 @@ pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
             "/:account_id",
             get(get_account).put(update_account).delete(delete_account),
         )
-        .nest("/:account_id/assets", assets::router()?);
+        .nest("/:account_id/assets", assets::router()?)
+        .nest("/:account_id/permissions", permissions::router()?)
+        .nest("/:account_id/multisig", multisig::router()?);
 
     Ok(router)
 }
 
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(create_account, list_accounts, get_account, update_account, delete_account),
+    nest(
+        (path = "/{account_id}/assets", api = assets::ApiDoc),
+        (path = "/{account_id}/permissions", api = permissions::ApiDoc),
+        (path = "/{account_id}/multisig", api = multisig::ApiDoc),
+    ),
+)]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "accounts",
+    request_body = types::CreateAccountRequest,
+    responses((status = OK, body = types::CreateAccountResponse)),
+)]
 async fn create_account(
     State(app_state): State<AppState>,
     Path(user_id): Path<String>,
-    Json(req): Json<types::CreateAccountRequest>,
+    ValidJson(req): ValidJson<types::CreateAccountRequest>,
 ) -> Result<Json<types::CreateAccountResponse>, ApiError> {
-    // check if the token manager has the asset type
-    let tm_supported_assets = app_state
+    let user = app_state
         .storage
-        .get_token_manager_interface()
+        .get_user_interface()
         .await
         .change_context(ApiError::AccountCreationError)
         .map_err(log_convert)?
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::AccountCreationError)
+        .map_err(log_convert)?;
+
+    (user.status == UserStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::UserClosedError)?;
+
+    let token_manager_interface = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(ApiError::AccountCreationError)
+        .map_err(log_convert)?;
+
+    let token_manager = token_manager_interface
+        .get_token_manager(&req.token_manager_id)
+        .await
+        .change_context(ApiError::AccountCreationError)
+        .map_err(log_convert)?;
+
+    (token_manager.status == TokenManagerStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::TokenManagerDeactivatedError)?;
+
+    // check if the token manager has the asset type
+    let tm_supported_assets = token_manager_interface
         .get_supported_asset_interface(&req.token_manager_id)
         .await
         .change_context(ApiError::AccountCreationError)
@@ -47,12 +102,22 @@ async fn create_account(
     let account_name = req.account_name.clone();
     let asset_type = req.asset_type.clone();
 
-    tm_supported_assets
+    let statuses = tm_supported_assets
         .iter()
-        .any(|asset| asset.asset_type == req.asset_type)
+        .filter(|(_, asset)| asset.asset_type == req.asset_type)
+        .map(|(_, asset)| asset.status)
+        .collect::<Vec<_>>();
+
+    (!statuses.is_empty())
         .then_some(())
         .ok_or(ApiError::AssetTypeNotSupportedError)?;
 
+    // accounts already opened for a deprecated asset type stay, new ones aren't
+    statuses
+        .contains(&SupportedAssetStatus::Active)
+        .then_some(())
+        .ok_or(ApiError::SupportedAssetDeprecatedError)?;
+
     let account_id = app_state
         .storage
         .get_user_interface()
@@ -76,15 +141,87 @@ async fn create_account(
     }))
 }
 
-async fn list_accounts() -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "accounts",
+    params(PageParams, types::ListAccountsQuery),
+    responses((status = OK, body = types::ListAccountsResponse)),
+)]
+async fn list_accounts(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(user_id): Path<String>,
+    Query(page): Query<PageParams>,
+    Query(query): Query<types::ListAccountsQuery>,
+) -> Result<Json<types::ListAccountsResponse>, ApiError> {
+    (principal.acts_for(&user_id) || principal.is_auditor())
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let account_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::ListAccountsError)
+        .map_err(log_convert)?
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::ListAccountsError)
+        .map_err(log_convert)?;
+
+    let page = account_interface
+        .query_accounts(
+            &page.query(query.sort.unwrap_or_default())?,
+            &query.filter(),
+        )
+        .await
+        .change_context(ApiError::ListAccountsError)
+        .map_err(log_convert)?;
+
+    let mut items = Vec::with_capacity(page.items.len());
+
+    for entry in page.items {
+        let (_, total_assets) = account_interface
+            .get_account(&entry.id)
+            .await
+            .change_context(ApiError::ListAccountsError)
+            .map_err(log_convert)?;
+
+        items.push((entry, total_assets).into());
+    }
+
+    Ok(Json(types::ListAccountsResponse {
+        items,
+        next_cursor: page.next_cursor,
+    }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/{account_id}",
+    tag = "accounts",
+    responses((status = OK, body = types::FetchAccountResponse)),
+)]
 async fn get_account(
     State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path((user_id, account_id)): Path<(String, String)>,
 ) -> Result<Json<types::FetchAccountResponse>, ApiError> {
+    if !principal.is_auditor() {
+        acl::check(
+            &app_state,
+            &principal,
+            &user_id,
+            &account_id,
+            None,
+            Permission::View,
+            0,
+        )
+        .await
+        .map_err(|error| acl_error(error, ApiError::FetchAccountError))?;
+    }
+
     let account_interface = app_state
         .storage
         .get_user_interface()
@@ -109,16 +246,656 @@ async fn get_account(
     // Err(ApiError::NotImplemented)
 }
 
+#[utoipa::path(
+    put,
+    path = "/{account_id}",
+    tag = "accounts",
+    request_body = types::UpdateAccountRequest,
+    responses((status = OK, body = types::FetchAccountResponse)),
+)]
 async fn update_account(
-    Path((_user_id, _account_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id)): Path<(String, String)>,
+    ValidJson(req): ValidJson<types::UpdateAccountRequest>,
+) -> Result<Json<types::FetchAccountResponse>, ApiError> {
+    let account_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::UpdateAccountError)
+        .map_err(log_convert)?
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::UpdateAccountError)
+        .map_err(log_convert)?;
+
+    let (mut account, _) = account_interface
+        .get_account(&account_id)
+        .await
+        .change_context(ApiError::UpdateAccountError)
+        .map_err(log_convert)?;
+
+    // the token manager behind the account may re-point the reference it keeps it under
+    (principal.acts_for(&user_id) || principal.operates(&account.token_manager_id))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    if let Some(account_name) = req.account_name {
+        // the name is the holder's own, operators only get to touch their reference
+        principal.ensure_user(&user_id)?;
+
+        account.account_name = account_name;
+    }
+
+    if let Some(token_manager_ref) = req.token_manager_ref {
+        account.token_manager_ref = token_manager_ref;
+    }
+
+    account_interface
+        .update_account(&account_id, account)
+        .await
+        .change_context(ApiError::UpdateAccountError)
+        .map_err(log_convert)?;
+
+    let (account, total_assets) = account_interface
+        .get_account(&account_id)
+        .await
+        .change_context(ApiError::UpdateAccountError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::FetchAccountResponse {
+        account,
+        total_assets,
+    }))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/{account_id}",
+    tag = "accounts",
+    params(types::CloseAccountQuery),
+    responses((status = OK, body = types::CloseAccountResponse)),
+)]
 async fn delete_account(
-    Path((_user_id, _account_id)): Path<(String, String)>,
-) -> impl IntoResponse {
-    // ...
-    ApiError::NotImplemented
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id)): Path<(String, String)>,
+    Query(query): Query<types::CloseAccountQuery>,
+) -> Result<Json<types::CloseAccountResponse>, ApiError> {
+    principal.ensure_user(&user_id)?;
+
+    let sweep_to = match &query.sweep_to {
+        Some(sweep_to) => Some(SweepTarget {
+            ua_addr: app_state
+                .storage
+                .get_user_interface()
+                .await
+                .change_context(ApiError::CloseAccountError)
+                .map_err(log_convert)?
+                .get_user(&user_id)
+                .await
+                .change_context(ApiError::CloseAccountError)
+                .map_err(log_convert)?
+                .ua_addr,
+            account_id: sweep_to.clone(),
+        }),
+        None => None,
+    };
+
+    let swept_asset_ids = close(
+        &app_state,
+        &user_id,
+        &account_id,
+        sweep_to.as_ref(),
+        ApiError::CloseAccountError,
+    )
+    .await?;
+
+    Ok(Json(types::CloseAccountResponse {
+        account_id,
+        swept_to: query.sweep_to,
+        swept_asset_ids,
+    }))
+}
+
+/// Account what is left in a closed account goes to.
+pub(super) struct SweepTarget {
+    pub ua_addr: String,
+    pub account_id: String,
+}
+
+/// Closes the account, after sweeping what it holds to `sweep_to` when given, and returns the ids
+/// of the assets created there. Closing fails while the account still holds anything, so nothing
+/// is dropped along with it.
+///
+/// Approvals pending for the account and descriptors handed out for it are dropped along with it,
+/// as are its schedules. Mounts address token managers rather than accounts, paths through them
+/// simply stop resolving to the account.
+pub(super) async fn close(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    sweep_to: Option<&SweepTarget>,
+    fallback: ApiError,
+) -> Result<Vec<String>, ApiError> {
+    ensure_closable(app_state, user_id, account_id, sweep_to, fallback.clone()).await?;
+
+    for approval_id in pending_approvals(app_state, user_id, account_id, fallback.clone()).await? {
+        crate::multisig::cancel(app_state, &approval_id)
+            .await
+            .change_context(fallback.clone())
+            .map_err(log_convert)?;
+    }
+
+    let descriptors = app_state
+        .storage
+        .get_descriptor_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .list_descriptors()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    for (descriptor_id, descriptor) in descriptors.iter().filter(|(_, descriptor)| {
+        descriptor.user_id == user_id && descriptor.account_id == account_id
+    }) {
+        crate::app::intents::revoke(app_state, descriptor_id, descriptor)
+            .await
+            .change_context(fallback.clone())
+            .map_err(log_convert)?;
+    }
+
+    let account_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let swept_asset_ids = match sweep_to {
+        Some(target) => ledger::sweep(
+            app_state,
+            user_id,
+            account_id,
+            &target.ua_addr,
+            &target.account_id,
+        )
+        .await
+        .map_err(|e| ledger_convert(e, fallback.clone()))?,
+        None => Vec::new(),
+    };
+
+    account_interface
+        .delete_account(account_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    // an account created later under the same id must not inherit who may act on this one
+    let resource = ledger::account_resource(user_id, account_id);
+
+    app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .delete_acl(&resource)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let multisig = crate::multisig::setup(app_state, user_id, account_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    if multisig.is_some() {
+        app_state
+            .storage
+            .get_multisig_interface()
+            .await
+            .change_context(fallback.clone())
+            .map_err(log_convert)?
+            .delete_multisig(&resource)
+            .await
+            .change_context(fallback.clone())
+            .map_err(log_convert)?;
+    }
+
+    // recurring transfers out of the account have nothing left to draw from
+    let schedule_interface = app_state
+        .storage
+        .get_schedule_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let schedules = schedule_interface
+        .list_schedules(user_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    for (schedule_id, _) in schedules
+        .iter()
+        .filter(|(_, schedule)| schedule.account_id == account_id)
+    {
+        schedule_interface
+            .delete_schedule(schedule_id)
+            .await
+            .change_context(fallback.clone())
+            .map_err(log_convert)?;
+    }
+
+    Ok(swept_asset_ids)
+}
+
+/// Checks that [`close`] can go through for the account, so that closing several of them doesn't
+/// stop halfway for a reason known beforehand: the sweep target, the multisig threshold and the
+/// policies of both accounts are all looked at before anything moves.
+pub(super) async fn ensure_closable(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    sweep_to: Option<&SweepTarget>,
+    fallback: ApiError,
+) -> Result<(), ApiError> {
+    // assets held for a swap have to settle first, those held for the signers are given back
+    // when their approvals are dropped
+    let approval_ids = pending_approvals(app_state, user_id, account_id, fallback.clone()).await?;
+
+    let assets = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .get_asset_interface(account_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .list_assets()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let lock_interface = app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    for (asset_id, _) in assets {
+        lock_interface
+            .get_lock(&ledger::asset_resource(user_id, account_id, &asset_id))
+            .await
+            .change_context(fallback.clone())
+            .map_err(log_convert)?
+            .is_none_or(|lock| approval_ids.contains(&lock.holder))
+            .then_some(())
+            .ok_or(ApiError::AssetLockedError)?;
+    }
+
+    let Some(target) = sweep_to else {
+        let balance = ledger::balance(app_state, user_id, account_id)
+            .await
+            .map_err(|e| ledger_convert(e, fallback))?;
+
+        return (balance.amount == 0)
+            .then_some(())
+            .ok_or(ApiError::AccountNotEmptyError);
+    };
+
+    let (account, _) = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .get_account(account_id)
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    ensure_sweep_target(
+        app_state,
+        user_id,
+        account_id,
+        &account.asset_type,
+        target,
+        fallback.clone(),
+    )
+    .await?;
+
+    ledger::ensure_sweepable(
+        app_state,
+        user_id,
+        account_id,
+        &target.ua_addr,
+        &target.account_id,
+    )
+    .await
+    .map_err(|e| ledger_convert(e, fallback))
+}
+
+/// Ids of the approvals still pending for the account.
+async fn pending_approvals(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    fallback: ApiError,
+) -> Result<Vec<String>, ApiError> {
+    let approvals = app_state
+        .storage
+        .get_multisig_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?
+        .list_approvals()
+        .await
+        .change_context(fallback)
+        .map_err(log_convert)?;
+
+    Ok(approvals
+        .into_iter()
+        .filter(|(_, approval)| {
+            approval.user_id == user_id
+                && approval.account_id == account_id
+                && approval.status == ApprovalStatus::Pending
+        })
+        .map(|(approval_id, _)| approval_id)
+        .collect())
+}
+
+/// The target has to be another account holding the same type of assets.
+async fn ensure_sweep_target(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_type: &AssetType,
+    target: &SweepTarget,
+    fallback: ApiError,
+) -> Result<(), ApiError> {
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(fallback.clone())
+        .map_err(log_convert)?;
+
+    let (target_account, _) = user_interface
+        .get_account_interface_by_ua(&target.ua_addr)
+        .await
+        .change_context(ApiError::InvalidSweepTargetError)
+        .map_err(log_convert)?
+        .get_account(&target.account_id)
+        .await
+        .change_context(ApiError::InvalidSweepTargetError)
+        .map_err(log_convert)?;
+
+    let same_account = user_interface
+        .get_user_id_by_ua(&target.ua_addr)
+        .await
+        .change_context(fallback)
+        .map_err(log_convert)?
+        == user_id
+        && target.account_id == account_id;
+
+    (!same_account && &target_account.asset_type == asset_type)
+        .then_some(())
+        .ok_or(ApiError::InvalidSweepTargetError)
+}
+
+/// Like [`ledger_convert`], telling a locked account apart from a failure to close it.
+fn acl_error(error: error_stack::Report<AclError>, fallback: ApiError) -> ApiError {
+    let context = match error.current_context() {
+        AclError::Denied => ApiError::ForbiddenError,
+        AclError::LimitExceeded => ApiError::DebitLimitError,
+        _ => fallback,
+    };
+
+    log_convert(error.change_context(context))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::storage::types::{
+        Account, AssetInfo, Currency, Multisig, TokenManager, TokenManagerRef, User,
+    };
+
+    pub(crate) async fn register(app_state: &AppState, ua_addr: &str) -> String {
+        let user = User {
+            email: format!("{ua_addr}.example"),
+            name: ua_addr.to_string(),
+            public_key: String::new(),
+            ua_addr: ua_addr.to_string(),
+            jurisdiction: None,
+            previous_keys: Vec::new(),
+            status: UserStatus::Active,
+            closed_at: None,
+        };
+
+        app_state
+            .storage
+            .get_user_interface()
+            .await
+            .unwrap()
+            .create_user(user)
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn token_manager(app_state: &AppState) -> String {
+        let token_manager = TokenManager {
+            token_manager_name: "bank".to_string(),
+            public_key: String::new(),
+            sync_url: None,
+            previous_keys: Vec::new(),
+            status: TokenManagerStatus::Active,
+            deactivated_at: None,
+        };
+
+        app_state
+            .storage
+            .get_token_manager_interface()
+            .await
+            .unwrap()
+            .create_token_manager(token_manager)
+            .await
+            .unwrap()
+    }
+
+    /// Opens a cash account of the user with the token manager, holding `amount` when non-zero.
+    pub(crate) async fn open(
+        app_state: &AppState,
+        user_id: &str,
+        token_manager_id: &str,
+        amount: u64,
+    ) -> String {
+        let account = Account {
+            account_name: "cash".to_string(),
+            token_manager_id: token_manager_id.to_string(),
+            asset_type: AssetType::Cash,
+            token_manager_ref: TokenManagerRef {
+                id: "ref".to_string(),
+                token_manager_name: "bank".to_string(),
+                internal_addr: "internal".to_string(),
+            },
+        };
+
+        let account_id = app_state
+            .storage
+            .get_user_interface()
+            .await
+            .unwrap()
+            .get_account_interface(user_id)
+            .await
+            .unwrap()
+            .create_account(account)
+            .await
+            .unwrap();
+
+        if amount > 0 {
+            let asset_info = AssetInfo::Cash {
+                currency: Currency::USD,
+                amount,
+            };
+
+            ledger::mint(app_state, user_id, &account_id, asset_info)
+                .await
+                .unwrap();
+        }
+
+        account_id
+    }
+
+    pub(crate) async fn balance(app_state: &AppState, user_id: &str, account_id: &str) -> u64 {
+        ledger::balance(app_state, user_id, account_id)
+            .await
+            .unwrap()
+            .amount
+    }
+
+    async fn is_open(app_state: &AppState, user_id: &str, account_id: &str) -> bool {
+        app_state
+            .storage
+            .get_user_interface()
+            .await
+            .unwrap()
+            .get_account_interface(user_id)
+            .await
+            .unwrap()
+            .get_account(account_id)
+            .await
+            .is_ok()
+    }
+
+    fn target(account_id: &str) -> SweepTarget {
+        SweepTarget {
+            ua_addr: "alice@ua".to_string(),
+            account_id: account_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn sweeps_what_is_left_before_closing() {
+        let app_state = AppState::for_tests();
+        let user_id = register(&app_state, "alice@ua").await;
+        let token_manager_id = token_manager(&app_state).await;
+        let closing = open(&app_state, &user_id, &token_manager_id, 50).await;
+        let kept = open(&app_state, &user_id, &token_manager_id, 0).await;
+
+        let swept = close(
+            &app_state,
+            &user_id,
+            &closing,
+            Some(&target(&kept)),
+            ApiError::CloseAccountError,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(swept.len(), 1);
+        assert_eq!(balance(&app_state, &user_id, &kept).await, 50);
+        assert!(!is_open(&app_state, &user_id, &closing).await);
+    }
+
+    #[tokio::test]
+    async fn keeps_an_account_that_still_holds_assets() {
+        let app_state = AppState::for_tests();
+        let user_id = register(&app_state, "alice@ua").await;
+        let token_manager_id = token_manager(&app_state).await;
+        let account_id = open(&app_state, &user_id, &token_manager_id, 50).await;
+
+        let refused = close(
+            &app_state,
+            &user_id,
+            &account_id,
+            None,
+            ApiError::CloseAccountError,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(refused, ApiError::AccountNotEmptyError));
+        assert_eq!(balance(&app_state, &user_id, &account_id).await, 50);
+    }
+
+    #[tokio::test]
+    async fn refuses_sweeping_into_the_account_itself() {
+        let app_state = AppState::for_tests();
+        let user_id = register(&app_state, "alice@ua").await;
+        let token_manager_id = token_manager(&app_state).await;
+        let account_id = open(&app_state, &user_id, &token_manager_id, 50).await;
+
+        let refused = close(
+            &app_state,
+            &user_id,
+            &account_id,
+            Some(&target(&account_id)),
+            ApiError::CloseAccountError,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(refused, ApiError::InvalidSweepTargetError));
+        assert!(is_open(&app_state, &user_id, &account_id).await);
+    }
+
+    #[tokio::test]
+    async fn moves_nothing_when_the_sweep_needs_the_signers() {
+        let app_state = AppState::for_tests();
+        let user_id = register(&app_state, "alice@ua").await;
+        let token_manager_id = token_manager(&app_state).await;
+        let closing = open(&app_state, &user_id, &token_manager_id, 50).await;
+        let kept = open(&app_state, &user_id, &token_manager_id, 0).await;
+
+        let multisig = Multisig {
+            threshold: 10,
+            required: 1,
+            signers: vec!["bob@ua".to_string()],
+            updated_at: 0,
+        };
+
+        app_state
+            .storage
+            .get_multisig_interface()
+            .await
+            .unwrap()
+            .set_multisig(&ledger::account_resource(&user_id, &closing), multisig)
+            .await
+            .unwrap();
+
+        let refused = close(
+            &app_state,
+            &user_id,
+            &closing,
+            Some(&target(&kept)),
+            ApiError::CloseAccountError,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(refused, ApiError::ApprovalRequiredError));
+        assert_eq!(balance(&app_state, &user_id, &closing).await, 50);
+        assert_eq!(balance(&app_state, &user_id, &kept).await, 0);
+    }
 }
@@ -1,28 +1,49 @@
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
-use axum::Json;
+use axum::{Extension, Json};
 use error_stack::ResultExt;
 use serde::Deserialize;
 
-use crate::error::{log_convert, ApiError, ConfigurationError};
+use super::acl_error;
+use crate::app::pagination::PageParams;
+use crate::auth::Principal;
+use crate::error::{ledger_convert, log_convert, policy_convert, ApiError, ConfigurationError};
 use crate::state::AppState;
+use crate::storage::types::{
+    Approval, ApprovalAction, ApprovalStatus, AssetInfo, Permission, PolicyOperation,
+};
+use crate::utils::unix_timestamp;
+use crate::validation::ValidJson;
+use crate::{acl, ledger, multisig, namespace, policy};
 
 mod types;
 
 pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
     let router = axum::Router::new()
-        .route("/", post(create_asset))
+        .route("/", post(create_asset).get(list_assets))
         .route(
             "/:asset_id",
             get(get_asset).put(update_asset).delete(delete_asset),
         )
-        .route("/:asset_id/asset:verb", post(action_asset));
+        .route("/:asset_id/asset:verb", post(action_asset))
+        .route("/:asset_id/proof", get(get_asset_proof))
+        .nest("/:asset_id/permissions", super::permissions::router()?);
 
     Ok(router)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    paths(create_asset, list_assets, delete_asset, action_asset, get_asset_proof),
+    components(schemas(Verb)),
+    nest(
+        (path = "/{asset_id}/permissions", api = super::permissions::AssetApiDoc),
+    ),
+)]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 enum Verb {
     #[serde(rename = ":transfer")]
     Transfer,
@@ -30,46 +51,112 @@ enum Verb {
     // Nominate,
 }
 
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "assets",
+    request_body = types::MintAssetRequest,
+    responses((status = OK, body = types::MintAssetResponse)),
+)]
 async fn create_asset(
     State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path((user_id, account_id)): Path<(String, String)>,
-    axum::Json(asset): axum::Json<types::MintAssetRequest>,
+    ValidJson(asset): ValidJson<types::MintAssetRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let asset_store = app_state
-        .storage
-        .get_user_interface()
-        .await
-        .change_context(ApiError::CreateAssetError)
-        .map_err(log_convert)?
-        .get_account_interface(&user_id)
-        .await
-        .change_context(ApiError::CreateAssetError)
-        .map_err(log_convert)?
-        .get_asset_interface(&account_id)
+    // only the token manager behind the account mints into it, not the holder
+    let token_manager_id = ledger::token_manager_of(&app_state, &user_id, &account_id)
         .await
-        .change_context(ApiError::CreateAssetError)
-        .map_err(log_convert)?;
+        .map_err(|error| ledger_convert(error, ApiError::CreateAssetError))?;
+
+    principal.ensure_token_manager(&token_manager_id)?;
+
+    // the owner may still refuse credits from its token manager
+    acl::check(
+        &app_state,
+        &principal,
+        &user_id,
+        &account_id,
+        None,
+        Permission::Credit,
+        0,
+    )
+    .await
+    .map_err(|error| acl_error(error, ApiError::CreateAssetError))?;
 
     let asset = match asset {
-        types::MintAssetRequest::Cash { currency, amount } => {
-            crate::storage::types::AssetInfo::Cash { currency, amount }
-        }
+        types::MintAssetRequest::Cash { currency, amount } => AssetInfo::Cash { currency, amount },
     };
 
-    let asset_id = asset_store
-        .create_asset(asset.clone())
+    let (asset_id, transaction_id) = ledger::mint(&app_state, &user_id, &account_id, asset.clone())
         .await
-        .change_context(ApiError::CreateAssetError)
-        .map_err(log_convert)?;
+        .map_err(|error| ledger_convert(error, ApiError::CreateAssetError))?;
 
     Ok(axum::response::Json(types::MintAssetResponse {
         asset_id,
         asset_info: asset,
+        transaction_id,
     }))
 
     // Err::<(), _>(ApiError::NotImplemented)
 }
 
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "assets",
+    params(PageParams, types::ListAssetsQuery),
+    responses((status = OK, body = types::ListAssetsResponse)),
+)]
+async fn list_assets(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id)): Path<(String, String)>,
+    Query(page): Query<PageParams>,
+    Query(query): Query<types::ListAssetsQuery>,
+) -> Result<Json<types::ListAssetsResponse>, ApiError> {
+    if !principal.is_auditor() {
+        acl::check(
+            &app_state,
+            &principal,
+            &user_id,
+            &account_id,
+            None,
+            Permission::View,
+            0,
+        )
+        .await
+        .map_err(|error| acl_error(error, ApiError::ListAssetsError))?;
+    }
+
+    let page = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::ListAssetsError)
+        .map_err(log_convert)?
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::ListAssetsError)
+        .map_err(log_convert)?
+        .get_asset_interface(&account_id)
+        .await
+        .change_context(ApiError::ListAssetsError)
+        .map_err(log_convert)?
+        .query_assets(
+            &page.query(query.sort.unwrap_or_default())?,
+            &query.filter()?,
+        )
+        .await
+        .change_context(ApiError::ListAssetsError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::ListAssetsResponse {
+        items: page.items.into_iter().map(Into::into).collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
 async fn get_asset(
     Path((_user_id, _account_id, _asset_id)): Path<(String, String, String)>,
 ) -> impl IntoResponse {
@@ -82,62 +169,271 @@ async fn update_asset(
     // ...
 }
 
+#[utoipa::path(
+    delete,
+    path = "/{asset_id}",
+    tag = "assets",
+    responses((status = OK, body = types::BurnAssetResponse)),
+)]
 async fn delete_asset(
-    Path((_user_id, _account_id, _asset_id)): Path<(String, String, String)>,
-) -> impl IntoResponse {
-    // ...
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id, asset_id)): Path<(String, String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let amount = asset_amount(&app_state, &user_id, &account_id, &asset_id).await?;
+
+    let charge = acl::check(
+        &app_state,
+        &principal,
+        &user_id,
+        &account_id,
+        Some(&asset_id),
+        Permission::Debit,
+        amount,
+    )
+    .await
+    .map_err(|error| acl_error(error, ApiError::BurnAssetError))?;
+
+    // locked assets, e.g. ones earmarked for a swap, can't be burnt from under their holder
+    let burnt = ledger::burn(&app_state, &user_id, &account_id, &asset_id, None).await;
+
+    if burnt.is_err() {
+        acl::refund(&app_state, charge)
+            .await
+            .change_context(ApiError::BurnAssetError)
+            .map_err(log_convert)?;
+    }
+
+    let (asset_info, transaction_id) =
+        burnt.map_err(|error| ledger_convert(error, ApiError::BurnAssetError))?;
+
+    Ok(axum::response::Json(types::BurnAssetResponse {
+        asset_id,
+        asset_info,
+        transaction_id,
+    }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/{asset_id}/asset{verb}",
+    tag = "assets",
+    params(("asset_id" = String, Path), ("verb" = Verb, Path)),
+    request_body = types::VerbRequest,
+    responses((status = OK, body = types::VerbResponse)),
+)]
 async fn action_asset(
     State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
     Path((user_id, account_id, asset_id, verb)): Path<(String, String, String, Verb)>,
-    Json(action): Json<types::VerbRequest>,
+    ValidJson(action): ValidJson<types::VerbRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
     match verb {
         Verb::Transfer => {
-            let my_asset_store = app_state
-                .storage
-                .get_user_interface()
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?
-                .get_account_interface(&user_id)
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?
-                .get_asset_interface(&account_id)
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?;
+            let (peer_ua_addr, peer_account_id) = match action {
+                types::VerbRequest::Ids {
+                    peer_ua_addr,
+                    account_id,
+                } => (peer_ua_addr, account_id),
+                types::VerbRequest::Path { peer_path } => {
+                    let resolved = namespace::resolve_account(&app_state, &peer_path)
+                        .await
+                        .change_context(ApiError::ResolvePathError)
+                        .map_err(log_convert)?;
 
-            let peer_asset_store = app_state
-                .storage
-                .get_user_interface()
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?
-                .get_account_interface_by_ua(&action.peer_ua_addr)
+                    (resolved.ua_addr, resolved.account_id)
+                }
+            };
+
+            let amount = asset_amount(&app_state, &user_id, &account_id, &asset_id).await?;
+
+            let multisig = multisig::setup(&app_state, &user_id, &account_id)
                 .await
                 .change_context(ApiError::ActionAssetError)
                 .map_err(log_convert)?
-                .get_asset_interface(&action.account_id)
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?;
+                .filter(|multisig| amount > multisig.threshold);
 
-            let output = my_asset_store
-                .delete_asset(&asset_id)
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?;
+            let charge = acl::check(
+                &app_state,
+                &principal,
+                &user_id,
+                &account_id,
+                Some(&asset_id),
+                Permission::Transfer,
+                amount,
+            )
+            .await
+            .map_err(|error| acl_error(error, ApiError::ActionAssetError))?;
 
-            let output = peer_asset_store
-                .create_asset(output)
-                .await
-                .change_context(ApiError::ActionAssetError)
-                .map_err(log_convert)?;
+            let admission = match policy::admit_transfer(
+                &app_state,
+                PolicyOperation::Verb,
+                &user_id,
+                &account_id,
+                &peer_ua_addr,
+                &peer_account_id,
+                amount,
+            )
+            .await
+            {
+                Ok(admission) => admission,
+                Err(error) => {
+                    acl::refund(&app_state, charge)
+                        .await
+                        .change_context(ApiError::ActionAssetError)
+                        .map_err(log_convert)?;
+
+                    return Err(policy_convert(error, ApiError::ActionAssetError));
+                }
+            };
+
+            // above the threshold of a treasury account the transfer waits for its signers, and
+            // the policies are evaluated again once they approve it
+            if let Some(multisig) = multisig {
+                admission.release(&app_state).await;
+
+                let now = unix_timestamp();
+                let approval = Approval {
+                    user_id,
+                    account_id,
+                    action: ApprovalAction::Transfer {
+                        asset_id,
+                        amount,
+                        peer_ua_addr,
+                        peer_account_id,
+                        peer_asset_id: None,
+                    },
+                    requested_by: principal.subject.as_ref().map(ToString::to_string),
+                    signers: multisig.signers,
+                    required: multisig.required,
+                    approvals: Vec::new(),
+                    rejections: Vec::new(),
+                    charge,
+                    status: ApprovalStatus::Pending,
+                    created_at: now,
+                    expires_at: now + app_state.config.multisig.approval_ttl_secs,
+                    decided_at: None,
+                    last_error: None,
+                };
+
+                let (approval_id, approval) = multisig::request(&app_state, approval)
+                    .await
+                    .change_context(ApiError::RequestApprovalError)
+                    .map_err(log_convert)?;
+
+                return Ok(axum::response::Json(types::VerbResponse::Pending(
+                    types::PendingApprovalResponse {
+                        approval_id,
+                        status: approval.status,
+                        required: approval.required,
+                        expires_at: approval.expires_at,
+                    },
+                )));
+            }
 
-            Ok(axum::response::Json(output))
+            let moved = ledger::move_asset(
+                &app_state,
+                &user_id,
+                &account_id,
+                &asset_id,
+                &peer_ua_addr,
+                &peer_account_id,
+                None,
+            )
+            .await;
+
+            admission.conclude(&app_state, &moved).await;
+
+            if moved.is_err() {
+                acl::refund(&app_state, charge)
+                    .await
+                    .change_context(ApiError::ActionAssetError)
+                    .map_err(log_convert)?;
+            }
+
+            let peer_asset_id =
+                moved.map_err(|error| ledger_convert(error, ApiError::ActionAssetError))?;
+
+            Ok(axum::response::Json(types::VerbResponse::Transferred(
+                peer_asset_id,
+            )))
         }
     }
 }
+
+#[utoipa::path(
+    get,
+    path = "/{asset_id}/proof",
+    tag = "assets",
+    responses((status = OK, body = types::AssetProofResponse)),
+)]
+async fn get_asset_proof(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id, asset_id)): Path<(String, String, String)>,
+) -> Result<Json<types::AssetProofResponse>, ApiError> {
+    if !principal.is_auditor() {
+        acl::check(
+            &app_state,
+            &principal,
+            &user_id,
+            &account_id,
+            Some(&asset_id),
+            Permission::View,
+            0,
+        )
+        .await
+        .map_err(|error| acl_error(error, ApiError::AssetProofError))?;
+    }
+
+    let user = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::AssetProofError)
+        .map_err(log_convert)?
+        .get_user(&user_id)
+        .await
+        .change_context(ApiError::AssetProofError)
+        .map_err(log_convert)?;
+
+    let (leaf, proof, root) = app_state
+        .merkle
+        .prove(&account_id, &asset_id)
+        .await
+        .filter(|(leaf, _, _)| leaf.ua_addr == user.ua_addr)
+        .ok_or(ApiError::AssetNotCommittedError)?;
+
+    Ok(Json(types::AssetProofResponse { leaf, proof, root }))
+}
+
+/// Value held by the asset, which is what taking it out counts against debit limits.
+async fn asset_amount(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+) -> Result<u64, ApiError> {
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::FetchAssetError)
+        .map_err(log_convert)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(ApiError::FetchAssetError)
+        .map_err(log_convert)?
+        .get_asset_interface(account_id)
+        .await
+        .change_context(ApiError::FetchAssetError)
+        .map_err(log_convert)?
+        .list_assets()
+        .await
+        .change_context(ApiError::FetchAssetError)
+        .map_err(log_convert)?
+        .into_iter()
+        .find(|(id, _)| id == asset_id)
+        .map(|(_, AssetInfo::Cash { amount, .. })| amount)
+        .ok_or(ApiError::FetchAssetError)
+}
@@ -1,24 +1,156 @@
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::storage::types::{AssetInfo, Currency};
+use crate::error::ApiError;
+use crate::merkle::{InclusionProof, MerkleLeaf, SignedRoot};
+use crate::storage::types::{
+    ApprovalStatus, AssetFilter, AssetInfo, AssetSort, AssetType, Currency, Entry,
+};
+use crate::validation::{Validate, Violations};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum MintAssetRequest {
     Cash { currency: Currency, amount: u64 },
     // Property { lat: f64, lon: f64 },
 }
 
-#[derive(Debug, Serialize)]
+impl Validate for MintAssetRequest {
+    fn validate(&self, violations: &mut Violations) {
+        match self {
+            Self::Cash { amount, .. } => {
+                violations.check(*amount > 0, "amount", "must be greater than zero")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListAssetsQuery {
+    /// Key the assets are ordered by, their creation time unless given.
+    pub sort: Option<AssetSort>,
+    pub asset_type: Option<AssetType>,
+    pub currency: Option<Currency>,
+    /// Smallest amount held, inclusive.
+    pub min_amount: Option<u64>,
+    /// Largest amount held, inclusive.
+    pub max_amount: Option<u64>,
+    /// Assets created strictly after this unix timestamp.
+    pub created_after: Option<u64>,
+}
+
+impl ListAssetsQuery {
+    pub fn filter(&self) -> Result<AssetFilter, ApiError> {
+        let mut violations = Violations::default();
+        violations.check(
+            self.min_amount
+                .zip(self.max_amount)
+                .is_none_or(|(min_amount, max_amount)| min_amount <= max_amount),
+            "min_amount",
+            "must not be greater than max_amount",
+        );
+        violations.into_result()?;
+
+        Ok(AssetFilter {
+            asset_type: self.asset_type.clone(),
+            currency: self.currency.clone(),
+            min_amount: self.min_amount,
+            max_amount: self.max_amount,
+            created_after: self.created_after,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AssetResponse {
+    pub asset_id: String,
+    pub asset_info: AssetInfo,
+    pub created_at: u64,
+}
+
+impl From<Entry<AssetInfo>> for AssetResponse {
+    fn from(entry: Entry<AssetInfo>) -> Self {
+        Self {
+            asset_id: entry.id,
+            asset_info: entry.item,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListAssetsResponse {
+    pub items: Vec<AssetResponse>,
+    /// Cursor of the next page, missing on the last one.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub struct MintAssetResponse {
     pub asset_id: String,
     pub asset_info: AssetInfo,
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BurnAssetResponse {
+    pub asset_id: String,
+    pub asset_info: AssetInfo,
+    pub transaction_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AssetProofResponse {
+    pub leaf: MerkleLeaf,
+    pub proof: InclusionProof,
+    pub root: SignedRoot,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum VerbRequest {
+    /// Peer account addressed through its path in the namespace.
+    Path { peer_path: String },
+    Ids {
+        peer_ua_addr: String,
+        account_id: String,
+    },
+}
+
+impl Validate for VerbRequest {
+    fn validate(&self, violations: &mut Violations) {
+        match self {
+            Self::Path { peer_path } => violations.check(
+                peer_path.starts_with('/'),
+                "peer_path",
+                "must be an absolute path",
+            ),
+            Self::Ids {
+                peer_ua_addr,
+                account_id,
+            } => {
+                violations.non_empty(peer_ua_addr, "peer_ua_addr");
+                violations.non_empty(account_id, "account_id");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum VerbResponse {
+    /// Id of the asset on the peer side.
+    Transferred(String),
+    /// Transfer held back until the signers of the account approve it.
+    Pending(PendingApprovalResponse),
 }
 
-#[derive(Debug, Deserialize)]
-pub struct VerbRequest {
-    pub peer_ua_addr: String,
-    pub account_id: String,
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PendingApprovalResponse {
+    pub approval_id: String,
+    pub status: ApprovalStatus,
+    pub required: u32,
+    pub expires_at: u64,
 }
 
 //
@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+
+use axum::extract::{Path, State};
+use axum::routing::get;
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::ledger;
+use crate::multisig;
+use crate::state::AppState;
+use crate::storage::types::{Approval, ApprovalAction, ApprovalStatus, Multisig};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+/// Signers whose approval transfers out of a treasury account wait for.
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new().route(
+        "/",
+        get(get_multisig).put(set_multisig).delete(delete_multisig),
+    );
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(get_multisig, set_multisig, delete_multisig))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "multisig",
+    responses((status = OK, body = types::MultisigResponse)),
+)]
+async fn get_multisig(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id)): Path<(String, String)>,
+) -> Result<Json<types::MultisigResponse>, ApiError> {
+    (principal.is_auditor() || principal.acts_for(&user_id))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    let multisig = multisig::setup(&app_state, &user_id, &account_id)
+        .await
+        .change_context(ApiError::FetchMultisigError)
+        .map_err(log_convert)?
+        .ok_or(ApiError::FetchMultisigError)?;
+
+    Ok(Json(multisig.into()))
+}
+
+/// Applies the setup right away when the account has none yet or it only makes transfers harder,
+/// otherwise holds it back until the current signers approve it.
+#[utoipa::path(
+    put,
+    path = "",
+    tag = "multisig",
+    responses((status = OK, body = types::MultisigChangeResponse)),
+)]
+async fn set_multisig(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id)): Path<(String, String)>,
+    Json(req): Json<types::MultisigRequest>,
+) -> Result<Json<types::MultisigChangeResponse>, ApiError> {
+    principal.ensure_user(&user_id)?;
+
+    let distinct = req.signers.iter().collect::<HashSet<_>>().len() == req.signers.len();
+
+    (distinct && req.required > 0 && req.required as usize <= req.signers.len())
+        .then_some(())
+        .ok_or(ApiError::InvalidMultisigError)?;
+
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::UpdateMultisigError)
+        .map_err(log_convert)?;
+
+    user_interface
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::UpdateMultisigError)
+        .map_err(log_convert)?
+        .get_account(&account_id)
+        .await
+        .change_context(ApiError::UpdateMultisigError)
+        .map_err(log_convert)?;
+
+    for signer in &req.signers {
+        user_interface
+            .is_valid_ua_addr(signer)
+            .await
+            .change_context(ApiError::UpdateMultisigError)
+            .map_err(log_convert)?
+            .then_some(())
+            .ok_or(ApiError::InvalidMultisigError)?;
+    }
+
+    // approvals already pending keep the signers they were requested with
+    let multisig = Multisig {
+        threshold: req.threshold,
+        required: req.required,
+        signers: req.signers,
+        updated_at: unix_timestamp(),
+    };
+
+    let current = multisig::setup(&app_state, &user_id, &account_id)
+        .await
+        .change_context(ApiError::UpdateMultisigError)
+        .map_err(log_convert)?;
+
+    if let Some(current) = current.filter(|current| multisig::loosens(current, Some(&multisig))) {
+        return request_change(
+            &app_state,
+            &principal,
+            user_id,
+            account_id,
+            current,
+            Some(multisig),
+        )
+        .await;
+    }
+
+    app_state
+        .storage
+        .get_multisig_interface()
+        .await
+        .change_context(ApiError::UpdateMultisigError)
+        .map_err(log_convert)?
+        .set_multisig(
+            &ledger::account_resource(&user_id, &account_id),
+            multisig.clone(),
+        )
+        .await
+        .change_context(ApiError::UpdateMultisigError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::MultisigChangeResponse::Applied(
+        multisig.into(),
+    )))
+}
+
+/// Dropping the signers leaves transfers out of the account unchecked, so it waits for the
+/// current signers to approve it.
+#[utoipa::path(
+    delete,
+    path = "",
+    tag = "multisig",
+    responses((status = OK, body = types::MultisigChangeResponse)),
+)]
+async fn delete_multisig(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((user_id, account_id)): Path<(String, String)>,
+) -> Result<Json<types::MultisigChangeResponse>, ApiError> {
+    principal.ensure_user(&user_id)?;
+
+    let current = multisig::setup(&app_state, &user_id, &account_id)
+        .await
+        .change_context(ApiError::FetchMultisigError)
+        .map_err(log_convert)?
+        .ok_or(ApiError::FetchMultisigError)?;
+
+    request_change(&app_state, &principal, user_id, account_id, current, None).await
+}
+
+async fn request_change(
+    app_state: &AppState,
+    principal: &Principal,
+    user_id: String,
+    account_id: String,
+    current: Multisig,
+    multisig: Option<Multisig>,
+) -> Result<Json<types::MultisigChangeResponse>, ApiError> {
+    let now = unix_timestamp();
+    let approval = Approval {
+        user_id,
+        account_id,
+        action: ApprovalAction::Setup { multisig },
+        requested_by: principal.subject.as_ref().map(ToString::to_string),
+        signers: current.signers,
+        required: current.required,
+        approvals: Vec::new(),
+        rejections: Vec::new(),
+        charge: None,
+        status: ApprovalStatus::Pending,
+        created_at: now,
+        expires_at: now + app_state.config.multisig.approval_ttl_secs,
+        decided_at: None,
+        last_error: None,
+    };
+
+    let (approval_id, approval) = multisig::request(app_state, approval)
+        .await
+        .change_context(ApiError::RequestApprovalError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::MultisigChangeResponse::Pending(
+        types::PendingChangeResponse {
+            approval_id,
+            status: approval.status,
+            required: approval.required,
+            expires_at: approval.expires_at,
+        },
+    )))
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::storage::types::{ApprovalStatus, Multisig};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MultisigRequest {
+    /// Transfers moving more than this wait for the signers.
+    pub threshold: u64,
+    pub required: u32,
+    /// Universal addresses of the signers.
+    pub signers: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MultisigResponse {
+    pub threshold: u64,
+    pub required: u32,
+    pub signers: Vec<String>,
+    pub updated_at: u64,
+}
+
+impl From<Multisig> for MultisigResponse {
+    fn from(multisig: Multisig) -> Self {
+        Self {
+            threshold: multisig.threshold,
+            required: multisig.required,
+            signers: multisig.signers,
+            updated_at: multisig.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum MultisigChangeResponse {
+    /// Setup as it applies from now on.
+    Applied(MultisigResponse),
+    /// Change held back until the current signers of the account approve it.
+    Pending(PendingChangeResponse),
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PendingChangeResponse {
+    pub approval_id: String,
+    pub status: ApprovalStatus,
+    pub required: u32,
+    pub expires_at: u64,
+}
@@ -0,0 +1,344 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, put};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::acl;
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::ledger;
+use crate::state::AppState;
+use crate::storage::types::{AccessGrant, Mode};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+/// Permissions of an account, or of an asset when nested under one.
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", get(get_permissions).put(set_mode))
+        .route("/grants/:ua_addr", put(put_grant).delete(delete_grant));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(get_permissions, set_mode, put_grant, delete_grant))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "permissions",
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+async fn get_permissions(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(path): Path<types::PermissionsPath>,
+) -> Result<Json<types::PermissionsResponse>, ApiError> {
+    (principal.is_auditor() || principal.acts_for(&path.user_id))
+        .then_some(())
+        .ok_or(ApiError::ForbiddenError)?;
+
+    ensure_exists(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await?;
+
+    let effective = acl::effective(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await
+    .change_context(ApiError::FetchPermissionsError)
+    .map_err(log_convert)?;
+
+    Ok(Json(effective.into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "",
+    tag = "permissions",
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+async fn set_mode(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(path): Path<types::PermissionsPath>,
+    Json(req): Json<types::ModeRequest>,
+) -> Result<Json<types::PermissionsResponse>, ApiError> {
+    principal.ensure_user(&path.user_id)?;
+
+    let mode = Mode {
+        owner: acl::parse_bits(&req.owner).ok_or(ApiError::InvalidPermissionsError)?,
+        group: acl::parse_bits(&req.group).ok_or(ApiError::InvalidPermissionsError)?,
+        other: acl::parse_bits(&req.other).ok_or(ApiError::InvalidPermissionsError)?,
+    };
+
+    ensure_exists(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await?;
+
+    let resource = resource(&path.user_id, &path.account_id, path.asset_id.as_deref());
+
+    app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?
+        .set_mode(&resource, mode)
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?;
+
+    fetch(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await
+}
+
+#[utoipa::path(
+    put,
+    path = "/grants/{ua_addr}",
+    tag = "permissions",
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+async fn put_grant(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(path): Path<types::GrantPath>,
+    Json(req): Json<types::GrantRequest>,
+) -> Result<Json<types::PermissionsResponse>, ApiError> {
+    principal.ensure_user(&path.user_id)?;
+
+    let permissions = acl::parse_bits(&req.permissions)
+        .filter(|bits| *bits != 0)
+        .ok_or(ApiError::InvalidPermissionsError)?;
+
+    ensure_exists(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await?;
+
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?
+        .is_valid_ua_addr(&path.ua_addr)
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?
+        .then_some(())
+        .ok_or(ApiError::InvalidPermissionsError)?;
+
+    // a grant on an asset without permissions of its own starts from those of its account
+    let effective = acl::effective(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await
+    .change_context(ApiError::UpdatePermissionsError)
+    .map_err(log_convert)?;
+
+    let grant = AccessGrant {
+        permissions,
+        debit_limit: req.debit_limit,
+        debited: 0,
+        expires_at: req.expires_at,
+        granted_at: unix_timestamp(),
+    };
+
+    app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?
+        .put_grant(
+            &resource(&path.user_id, &path.account_id, path.asset_id.as_deref()),
+            effective.acl.mode,
+            &path.ua_addr,
+            grant,
+        )
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?;
+
+    fetch(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/grants/{ua_addr}",
+    tag = "permissions",
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+async fn delete_grant(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(path): Path<types::GrantPath>,
+) -> Result<Json<types::PermissionsResponse>, ApiError> {
+    principal.ensure_user(&path.user_id)?;
+
+    app_state
+        .storage
+        .get_acl_interface()
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?
+        .delete_grant(
+            &resource(&path.user_id, &path.account_id, path.asset_id.as_deref()),
+            &path.ua_addr,
+        )
+        .await
+        .change_context(ApiError::UpdatePermissionsError)
+        .map_err(log_convert)?;
+
+    fetch(
+        &app_state,
+        &path.user_id,
+        &path.account_id,
+        path.asset_id.as_deref(),
+    )
+    .await
+}
+
+fn resource(user_id: &str, account_id: &str, asset_id: Option<&str>) -> String {
+    match asset_id {
+        Some(asset_id) => ledger::asset_resource(user_id, account_id, asset_id),
+        None => ledger::account_resource(user_id, account_id),
+    }
+}
+
+async fn fetch(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: Option<&str>,
+) -> Result<Json<types::PermissionsResponse>, ApiError> {
+    let effective = acl::effective(app_state, user_id, account_id, asset_id)
+        .await
+        .change_context(ApiError::FetchPermissionsError)
+        .map_err(log_convert)?;
+
+    Ok(Json(effective.into()))
+}
+
+async fn ensure_exists(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: Option<&str>,
+) -> Result<(), ApiError> {
+    let account_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::FetchPermissionsError)
+        .map_err(log_convert)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(ApiError::FetchPermissionsError)
+        .map_err(log_convert)?;
+
+    account_interface
+        .get_account(account_id)
+        .await
+        .change_context(ApiError::FetchPermissionsError)
+        .map_err(log_convert)?;
+
+    let Some(asset_id) = asset_id else {
+        return Ok(());
+    };
+
+    account_interface
+        .get_asset_interface(account_id)
+        .await
+        .change_context(ApiError::FetchPermissionsError)
+        .map_err(log_convert)?
+        .list_assets()
+        .await
+        .change_context(ApiError::FetchPermissionsError)
+        .map_err(log_convert)?
+        .iter()
+        .any(|(id, _)| id == asset_id)
+        .then_some(())
+        .ok_or(ApiError::FetchPermissionsError)
+}
+
+// Routes of asset permissions share the handlers above, the following only describe them.
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    get_asset_permissions,
+    set_asset_mode,
+    put_asset_grant,
+    delete_asset_grant
+))]
+pub struct AssetApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "permissions",
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+#[allow(dead_code)]
+fn get_asset_permissions() {}
+
+#[utoipa::path(
+    put,
+    path = "",
+    tag = "permissions",
+    request_body = types::ModeRequest,
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+#[allow(dead_code)]
+fn set_asset_mode() {}
+
+#[utoipa::path(
+    put,
+    path = "/grants/{ua_addr}",
+    tag = "permissions",
+    request_body = types::GrantRequest,
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+#[allow(dead_code)]
+fn put_asset_grant() {}
+
+#[utoipa::path(
+    delete,
+    path = "/grants/{ua_addr}",
+    tag = "permissions",
+    responses((status = OK, body = types::PermissionsResponse)),
+)]
+#[allow(dead_code)]
+fn delete_asset_grant() {}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::acl::{self, Effective};
+use crate::storage::types::AccessGrant;
+
+#[derive(Debug, Deserialize)]
+pub struct PermissionsPath {
+    pub user_id: String,
+    pub account_id: String,
+    /// Set when the permissions are those of a single asset rather than the account.
+    pub asset_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GrantPath {
+    pub user_id: String,
+    pub account_id: String,
+    pub asset_id: Option<String>,
+    pub ua_addr: String,
+}
+
+/// Permission bits of each class, written as `vdct` with `-` for what isn't given.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ModeRequest {
+    pub owner: String,
+    pub group: String,
+    pub other: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GrantRequest {
+    pub permissions: String,
+    pub debit_limit: Option<u64>,
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GrantResponse {
+    pub ua_addr: String,
+    pub permissions: String,
+    pub debit_limit: Option<u64>,
+    pub debited: u64,
+    pub expires_at: Option<u64>,
+    pub granted_at: u64,
+}
+
+impl From<(String, AccessGrant)> for GrantResponse {
+    fn from((ua_addr, grant): (String, AccessGrant)) -> Self {
+        Self {
+            ua_addr,
+            permissions: acl::format_bits(grant.permissions),
+            debit_limit: grant.debit_limit,
+            debited: grant.debited,
+            expires_at: grant.expires_at,
+            granted_at: grant.granted_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PermissionsResponse {
+    pub owner: String,
+    pub group: String,
+    pub other: String,
+    /// Whether an asset follows the permissions of its account.
+    pub inherited: bool,
+    pub grants: Vec<GrantResponse>,
+}
+
+impl From<Effective> for PermissionsResponse {
+    fn from(value: Effective) -> Self {
+        let mut grants: Vec<GrantResponse> = value.acl.grants.into_iter().map(Into::into).collect();
+        grants.sort_by(|a, b| a.ua_addr.cmp(&b.ua_addr));
+
+        Self {
+            owner: acl::format_bits(value.acl.mode.owner),
+            group: acl::format_bits(value.acl.mode.group),
+            other: acl::format_bits(value.acl.mode.other),
+            inherited: value.inherited,
+            grants,
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::storage::types::{Account, AssetType, TokenManagerRef, TotalAssets};
+use crate::storage::types::{
+    Account, AccountFilter, AccountSort, AssetType, Entry, TokenManagerRef, TotalAssets,
+};
+use crate::validation::{Validate, Violations};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateAccountRequest {
     pub token_manager_id: String,
     pub account_name: String,
@@ -10,6 +14,22 @@ pub struct CreateAccountRequest {
     pub token_manager_ref: TokenManagerRef,
 }
 
+impl Validate for CreateAccountRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.non_empty(&self.token_manager_id, "token_manager_id");
+        violations.non_empty(&self.account_name, "account_name");
+        violations.non_empty(&self.token_manager_ref.id, "token_manager_ref.id");
+        violations.non_empty(
+            &self.token_manager_ref.token_manager_name,
+            "token_manager_ref.token_manager_name",
+        );
+        violations.non_empty(
+            &self.token_manager_ref.internal_addr,
+            "token_manager_ref.internal_addr",
+        );
+    }
+}
+
 impl From<CreateAccountRequest> for crate::storage::types::Account {
     fn from(value: CreateAccountRequest) -> Self {
         Self {
@@ -21,7 +41,7 @@ impl From<CreateAccountRequest> for crate::storage::types::Account {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateAccountResponse {
     pub account_id: String,
     pub token_manager_id: String,
@@ -29,8 +49,109 @@ pub struct CreateAccountResponse {
     pub asset_type: AssetType,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct FetchAccountResponse {
     pub account: Account,
     pub total_assets: TotalAssets,
 }
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ListAccountsQuery {
+    /// Key the accounts are ordered by, their creation time unless given.
+    pub sort: Option<AccountSort>,
+    pub asset_type: Option<AssetType>,
+    pub token_manager_id: Option<String>,
+    /// Accounts created strictly after this unix timestamp.
+    pub created_after: Option<u64>,
+}
+
+impl ListAccountsQuery {
+    pub fn filter(&self) -> AccountFilter {
+        AccountFilter {
+            asset_type: self.asset_type.clone(),
+            token_manager_id: self.token_manager_id.clone(),
+            created_after: self.created_after,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountResponse {
+    pub account_id: String,
+    pub account_name: String,
+    pub token_manager_id: String,
+    pub asset_type: AssetType,
+    pub token_manager_ref: TokenManagerRef,
+    pub total_assets: TotalAssets,
+    pub created_at: u64,
+}
+
+impl From<(Entry<Account>, TotalAssets)> for AccountResponse {
+    fn from((entry, total_assets): (Entry<Account>, TotalAssets)) -> Self {
+        Self {
+            account_id: entry.id,
+            account_name: entry.item.account_name,
+            token_manager_id: entry.item.token_manager_id,
+            asset_type: entry.item.asset_type,
+            token_manager_ref: entry.item.token_manager_ref,
+            total_assets,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListAccountsResponse {
+    pub items: Vec<AccountResponse>,
+    /// Cursor of the next page, missing on the last one.
+    pub next_cursor: Option<String>,
+}
+
+/// Changes to the account, fields that are left out stay as they are.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateAccountRequest {
+    pub account_name: Option<String>,
+    /// Reference the token manager keeps the account under.
+    pub token_manager_ref: Option<TokenManagerRef>,
+}
+
+impl Validate for UpdateAccountRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            self.account_name.is_some() || self.token_manager_ref.is_some(),
+            ".",
+            "must change at least one field",
+        );
+
+        if let Some(account_name) = &self.account_name {
+            violations.non_empty(account_name, "account_name");
+        }
+
+        if let Some(token_manager_ref) = &self.token_manager_ref {
+            violations.non_empty(&token_manager_ref.id, "token_manager_ref.id");
+            violations.non_empty(
+                &token_manager_ref.token_manager_name,
+                "token_manager_ref.token_manager_name",
+            );
+            violations.non_empty(
+                &token_manager_ref.internal_addr,
+                "token_manager_ref.internal_addr",
+            );
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CloseAccountQuery {
+    /// Another account of the user to move the remaining assets to. Without it, accounts still
+    /// holding assets aren't closed.
+    pub sweep_to: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CloseAccountResponse {
+    pub account_id: String,
+    pub swept_to: Option<String>,
+    /// Ids of the assets created in the account swept to.
+    pub swept_asset_ids: Vec<String>,
+}
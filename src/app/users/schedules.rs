@@ -0,0 +1,298 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::Json;
+use error_stack::ResultExt;
+
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::schedule;
+use crate::state::AppState;
+use crate::storage::types::{Schedule, ScheduleStatus};
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(create_schedule).get(list_schedules))
+        .route(
+            "/:schedule_id",
+            get(get_schedule)
+                .put(update_schedule)
+                .delete(delete_schedule),
+        );
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(
+    create_schedule,
+    list_schedules,
+    get_schedule,
+    update_schedule,
+    delete_schedule
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "schedules",
+    responses((status = OK, body = types::ScheduleResponse)),
+)]
+async fn create_schedule(
+    State(app_state): State<AppState>,
+    Path(user_id): Path<String>,
+    Json(req): Json<types::CreateScheduleRequest>,
+) -> Result<Json<types::ScheduleResponse>, ApiError> {
+    let now = unix_timestamp();
+    let start_at = req.start_at.unwrap_or(now);
+
+    (req.amount > 0 && start_at >= now)
+        .then_some(())
+        .ok_or(ApiError::InvalidScheduleError)?;
+
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::CreateScheduleError)
+        .map_err(log_convert)?;
+
+    user_interface
+        .get_account_interface(&user_id)
+        .await
+        .change_context(ApiError::CreateScheduleError)
+        .map_err(log_convert)?
+        .get_account(&req.account_id)
+        .await
+        .change_context(ApiError::InvalidScheduleError)
+        .map_err(log_convert)?;
+
+    user_interface
+        .get_account_interface_by_ua(&req.peer_ua_addr)
+        .await
+        .change_context(ApiError::InvalidScheduleError)
+        .map_err(log_convert)?
+        .get_account(&req.peer_account_id)
+        .await
+        .change_context(ApiError::InvalidScheduleError)
+        .map_err(log_convert)?;
+
+    let schedule = Schedule {
+        user_id,
+        account_id: req.account_id,
+        peer_ua_addr: req.peer_ua_addr,
+        peer_account_id: req.peer_account_id,
+        amount: req.amount,
+        cadence: req.cadence,
+        start_at,
+        occurrence: 0,
+        next_run_at: Some(start_at),
+        status: ScheduleStatus::Active,
+        failures: 0,
+        history: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let schedule_id = app_state
+        .storage
+        .get_schedule_interface()
+        .await
+        .change_context(ApiError::CreateScheduleError)
+        .map_err(log_convert)?
+        .create_schedule(schedule.clone())
+        .await
+        .change_context(ApiError::CreateScheduleError)
+        .map_err(log_convert)?;
+
+    Ok(Json((schedule_id, schedule).into()))
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "schedules",
+    responses((status = OK, body = Vec<types::ScheduleResponse>)),
+)]
+async fn list_schedules(
+    State(app_state): State<AppState>,
+    Path(user_id): Path<String>,
+) -> Result<Json<Vec<types::ScheduleResponse>>, ApiError> {
+    let schedules = app_state
+        .storage
+        .get_schedule_interface()
+        .await
+        .change_context(ApiError::FetchScheduleError)
+        .map_err(log_convert)?
+        .list_schedules(&user_id)
+        .await
+        .change_context(ApiError::FetchScheduleError)
+        .map_err(log_convert)?;
+
+    Ok(Json(schedules.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{schedule_id}",
+    tag = "schedules",
+    responses((status = OK, body = types::ScheduleResponse)),
+)]
+async fn get_schedule(
+    State(app_state): State<AppState>,
+    Path((user_id, schedule_id)): Path<(String, String)>,
+) -> Result<Json<types::ScheduleResponse>, ApiError> {
+    let schedule = owned_schedule(&app_state, &user_id, &schedule_id).await?;
+
+    Ok(Json((schedule_id, schedule).into()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{schedule_id}",
+    tag = "schedules",
+    responses((status = OK, body = types::ScheduleResponse)),
+)]
+async fn update_schedule(
+    State(app_state): State<AppState>,
+    Path((user_id, schedule_id)): Path<(String, String)>,
+    Json(req): Json<types::UpdateScheduleRequest>,
+) -> Result<Json<types::ScheduleResponse>, ApiError> {
+    let guard = schedule::enter(&app_state, &schedule_id)
+        .await
+        .change_context(ApiError::ScheduleBusyError)
+        .map_err(log_convert)?;
+    let output = change_schedule(&app_state, &user_id, &schedule_id, req).await;
+    schedule::leave(&app_state, &schedule_id, &guard)
+        .await
+        .change_context(ApiError::UpdateScheduleError)
+        .map_err(log_convert)?;
+
+    Ok(Json((schedule_id, output?).into()))
+}
+
+async fn change_schedule(
+    app_state: &AppState,
+    user_id: &str,
+    schedule_id: &str,
+    req: types::UpdateScheduleRequest,
+) -> Result<Schedule, ApiError> {
+    let mut schedule = owned_schedule(app_state, user_id, schedule_id).await?;
+    let now = unix_timestamp();
+
+    let valid = req.amount.is_none_or(|amount| amount > 0)
+        && req.start_at.is_none_or(|start_at| start_at >= now)
+        && req
+            .status
+            .is_none_or(|status| status != ScheduleStatus::Completed);
+
+    valid.then_some(()).ok_or(ApiError::InvalidScheduleError)?;
+
+    if let Some(amount) = req.amount {
+        schedule.amount = amount;
+    }
+
+    // a new timing restarts the schedule from its first occurrence
+    if req.cadence.is_some() || req.start_at.is_some() {
+        schedule.cadence = req.cadence.unwrap_or(schedule.cadence);
+        schedule.start_at = req.start_at.unwrap_or(schedule.start_at);
+        schedule.occurrence = 0;
+        schedule.next_run_at = Some(schedule.start_at);
+        schedule.status = ScheduleStatus::Active;
+
+        if schedule.start_at <= now {
+            schedule::advance(&mut schedule, now);
+        }
+    }
+
+    if let Some(status) = req.status {
+        (schedule.status != ScheduleStatus::Completed)
+            .then_some(())
+            .ok_or(ApiError::InvalidScheduleError)?;
+
+        schedule.status = status;
+    }
+
+    schedule.updated_at = now;
+
+    app_state
+        .storage
+        .get_schedule_interface()
+        .await
+        .change_context(ApiError::UpdateScheduleError)
+        .map_err(log_convert)?
+        .update_schedule(schedule_id, schedule.clone())
+        .await
+        .change_context(ApiError::UpdateScheduleError)
+        .map_err(log_convert)?;
+
+    Ok(schedule)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{schedule_id}",
+    tag = "schedules",
+    responses((status = OK, body = types::ScheduleResponse)),
+)]
+async fn delete_schedule(
+    State(app_state): State<AppState>,
+    Path((user_id, schedule_id)): Path<(String, String)>,
+) -> Result<Json<types::ScheduleResponse>, ApiError> {
+    let guard = schedule::enter(&app_state, &schedule_id)
+        .await
+        .change_context(ApiError::ScheduleBusyError)
+        .map_err(log_convert)?;
+    let output = remove_schedule(&app_state, &user_id, &schedule_id).await;
+    schedule::leave(&app_state, &schedule_id, &guard)
+        .await
+        .change_context(ApiError::DeleteScheduleError)
+        .map_err(log_convert)?;
+
+    Ok(Json((schedule_id, output?).into()))
+}
+
+async fn remove_schedule(
+    app_state: &AppState,
+    user_id: &str,
+    schedule_id: &str,
+) -> Result<Schedule, ApiError> {
+    owned_schedule(app_state, user_id, schedule_id).await?;
+
+    app_state
+        .storage
+        .get_schedule_interface()
+        .await
+        .change_context(ApiError::DeleteScheduleError)
+        .map_err(log_convert)?
+        .delete_schedule(schedule_id)
+        .await
+        .change_context(ApiError::DeleteScheduleError)
+        .map_err(log_convert)
+}
+
+/// Fetches the schedule, hiding schedules of other users.
+async fn owned_schedule(
+    app_state: &AppState,
+    user_id: &str,
+    schedule_id: &str,
+) -> Result<Schedule, ApiError> {
+    let schedule = app_state
+        .storage
+        .get_schedule_interface()
+        .await
+        .change_context(ApiError::FetchScheduleError)
+        .map_err(log_convert)?
+        .get_schedule(schedule_id)
+        .await
+        .change_context(ApiError::FetchScheduleError)
+        .map_err(log_convert)?;
+
+    (schedule.user_id == user_id)
+        .then_some(())
+        .ok_or(ApiError::FetchScheduleError)?;
+
+    Ok(schedule)
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::storage::types::{Cadence, Schedule, ScheduleRun, ScheduleStatus};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateScheduleRequest {
+    pub account_id: String,
+    pub peer_ua_addr: String,
+    pub peer_account_id: String,
+    pub amount: u64,
+    pub cadence: Cadence,
+    /// Time of the first run, right away when left out.
+    pub start_at: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateScheduleRequest {
+    pub amount: Option<u64>,
+    pub cadence: Option<Cadence>,
+    pub start_at: Option<u64>,
+    /// Either `active` or `paused`.
+    pub status: Option<ScheduleStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScheduleResponse {
+    pub schedule_id: String,
+    pub account_id: String,
+    pub peer_ua_addr: String,
+    pub peer_account_id: String,
+    pub amount: u64,
+    pub cadence: Cadence,
+    pub start_at: u64,
+    pub next_run_at: Option<u64>,
+    pub status: ScheduleStatus,
+    pub failures: u32,
+    pub history: Vec<ScheduleRun>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl From<(String, Schedule)> for ScheduleResponse {
+    fn from((schedule_id, schedule): (String, Schedule)) -> Self {
+        Self {
+            schedule_id,
+            account_id: schedule.account_id,
+            peer_ua_addr: schedule.peer_ua_addr,
+            peer_account_id: schedule.peer_account_id,
+            amount: schedule.amount,
+            cadence: schedule.cadence,
+            start_at: schedule.start_at,
+            next_run_at: schedule.next_run_at,
+            status: schedule.status,
+            failures: schedule.failures,
+            history: schedule.history,
+            created_at: schedule.created_at,
+            updated_at: schedule.updated_at,
+        }
+    }
+}
@@ -1,22 +1,151 @@
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize, Debug, Clone)]
+use crate::error::ApiError;
+use crate::storage::types::{RetiredKey, UserStatus};
+use crate::validation::{self, Validate, Violations};
+
+#[derive(Deserialize, Debug, Clone, ToSchema)]
 pub(super) struct CreateUserRequest {
     pub email: String,
     pub name: String,
     pub public_key: String,
     pub ua_addr: String,
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
+}
+
+impl Validate for CreateUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            validation::is_email(&self.email),
+            "email",
+            "must be an email address",
+        );
+        violations.non_empty(&self.name, "name");
+        violations.check(
+            crate::auth::verifying_key(&self.public_key).is_some(),
+            "public_key",
+            "must be a hex encoded ed25519 public key",
+        );
+        violations.check(
+            !self.ua_addr.is_empty() && !self.ua_addr.chars().any(char::is_whitespace),
+            "ua_addr",
+            "must be non-empty without whitespace",
+        );
+
+        if let Some(jurisdiction) = &self.jurisdiction {
+            violations.non_empty(jurisdiction, "jurisdiction");
+        }
+    }
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub(super) struct GetUserResponse {
     ua_addr: String,
     pub email: String,
     pub name: String,
+    pub jurisdiction: Option<String>,
     // pub public_key: String,
+    pub status: UserStatus,
+    pub closed_at: Option<u64>,
+}
+
+/// Fields of the user to change, at least one of them. A new public key replaces the current one,
+/// which is kept to verify what it signed.
+#[derive(Deserialize, Debug, Clone, ToSchema)]
+pub(super) struct UpdateUserRequest {
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub public_key: Option<String>,
+}
+
+impl Validate for UpdateUserRequest {
+    fn validate(&self, violations: &mut Violations) {
+        violations.check(
+            self.email.is_some() || self.name.is_some() || self.public_key.is_some(),
+            ".",
+            "must change at least one field",
+        );
+
+        if let Some(email) = &self.email {
+            violations.check(
+                validation::is_email(email),
+                "email",
+                "must be an email address",
+            );
+        }
+        if let Some(name) = &self.name {
+            violations.non_empty(name, "name");
+        }
+        if let Some(public_key) = &self.public_key {
+            violations.check(
+                crate::auth::verifying_key(public_key).is_some(),
+                "public_key",
+                "must be a hex encoded ed25519 public key",
+            );
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub(super) struct UserKeysQuery {
+    /// Unix timestamp to tell the key the user signed with at that time.
+    pub at: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub(super) struct UserKeysResponse {
+    pub public_key: String,
+    /// Keys the user rotated away from, oldest first.
+    pub previous_keys: Vec<RetiredKey>,
+    /// Key in use at the time asked for with `at`.
+    pub key_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub(super) struct CloseUserQuery {
+    /// Unique address of the user to sweep the remaining assets to, along with `sweep_to_account`.
+    /// Without them, users whose accounts still hold assets aren't closed.
+    pub sweep_to_ua: Option<String>,
+    /// Account of that user receiving the assets.
+    pub sweep_to_account: Option<String>,
+}
+
+impl CloseUserQuery {
+    /// Address and account assets are swept to, when given.
+    pub fn target(&self) -> Result<Option<(String, String)>, ApiError> {
+        let mut violations = Violations::default();
+        violations.check(
+            self.sweep_to_ua.is_some() == self.sweep_to_account.is_some(),
+            "sweep_to_account",
+            "must be given along with sweep_to_ua",
+        );
+        violations.into_result()?;
+
+        Ok(self.sweep_to_ua.clone().zip(self.sweep_to_account.clone()))
+    }
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub(super) struct ClosedAccount {
+    pub account_id: String,
+    /// Ids of the assets created in the account swept to.
+    pub swept_asset_ids: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub(super) struct CloseUserResponse {
+    pub user_id: String,
+    pub ua_addr: String,
+    pub closed_at: u64,
+    pub closed_accounts: Vec<ClosedAccount>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub(super) struct CreateUserResponse {
     pub user_id: String,
     pub ua_addr: String,
@@ -30,6 +159,10 @@ impl From<CreateUserRequest> for crate::storage::types::User {
             name: value.name,
             public_key: value.public_key,
             ua_addr: value.ua_addr,
+            jurisdiction: value.jurisdiction,
+            previous_keys: Vec::new(),
+            status: Default::default(),
+            closed_at: None,
         }
     }
 }
@@ -40,6 +173,9 @@ impl From<crate::storage::types::User> for GetUserResponse {
             ua_addr: value.ua_addr,
             email: value.email,
             name: value.name,
+            jurisdiction: value.jurisdiction,
+            status: value.status,
+            closed_at: value.closed_at,
         }
     }
 }
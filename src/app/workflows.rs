@@ -0,0 +1,167 @@
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Extension, Json};
+use error_stack::ResultExt;
+
+use crate::auth::Principal;
+use crate::error::{log_convert, ApiError, ConfigurationError};
+use crate::runtime::{self, ExecutionReport};
+use crate::state::AppState;
+use crate::storage::types::Workflow;
+use crate::utils::unix_timestamp;
+
+mod types;
+
+pub fn router() -> Result<axum::Router<AppState>, ConfigurationError> {
+    let router = axum::Router::new()
+        .route("/", post(upload_workflow).get(list_workflows))
+        .route("/:workflow_id", get(get_workflow))
+        .route("/:workflow_id/execute", post(execute_workflow));
+
+    Ok(router)
+}
+
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(upload_workflow, list_workflows, get_workflow, execute_workflow))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    post,
+    path = "",
+    tag = "workflows",
+    request_body(content = Vec<u8>, content_type = "application/wasm", description = "Compiled WebAssembly module"),
+    params(types::UploadWorkflowQuery),
+    responses((status = OK, body = types::WorkflowResponse)),
+)]
+async fn upload_workflow(
+    State(app_state): State<AppState>,
+    Query(query): Query<types::UploadWorkflowQuery>,
+    body: Bytes,
+) -> Result<Json<types::WorkflowResponse>, ApiError> {
+    runtime::validate(&app_state.config.runtime, &body)
+        .change_context(ApiError::InvalidWorkflowModuleError)
+        .map_err(log_convert)?;
+
+    let workflow = Workflow {
+        name: query.name,
+        module: body.to_vec(),
+        created_at: unix_timestamp(),
+    };
+
+    let workflow_id = app_state
+        .storage
+        .get_workflow_interface()
+        .await
+        .change_context(ApiError::UploadWorkflowError)
+        .map_err(log_convert)?
+        .create_workflow(workflow.clone())
+        .await
+        .change_context(ApiError::UploadWorkflowError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::WorkflowResponse::new(workflow_id, &workflow)))
+}
+
+#[utoipa::path(
+    get,
+    path = "",
+    tag = "workflows",
+    responses((status = OK, body = Vec<types::WorkflowResponse>)),
+)]
+async fn list_workflows(
+    State(app_state): State<AppState>,
+) -> Result<Json<Vec<types::WorkflowResponse>>, ApiError> {
+    let workflows = app_state
+        .storage
+        .get_workflow_interface()
+        .await
+        .change_context(ApiError::FetchWorkflowError)
+        .map_err(log_convert)?
+        .list_workflows()
+        .await
+        .change_context(ApiError::FetchWorkflowError)
+        .map_err(log_convert)?;
+
+    Ok(Json(
+        workflows
+            .into_iter()
+            .map(|(workflow_id, workflow)| types::WorkflowResponse::new(workflow_id, &workflow))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{workflow_id}",
+    tag = "workflows",
+    responses((status = OK, body = types::WorkflowResponse)),
+)]
+async fn get_workflow(
+    State(app_state): State<AppState>,
+    Path(workflow_id): Path<String>,
+) -> Result<Json<types::WorkflowResponse>, ApiError> {
+    let workflow = app_state
+        .storage
+        .get_workflow_interface()
+        .await
+        .change_context(ApiError::FetchWorkflowError)
+        .map_err(log_convert)?
+        .get_workflow(&workflow_id)
+        .await
+        .change_context(ApiError::FetchWorkflowError)
+        .map_err(log_convert)?;
+
+    Ok(Json(types::WorkflowResponse::new(workflow_id, &workflow)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{workflow_id}/execute",
+    tag = "workflows",
+    responses((status = OK, body = ExecutionReport)),
+)]
+async fn execute_workflow(
+    State(app_state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(workflow_id): Path<String>,
+    Json(req): Json<types::ExecuteWorkflowRequest>,
+) -> Result<Json<ExecutionReport>, ApiError> {
+    principal.ensure_user(&req.user_id)?;
+
+    let workflow = app_state
+        .storage
+        .get_workflow_interface()
+        .await
+        .change_context(ApiError::FetchWorkflowError)
+        .map_err(log_convert)?
+        .get_workflow(&workflow_id)
+        .await
+        .change_context(ApiError::FetchWorkflowError)
+        .map_err(log_convert)?;
+
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(ApiError::GetUserError)
+        .map_err(log_convert)?
+        .get_user(&req.user_id)
+        .await
+        .change_context(ApiError::GetUserError)
+        .map_err(log_convert)?;
+
+    let report = runtime::execute(
+        &app_state,
+        workflow.module,
+        principal,
+        req.user_id,
+        req.entrypoint,
+        req.args,
+    )
+    .await
+    .change_context(ApiError::ExecuteWorkflowError)
+    .map_err(log_convert)?;
+
+    Ok(Json(report))
+}
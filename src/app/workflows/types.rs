@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::storage::types::Workflow;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UploadWorkflowQuery {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WorkflowResponse {
+    pub workflow_id: String,
+    pub name: String,
+    pub size: usize,
+    /// Hex encoded sha256 digest of the module.
+    pub digest: String,
+    pub created_at: u64,
+}
+
+impl WorkflowResponse {
+    pub fn new(workflow_id: String, workflow: &Workflow) -> Self {
+        use sha2::Digest;
+
+        Self {
+            workflow_id,
+            name: workflow.name.clone(),
+            size: workflow.module.len(),
+            digest: hex::encode(sha2::Sha256::digest(&workflow.module)),
+            created_at: workflow.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExecuteWorkflowRequest {
+    /// User on whose behalf the workflow runs, host calls can only act on their accounts.
+    pub user_id: String,
+    #[serde(default = "default_entrypoint")]
+    pub entrypoint: String,
+    #[serde(default)]
+    pub args: Vec<i64>,
+}
+
+fn default_entrypoint() -> String {
+    "run".to_string()
+}
@@ -0,0 +1,332 @@
+//! Tamper-evident audit trail of every mutating request.
+//!
+//! Records are chained like the entries of the proof store, see [`crate::hash_chain`], so that
+//! editing or dropping a record breaks every hash after it.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use error_stack::{report, ResultExt};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::auth::Principal;
+use crate::error::{ApiError, ConfigurationError, SResult, StorageError};
+use crate::hash_chain::{self, Link};
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{AuditEntry, AuditOutcome, AuditRecord};
+use crate::storage::AuditInterface;
+use crate::utils::unix_timestamp;
+
+/// Append-only, hash-chained audit trail, mirrored to a local file as json lines.
+#[derive(Clone)]
+pub struct AuditStore {
+    log: Arc<Mutex<AuditLog>>,
+}
+
+struct AuditLog {
+    records: Vec<AuditRecord>,
+    file: Option<tokio::fs::File>,
+}
+
+impl AuditStore {
+    pub fn in_memory() -> Self {
+        Self {
+            log: Arc::new(Mutex::new(AuditLog {
+                records: Vec::new(),
+                file: None,
+            })),
+        }
+    }
+
+    /// Loads the existing trail from `path`, new records are appended to the same file.
+    pub fn open(path: &Path) -> SResult<Self, ConfigurationError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).change_context(ConfigurationError::AuditStoreError)?;
+        }
+
+        let records = match std::fs::read_to_string(path) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str::<AuditRecord>)
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ConfigurationError::AuditStoreError)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                return Err(report!(error).change_context(ConfigurationError::AuditStoreError))
+            }
+        };
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .change_context(ConfigurationError::AuditStoreError)?;
+
+        Ok(Self {
+            log: Arc::new(Mutex::new(AuditLog {
+                records,
+                file: Some(tokio::fs::File::from_std(file)),
+            })),
+        })
+    }
+}
+
+impl Link for AuditRecord {
+    type Payload = AuditEntry;
+
+    const NAME: &'static str = "record";
+    const PAYLOAD_FIELD: &'static str = "entry";
+
+    fn index(&self) -> u64 {
+        self.index
+    }
+
+    fn previous_hash(&self) -> &str {
+        &self.previous_hash
+    }
+
+    fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    fn recorded_at(&self) -> u64 {
+        self.recorded_at
+    }
+
+    fn payload(&self) -> &AuditEntry {
+        &self.entry
+    }
+}
+
+#[async_trait::async_trait]
+impl AuditInterface for AuditStore {
+    async fn append(&self, entry: AuditEntry) -> SResult<AuditRecord, StorageError> {
+        let mut log = self.log.lock().await;
+
+        let index = log.records.len() as u64;
+        let previous_hash = hash_chain::head(&log.records);
+        let recorded_at = unix_timestamp();
+
+        let hash = hash_chain::hash::<AuditRecord>(index, &previous_hash, recorded_at, &entry)
+            .change_context(StorageError::AuditWriteError)?;
+
+        let record = AuditRecord {
+            index,
+            previous_hash,
+            hash,
+            recorded_at,
+            entry,
+        };
+
+        if let Some(file) = log.file.as_mut() {
+            let mut line =
+                serde_json::to_vec(&record).change_context(StorageError::AuditWriteError)?;
+            line.push(b'\n');
+
+            file.write_all(&line)
+                .await
+                .change_context(StorageError::AuditWriteError)?;
+            file.flush()
+                .await
+                .change_context(StorageError::AuditWriteError)?;
+        }
+
+        log.records.push(record.clone());
+
+        Ok(record)
+    }
+
+    async fn list_records(&self) -> SResult<Vec<AuditRecord>, StorageError> {
+        Ok(self.log.lock().await.records.clone())
+    }
+}
+
+/// Records every mutating request, whatever its outcome, including those turned away by the
+/// authentication layer. Runs outside of it, the principal it resolved comes back on the response
+/// and is left out when the request couldn't be authenticated.
+pub async fn record(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) {
+        return Ok(next.run(request).await);
+    }
+
+    let (parts, body) = request.into_parts();
+
+    let body = axum::body::to_bytes(body, app_state.config.auth.max_body_bytes)
+        .await
+        .map_err(|_| ApiError::RequestBodyError)?;
+
+    let route = parts
+        .extensions
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| parts.uri.path().to_string());
+
+    let mut entry = AuditEntry {
+        principal: None,
+        method: parts.method.to_string(),
+        entity_ids: path_ids(&route, parts.uri.path()),
+        route,
+        path: parts.uri.path().to_string(),
+        request_digest: hex::encode(Sha256::digest(&body)),
+        status: 0,
+        outcome: AuditOutcome::Succeeded,
+        error: None,
+    };
+
+    let response = next
+        .run(Request::from_parts(parts, axum::body::Body::from(body)))
+        .await;
+
+    entry.status = response.status().as_u16();
+    entry.principal = response
+        .extensions()
+        .get::<Principal>()
+        .and_then(|principal| principal.subject.as_ref())
+        .map(ToString::to_string);
+
+    let error = response.extensions().get::<ApiError>().cloned();
+    let (parts, body) = response.into_parts();
+
+    let response = match error {
+        Some(error) => {
+            entry.outcome = AuditOutcome::Failed;
+            entry.error = Some(error.to_string());
+
+            Response::from_parts(parts, body)
+        }
+        None if !parts.status.is_success() => {
+            entry.outcome = AuditOutcome::Failed;
+
+            Response::from_parts(parts, body)
+        }
+        None => {
+            // ids of created entities are only known from what the handler answered
+            let body = axum::body::to_bytes(body, usize::MAX)
+                .await
+                .map_err(|_| ApiError::RequestBodyError)?;
+
+            entry.entity_ids.extend(response_ids(&body));
+
+            Response::from_parts(parts, axum::body::Body::from(body))
+        }
+    };
+
+    if let Err(error) = app_state.audit.append(entry).await {
+        error!(
+            ?error,
+            "Failed while recording the request in the audit trail"
+        );
+    }
+
+    Ok(response)
+}
+
+/// Values of the route parameters, e.g. `user_id` for `/v1/users/:user_id`.
+fn path_ids(route: &str, path: &str) -> BTreeMap<String, String> {
+    route
+        .split('/')
+        .zip(path.split('/'))
+        .filter_map(|(template, value)| {
+            let name = template.strip_prefix(':')?;
+
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Top level `*_id` fields of a json response.
+fn response_ids(body: &[u8]) -> BTreeMap<String, String> {
+    let Ok(serde_json::Value::Object(fields)) = serde_json::from_slice(body) else {
+        return BTreeMap::new();
+    };
+
+    fields
+        .into_iter()
+        .filter(|(name, _)| name.ends_with("_id"))
+        .filter_map(|(name, value)| Some((name, value.as_str()?.to_string())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_chain::GENESIS_HASH;
+
+    fn entry(path: &str) -> AuditEntry {
+        AuditEntry {
+            principal: Some("users/user".to_string()),
+            method: "POST".to_string(),
+            route: "/v1/users/:user_id/accounts".to_string(),
+            path: path.to_string(),
+            request_digest: hex::encode(Sha256::digest(b"{}")),
+            status: 200,
+            outcome: AuditOutcome::Succeeded,
+            error: None,
+            entity_ids: BTreeMap::new(),
+        }
+    }
+
+    async fn trail() -> Vec<AuditRecord> {
+        let store = AuditStore::in_memory();
+
+        for path in ["/v1/users/a/accounts", "/v1/users/b/accounts"] {
+            store.append(entry(path)).await.unwrap();
+        }
+
+        store.list_records().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn verifies_an_untouched_trail() {
+        assert!(hash_chain::verify(&trail().await).is_ok());
+    }
+
+    #[tokio::test]
+    async fn detects_a_tampered_record() {
+        let mut records = trail().await;
+        records[0].entry.status = 500;
+
+        let violation = hash_chain::verify(&records).unwrap_err();
+
+        assert_eq!(violation.index, 0);
+        assert_eq!(violation.reason, "record hash does not match its contents");
+    }
+
+    #[tokio::test]
+    async fn detects_a_relinked_record() {
+        let mut records = trail().await;
+        let forged = hash_chain::hash::<AuditRecord>(
+            1,
+            GENESIS_HASH,
+            records[1].recorded_at,
+            &records[1].entry,
+        )
+        .unwrap();
+        records[1].previous_hash = GENESIS_HASH.to_string();
+        records[1].hash = forged;
+
+        let violation = hash_chain::verify(&records).unwrap_err();
+
+        assert_eq!(violation.index, 1);
+        assert_eq!(
+            violation.reason,
+            "record does not link to the previous record"
+        );
+    }
+}
@@ -0,0 +1,646 @@
+//! Authentication and authorization of incoming requests.
+//!
+//! Callers authenticate either by signing the request, or with a session token obtained through
+//! the login challenge. Signatures are ed25519, made with the key registered by the acting user or
+//! token manager, over the canonical form of the request:
+//!
+//! ```text
+//! {method}\n{path and query}\n{hex sha256 of the body}\n{timestamp}\n{nonce}
+//! ```
+//!
+//! The timestamp has to be within the configured window of the server clock, and every nonce is
+//! accepted only once per signer for as long as the timestamp it was signed with is.
+//!
+//! Session tokens are `{session_id}.{expires_at}.{mac}`, where the mac is computed with the server
+//! session key. Sessions are kept in storage as well, so that they can be revoked before expiring.
+//!
+//! What an authenticated caller may do follows from the roles granted to it, see [`Role`].
+
+use std::fmt;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use error_stack::ResultExt;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::config::AuthSettings;
+use crate::error::{log_convert, ApiError, ConfigurationError, SResult, StorageError};
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{Role, RoleGrant, UserStatus};
+use crate::utils::unix_timestamp;
+
+pub const SIGNER_HEADER: &str = "x-finternet-signer";
+pub const SIGNATURE_HEADER: &str = "x-finternet-signature";
+pub const TIMESTAMP_HEADER: &str = "x-finternet-timestamp";
+pub const NONCE_HEADER: &str = "x-finternet-nonce";
+
+/// Party acting through a request, given as `users/<id>` or `token_managers/<id>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject {
+    User(String),
+    TokenManager(String),
+}
+
+impl Subject {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.split_once('/')? {
+            ("users", id) if !id.is_empty() => Some(Self::User(id.to_string())),
+            ("token_managers", id) if !id.is_empty() => Some(Self::TokenManager(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Subject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(id) => write!(f, "users/{id}"),
+            Self::TokenManager(id) => write!(f, "token_managers/{id}"),
+        }
+    }
+}
+
+/// Caller of a request, available to the handlers as a request extension.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    /// `None` for anonymous callers.
+    pub subject: Option<Subject>,
+    pub roles: Vec<Role>,
+}
+
+impl Principal {
+    /// Caller of every request while authentication is disabled.
+    pub fn unrestricted() -> Self {
+        Self {
+            subject: None,
+            roles: vec![Role::PlatformAdmin],
+        }
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.roles.contains(&Role::PlatformAdmin)
+    }
+
+    pub fn is_auditor(&self) -> bool {
+        self.is_admin() || self.roles.contains(&Role::Auditor)
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        match &self.subject {
+            Some(Subject::User(user_id)) => Some(user_id),
+            _ => None,
+        }
+    }
+
+    /// Whether the caller may act on behalf of the user.
+    pub fn acts_for(&self, user_id: &str) -> bool {
+        self.is_admin() || (self.user_id() == Some(user_id) && self.roles.contains(&Role::User))
+    }
+
+    /// Whether the caller may act on behalf of the token manager.
+    pub fn operates(&self, token_manager_id: &str) -> bool {
+        self.is_admin()
+            || self.subject == Some(Subject::TokenManager(token_manager_id.to_string()))
+            || self.roles.contains(&Role::TokenManagerOperator {
+                token_manager_id: token_manager_id.to_string(),
+            })
+    }
+
+    /// Reads that are open to anonymous callers let handlers opt into authentication with this.
+    pub fn ensure_authenticated(&self) -> Result<(), ApiError> {
+        (self.subject.is_some() || self.is_admin())
+            .then_some(())
+            .ok_or(ApiError::UnauthenticatedError)
+    }
+
+    pub fn ensure_admin(&self) -> Result<(), ApiError> {
+        self.is_admin()
+            .then_some(())
+            .ok_or(ApiError::ForbiddenError)
+    }
+
+    pub fn ensure_user(&self, user_id: &str) -> Result<(), ApiError> {
+        self.acts_for(user_id)
+            .then_some(())
+            .ok_or(ApiError::ForbiddenError)
+    }
+
+    pub fn ensure_token_manager(&self, token_manager_id: &str) -> Result<(), ApiError> {
+        self.operates(token_manager_id)
+            .then_some(())
+            .ok_or(ApiError::ForbiddenError)
+    }
+}
+
+/// Key session tokens are authenticated with.
+#[derive(Clone)]
+pub struct SessionKey {
+    key: Arc<[u8; 32]>,
+}
+
+impl SessionKey {
+    pub fn new(settings: &AuthSettings) -> SResult<Self, ConfigurationError> {
+        let key = match &settings.session_key {
+            Some(key) => {
+                let key = hex::decode(key).change_context(ConfigurationError::SessionKeyError)?;
+
+                <[u8; 32]>::try_from(key).map_err(|_| ConfigurationError::SessionKeyError)?
+            }
+            None => {
+                warn!("No session key configured, generating an ephemeral key");
+
+                let mut key = [0_u8; 32];
+                rand::rngs::OsRng.fill_bytes(&mut key);
+                key
+            }
+        };
+
+        Ok(Self { key: Arc::new(key) })
+    }
+
+    fn mac(&self, session_id: &str, expires_at: u64) -> Hmac<Sha256> {
+        // Safety: HMAC accepts keys of any length.
+        #[allow(clippy::expect_used)]
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.key.as_slice())
+            .expect("HMAC can take a key of any size");
+
+        mac.update(session_id.as_bytes());
+        mac.update(b".");
+        mac.update(expires_at.to_string().as_bytes());
+
+        mac
+    }
+
+    pub fn token(&self, session_id: &str, expires_at: u64) -> String {
+        let mac = self.mac(session_id, expires_at).finalize().into_bytes();
+
+        format!("{session_id}.{expires_at}.{}", hex::encode(mac))
+    }
+
+    /// Returns the session id and expiry of an authentic token.
+    pub fn verify(&self, token: &str) -> Option<(String, u64)> {
+        let mut parts = token.splitn(3, '.');
+        let (session_id, expires_at, mac) = (parts.next()?, parts.next()?, parts.next()?);
+        let expires_at = expires_at.parse().ok()?;
+
+        self.mac(session_id, expires_at)
+            .verify_slice(&hex::decode(mac).ok()?)
+            .ok()?;
+
+        Some((session_id.to_string(), expires_at))
+    }
+}
+
+/// Message the request signature is computed over.
+pub fn canonical(
+    method: &Method,
+    path_and_query: &str,
+    body: &[u8],
+    timestamp: u64,
+    nonce: &str,
+) -> String {
+    format!(
+        "{method}\n{path_and_query}\n{}\n{timestamp}\n{nonce}",
+        hex::encode(Sha256::digest(body))
+    )
+}
+
+/// Mutating requests have to be authenticated, except for those registering a user or logging in.
+fn requires_authentication(method: &Method, path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+
+    match *method {
+        Method::POST => !matches!(path, "/v1/users" | "/v1/auth/challenge" | "/v1/auth/login"),
+        Method::PUT | Method::PATCH | Method::DELETE => true,
+        _ => false,
+    }
+}
+
+/// Middleware resolving the principal of the request, from its session token or its signature.
+pub async fn authenticate(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let settings = &app_state.config.auth;
+
+    if !settings.enabled {
+        let mut request = request;
+        request.extensions_mut().insert(Principal::unrestricted());
+
+        let mut response = next.run(request).await;
+        response.extensions_mut().insert(Principal::unrestricted());
+
+        return Ok(response);
+    }
+
+    let (mut parts, body) = request.into_parts();
+
+    let (subject, body) = if let Some(token) = bearer(&parts.headers) {
+        (Some(session(&app_state, token).await?.1), body)
+    } else if parts.headers.contains_key(SIGNATURE_HEADER) {
+        let (subject, body) = verify_request(&app_state, &parts, body).await?;
+
+        (Some(subject), Body::from(body))
+    } else {
+        (None, body)
+    };
+
+    let principal = match subject {
+        Some(subject) => Principal {
+            roles: roles(&app_state, &subject).await?,
+            subject: Some(subject),
+        },
+        None => Principal {
+            subject: None,
+            roles: Vec::new(),
+        },
+    };
+
+    let admitted = if principal.subject.is_some()
+        || !requires_authentication(&parts.method, parts.uri.path())
+    {
+        authorize(&app_state, &principal, &parts.method, parts.uri.path()).await
+    } else {
+        Err(ApiError::UnauthenticatedError)
+    };
+
+    let mut response = match admitted {
+        Ok(()) => {
+            parts.extensions.insert(principal.clone());
+
+            next.run(Request::from_parts(parts, body)).await
+        }
+        Err(error) => error.into_response(),
+    };
+
+    // the audit trail records who made the request, also when it was turned away
+    response.extensions_mut().insert(principal);
+
+    Ok(response)
+}
+
+pub fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+/// Resolves the session token into the id of the session and its subject.
+pub async fn session(app_state: &AppState, token: &str) -> Result<(String, Subject), ApiError> {
+    let (session_id, expires_at) = app_state
+        .session_key
+        .verify(token)
+        .filter(|(_, expires_at)| *expires_at > unix_timestamp())
+        .ok_or(ApiError::InvalidSessionError)?;
+
+    let session = app_state
+        .storage
+        .get_session_interface()
+        .await
+        .change_context(ApiError::InvalidSessionError)
+        .map_err(log_convert)?
+        .get_session(&session_id)
+        .await
+        .change_context(ApiError::InvalidSessionError)
+        .map_err(log_convert)?;
+
+    let subject = Subject::parse(&session.subject)
+        .filter(|_| session.expires_at == expires_at)
+        .ok_or(ApiError::InvalidSessionError)?;
+
+    Ok((session_id, subject))
+}
+
+/// Verifies the signature of the request, returning the signer along with the buffered body.
+async fn verify_request(
+    app_state: &AppState,
+    parts: &Parts,
+    body: Body,
+) -> Result<(Subject, axum::body::Bytes), ApiError> {
+    let settings = &app_state.config.auth;
+
+    let header = |name: &str| {
+        parts
+            .headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+
+    let signer = header(SIGNER_HEADER)
+        .and_then(|value| Subject::parse(&value))
+        .ok_or(ApiError::MissingSignatureError)?;
+    let signature = header(SIGNATURE_HEADER).ok_or(ApiError::MissingSignatureError)?;
+    let timestamp = header(TIMESTAMP_HEADER)
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or(ApiError::MissingSignatureError)?;
+    let nonce = header(NONCE_HEADER)
+        .filter(|nonce| (8..=128).contains(&nonce.len()))
+        .ok_or(ApiError::MissingSignatureError)?;
+
+    (timestamp.abs_diff(unix_timestamp()) <= settings.max_skew_secs)
+        .then_some(())
+        .ok_or(ApiError::StaleSignatureError)?;
+
+    let body = axum::body::to_bytes(body, settings.max_body_bytes)
+        .await
+        .map_err(|_| ApiError::InvalidSignatureError)?;
+
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|value| value.as_str())
+        .unwrap_or_else(|| parts.uri.path());
+
+    let message = canonical(&parts.method, path_and_query, &body, timestamp, &nonce);
+
+    verify(&public_key(app_state, &signer).await?, &signature, &message)
+        .then_some(())
+        .ok_or(ApiError::InvalidSignatureError)?;
+
+    // nonces are only recorded for authentic requests, so that nobody can burn them for others
+    app_state
+        .storage
+        .get_nonce_interface()
+        .await
+        .change_context(ApiError::InvalidSignatureError)
+        .map_err(log_convert)?
+        .consume(
+            &format!("{signer}/{nonce}"),
+            timestamp + settings.max_skew_secs + 1,
+        )
+        .await
+        .change_context(ApiError::ReplayedRequestError)
+        .map_err(log_convert)?;
+
+    Ok((signer, body))
+}
+
+/// Roles currently granted to the subject.
+pub async fn roles(app_state: &AppState, subject: &Subject) -> Result<Vec<Role>, ApiError> {
+    Ok(app_state
+        .storage
+        .get_role_interface()
+        .await
+        .change_context(ApiError::ForbiddenError)
+        .map_err(log_convert)?
+        .list_roles(Some(&subject.to_string()))
+        .await
+        .change_context(ApiError::ForbiddenError)
+        .map_err(log_convert)?
+        .into_iter()
+        .map(|(_, grant)| grant.role)
+        .collect())
+}
+
+/// Grants the roles a newly registered user starts with, the user role, along with the platform
+/// admin role when registering with one of the configured admin keys.
+pub async fn enroll(
+    app_state: &AppState,
+    user_id: &str,
+    public_key: &str,
+) -> SResult<(), StorageError> {
+    let subject = Subject::User(user_id.to_string());
+    let mut roles = vec![Role::User];
+
+    if app_state
+        .config
+        .auth
+        .admin_keys
+        .iter()
+        .any(|key| key.eq_ignore_ascii_case(public_key))
+    {
+        info!("Granting the platform admin role to {subject}");
+        roles.push(Role::PlatformAdmin);
+    }
+
+    let role_interface = app_state.storage.get_role_interface().await?;
+
+    for role in roles {
+        role_interface
+            .grant_role(RoleGrant {
+                subject: subject.to_string(),
+                role,
+                granted_by: None,
+                granted_at: unix_timestamp(),
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Parses a hex encoded ed25519 public key.
+pub fn verifying_key(public_key: &str) -> Option<VerifyingKey> {
+    hex::decode(public_key)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+}
+
+/// Checks an ed25519 signature made with a hex encoded public key.
+pub fn verify(public_key: &str, signature: &str, message: &str) -> bool {
+    let Some(public_key) = verifying_key(public_key) else {
+        return false;
+    };
+
+    let Some(signature) = hex::decode(signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    else {
+        return false;
+    };
+
+    public_key.verify(message.as_bytes(), &signature).is_ok()
+}
+
+/// Registered public key of the subject. Closed users have none, they can't sign anymore.
+pub async fn public_key(app_state: &AppState, subject: &Subject) -> Result<String, ApiError> {
+    match subject {
+        Subject::User(user_id) => {
+            let user = app_state
+                .storage
+                .get_user_interface()
+                .await
+                .change_context(ApiError::InvalidSignatureError)
+                .map_err(log_convert)?
+                .get_user(user_id)
+                .await
+                .change_context(ApiError::InvalidSignatureError)
+                .map_err(log_convert)?;
+
+            (user.status == UserStatus::Active)
+                .then_some(user.public_key)
+                .ok_or(ApiError::InvalidSignatureError)
+        }
+        Subject::TokenManager(token_manager_id) => Ok(app_state
+            .storage
+            .get_token_manager_interface()
+            .await
+            .change_context(ApiError::InvalidSignatureError)
+            .map_err(log_convert)?
+            .get_token_manager(token_manager_id)
+            .await
+            .change_context(ApiError::InvalidSignatureError)
+            .map_err(log_convert)?
+            .public_key),
+    }
+}
+
+/// Makes sure the caller acts on its own resources. Users act on everything under their id, token
+/// managers and their operators on everything under theirs and on the accounts they manage, admins
+/// on everything. Auditors read everything. Accounts and assets are left to their permission bits,
+/// see [`crate::acl`].
+async fn authorize(
+    app_state: &AppState,
+    principal: &Principal,
+    method: &Method,
+    path: &str,
+) -> Result<(), ApiError> {
+    if principal.is_admin() || (method == Method::GET && principal.is_auditor()) {
+        return Ok(());
+    }
+
+    let segments: Vec<&str> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    let allowed = match (segments.as_slice(), &principal.subject) {
+        (["v1", "users", _, ..], None) => return Err(ApiError::UnauthenticatedError),
+        (["v1", "users", user_id, ..], Some(_)) if principal.acts_for(user_id) => true,
+        // keys are public, anyone verifying what a user signed may look them up
+        (["v1", "users", _, "keys"], Some(_)) if method == Method::GET => true,
+        (["v1", "users", _, "accounts", _, "assets" | "permissions", ..], Some(_)) => true,
+        (["v1", "users", _, "accounts", _], Some(_)) if method == Method::GET => true,
+        (["v1", "users", user_id, "accounts", account_id, ..], Some(_)) => {
+            let (account, _) = app_state
+                .storage
+                .get_user_interface()
+                .await
+                .change_context(ApiError::ForbiddenError)
+                .map_err(log_convert)?
+                .get_account_interface(user_id)
+                .await
+                .change_context(ApiError::ForbiddenError)
+                .map_err(log_convert)?
+                .get_account(account_id)
+                .await
+                .change_context(ApiError::ForbiddenError)
+                .map_err(log_convert)?;
+
+            principal.operates(&account.token_manager_id)
+        }
+        (["v1", "users", _, ..], Some(_)) => false,
+        (["v1", "token_managers", token_manager_id, ..], _) if method != Method::GET => {
+            principal.operates(token_manager_id)
+        }
+        _ => true,
+    };
+
+    allowed.then_some(()).ok_or(ApiError::ForbiddenError)
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use super::*;
+    use crate::imc::NonceStore;
+    use crate::storage::NonceInterface;
+
+    const BODY: &[u8] = br#"{"amount":10}"#;
+
+    fn key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    fn sign(message: &str) -> String {
+        hex::encode(key().sign(message.as_bytes()).to_bytes())
+    }
+
+    fn public_key() -> String {
+        hex::encode(key().verifying_key().to_bytes())
+    }
+
+    #[test]
+    fn accepts_a_signature_over_the_request() {
+        let message = canonical(&Method::POST, "/v1/swaps", BODY, 100, "nonce-0001");
+
+        assert!(verify(&public_key(), &sign(&message), &message));
+    }
+
+    #[test]
+    fn rejects_a_signature_over_a_modified_body() {
+        let signature = sign(&canonical(
+            &Method::POST,
+            "/v1/swaps",
+            BODY,
+            100,
+            "nonce-0001",
+        ));
+        let tampered = canonical(
+            &Method::POST,
+            "/v1/swaps",
+            br#"{"amount":99}"#,
+            100,
+            "nonce-0001",
+        );
+
+        assert!(!verify(&public_key(), &signature, &tampered));
+    }
+
+    #[test]
+    fn binds_the_signature_to_its_nonce() {
+        let signature = sign(&canonical(
+            &Method::POST,
+            "/v1/swaps",
+            BODY,
+            100,
+            "nonce-0001",
+        ));
+        let replayed = canonical(&Method::POST, "/v1/swaps", BODY, 100, "nonce-0002");
+
+        assert!(!verify(&public_key(), &signature, &replayed));
+    }
+
+    #[tokio::test]
+    async fn refuses_a_replayed_nonce() {
+        let nonces = NonceStore::default();
+        let expires_at = unix_timestamp() + 60;
+
+        assert!(nonces
+            .consume("users/a/nonce-0001", expires_at)
+            .await
+            .is_ok());
+        assert!(nonces
+            .consume("users/b/nonce-0001", expires_at)
+            .await
+            .is_ok());
+        assert!(nonces
+            .consume("users/a/nonce-0001", expires_at)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_keys_and_signatures() {
+        let message = canonical(&Method::GET, "/v1/proofs", b"", 100, "nonce-0001");
+        let signature = sign(&message);
+
+        assert!(!verify("not hex", &signature, &message));
+        assert!(!verify(&public_key(), &signature[2..], &message));
+    }
+}
@@ -16,7 +16,16 @@ async fn main() -> SResult<(), ConfigurationError> {
 
     let router = finternet_app_api::app::router()?;
 
-    let router = router.with_state(AppState::imc_backed(config.clone()));
+    let app_state = AppState::imc_backed(config.clone())?;
+
+    tokio::spawn(finternet_app_api::webhooks::worker(app_state.clone()));
+    tokio::spawn(finternet_app_api::sync::worker(app_state.clone()));
+    tokio::spawn(finternet_app_api::merkle::worker(app_state.clone()));
+    tokio::spawn(finternet_app_api::saga::worker(app_state.clone()));
+    tokio::spawn(finternet_app_api::schedule::worker(app_state.clone()));
+    tokio::spawn(finternet_app_api::swap::worker(app_state.clone()));
+
+    let router = finternet_app_api::app::with_auth(router, app_state);
 
     finternet_app_api::app::start_server(
         router,
@@ -12,6 +12,30 @@ pub struct Config {
     pub log: LogConfig,
     #[serde(rename(deserialize = "server"))]
     pub server_config: ServerSettings,
+    #[serde(default)]
+    pub webhooks: WebhookSettings,
+    #[serde(default)]
+    pub sync: SyncSettings,
+    #[serde(default)]
+    pub proofs: ProofSettings,
+    #[serde(default)]
+    pub audit: AuditSettings,
+    #[serde(default)]
+    pub merkle: MerkleSettings,
+    #[serde(default)]
+    pub runtime: RuntimeSettings,
+    #[serde(default)]
+    pub intents: IntentSettings,
+    #[serde(default)]
+    pub sagas: SagaSettings,
+    #[serde(default)]
+    pub swaps: SwapSettings,
+    #[serde(default)]
+    pub multisig: MultisigSettings,
+    #[serde(default)]
+    pub schedules: ScheduleSettings,
+    #[serde(default)]
+    pub auth: AuthSettings,
     // #[cfg(feature = "aws-kms")]
     // pub aws_kms: kms::AwsKmsConfig,
     // pub imc_backup: BackupConfig,
@@ -23,6 +47,233 @@ pub struct ServerSettings {
     pub host: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    /// How often the delivery worker polls the retry queue.
+    pub poll_interval_secs: u64,
+    /// Number of attempts after which a delivery is marked as failed.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every subsequent attempt.
+    pub base_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+    pub request_timeout_secs: u64,
+    pub batch_size: usize,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 5,
+            max_attempts: 8,
+            base_backoff_secs: 10,
+            max_backoff_secs: 3600,
+            request_timeout_secs: 10,
+            batch_size: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// How often the sync worker drains the custodial outbox.
+    pub poll_interval_secs: u64,
+    pub request_timeout_secs: u64,
+    pub batch_size: usize,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 2,
+            request_timeout_secs: 10,
+            batch_size: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct ProofSettings {
+    /// File the proof chain is persisted to, the chain is kept in memory when unset.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct AuditSettings {
+    /// File the audit trail is persisted to, the trail is kept in memory when unset.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MerkleSettings {
+    /// How often a new root is computed over the account state.
+    pub interval_secs: u64,
+    /// Hex encoded ed25519 seed used to sign published roots, an ephemeral key is generated when
+    /// unset.
+    pub signing_key: Option<String>,
+}
+
+impl Default for MerkleSettings {
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            signing_key: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RuntimeSettings {
+    /// Fuel granted to a single workflow execution, roughly one unit per instruction.
+    pub fuel: u64,
+    /// Wall clock budget of a single workflow execution, checked whenever it calls into the host.
+    pub timeout_ms: u64,
+    pub max_module_bytes: usize,
+    /// Linear memory a workflow may grow to.
+    pub max_memory_bytes: usize,
+    pub max_table_elements: u32,
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self {
+            fuel: 10_000_000,
+            timeout_ms: 2_000,
+            max_module_bytes: 1024 * 1024,
+            max_memory_bytes: 16 * 1024 * 1024,
+            max_table_elements: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IntentSettings {
+    /// Lifetime of a descriptor when the caller doesn't ask for one.
+    pub default_ttl_secs: u64,
+    pub max_ttl_secs: u64,
+}
+
+impl Default for IntentSettings {
+    fn default() -> Self {
+        Self {
+            default_ttl_secs: 30,
+            max_ttl_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SagaSettings {
+    /// How often the saga worker picks up running instances.
+    pub poll_interval_ms: u64,
+    pub batch_size: usize,
+}
+
+impl Default for SagaSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 500,
+            batch_size: 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SwapSettings {
+    /// Time both legs have to be funded in when the maker doesn't ask for one.
+    pub default_ttl_secs: u64,
+    pub max_ttl_secs: u64,
+    /// How often rollbacks of failed settlements that didn't complete are retried.
+    pub retry_interval_secs: u64,
+}
+
+impl Default for SwapSettings {
+    fn default() -> Self {
+        Self {
+            default_ttl_secs: 3600,
+            max_ttl_secs: 86400,
+            retry_interval_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MultisigSettings {
+    /// Time the signers have to approve a transfer before it is dropped and the asset unlocked.
+    pub approval_ttl_secs: u64,
+}
+
+impl Default for MultisigSettings {
+    fn default() -> Self {
+        Self {
+            approval_ttl_secs: 86400,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ScheduleSettings {
+    /// How often the scheduler looks for due instructions.
+    pub poll_interval_secs: u64,
+    pub batch_size: usize,
+    /// Number of runs kept in the history of every schedule.
+    pub history_limit: usize,
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 1,
+            batch_size: 16,
+            history_limit: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AuthSettings {
+    /// Whether requests have to be authenticated, through a signature or a session token.
+    pub enabled: bool,
+    /// Largest accepted difference between the signed timestamp and the server clock.
+    pub max_skew_secs: u64,
+    /// Largest body that is buffered to verify its digest.
+    pub max_body_bytes: usize,
+    /// Time a login challenge can be answered in.
+    pub challenge_ttl_secs: u64,
+    pub session_ttl_secs: u64,
+    /// Hex encoded key session tokens are authenticated with, an ephemeral key is generated when
+    /// unset, which logs everyone out on restart.
+    pub session_key: Option<String>,
+    /// Hex encoded public keys that are granted the platform admin role when a user registers with
+    /// them, which is how the first admin comes about.
+    pub admin_keys: Vec<String>,
+}
+
+impl Default for AuthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_skew_secs: 300,
+            max_body_bytes: 2 * 1024 * 1024,
+            challenge_ttl_secs: 120,
+            session_ttl_secs: 3600,
+            session_key: None,
+            admin_keys: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct BackupConfig {
     pub path: PathBuf,
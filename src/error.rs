@@ -1,4 +1,6 @@
+use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use serde::Serialize;
 
 use crate::logging::prelude::*;
 
@@ -21,11 +23,24 @@ pub enum ConfigurationError {
 
     #[error("Error while binding the server")]
     ServerBindError,
+
+    #[error("Error while loading the proof store")]
+    ProofStoreError,
+
+    #[error("Error while loading the audit store")]
+    AuditStoreError,
+
+    #[error("Invalid merkle signing key")]
+    SigningKeyError,
+
+    #[error("Invalid session key")]
+    SessionKeyError,
 }
 
 pub type SResult<T, E> = error_stack::Result<T, E>;
 
-#[derive(thiserror::Error, Debug)]
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum StorageError {
     #[error("Error while getting the account interface")]
     AccountInterfaceError,
@@ -56,9 +71,289 @@ pub enum StorageError {
 
     #[error("Asset not found")]
     AssetNotFoundError,
+
+    #[error("Webhook not found")]
+    WebhookNotFoundError,
+
+    #[error("Webhook delivery not found")]
+    DeliveryNotFoundError,
+
+    #[error("Proof not found")]
+    ProofNotFoundError,
+
+    #[error("Failed while appending to the proof store")]
+    ProofWriteError,
+
+    #[error("Failed while appending to the audit trail")]
+    AuditWriteError,
+
+    #[error("Workflow not found")]
+    WorkflowNotFoundError,
+
+    #[error("Descriptor not found")]
+    DescriptorNotFoundError,
+
+    #[error("Resource is locked by another holder")]
+    LockHeldError,
+
+    #[error("Mount not found")]
+    MountNotFoundError,
+
+    #[error("Path is already mounted")]
+    MountExistsError,
+
+    #[error("Saga not found")]
+    SagaNotFoundError,
+
+    #[error("Saga instance not found")]
+    SagaInstanceNotFoundError,
+
+    #[error("Swap not found")]
+    SwapNotFoundError,
+
+    #[error("Schedule not found")]
+    ScheduleNotFoundError,
+
+    #[error("Nonce was already used")]
+    NonceReusedError,
+
+    #[error("Challenge not found")]
+    ChallengeNotFoundError,
+
+    #[error("Session not found")]
+    SessionNotFoundError,
+
+    #[error("Role grant not found")]
+    RoleNotFoundError,
+
+    #[error("Permissions not found")]
+    AclNotFoundError,
+
+    #[error("Access grant not found")]
+    AccessGrantNotFoundError,
+
+    #[error("Debit limit of the access grant would be exceeded")]
+    DebitLimitExceededError,
+
+    #[error("Policy not found")]
+    PolicyNotFoundError,
+
+    #[error("Multisig setup not found")]
+    MultisigNotFoundError,
+
+    #[error("Approval not found")]
+    ApprovalNotFoundError,
+
+    #[error("Cursor doesn't belong to this listing")]
+    InvalidCursorError,
+
+    #[error("Account still holds assets")]
+    AccountNotEmptyError,
 }
 
-#[derive(thiserror::Error, Debug, Clone, Copy)]
+impl StorageError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::UserNotFoundError
+            | Self::AccountNotFoundError
+            | Self::TokenManagerNotFoundError
+            | Self::SupportedAssetNotFoundError
+            | Self::AssetNotFoundError
+            | Self::WebhookNotFoundError
+            | Self::DeliveryNotFoundError
+            | Self::ProofNotFoundError
+            | Self::WorkflowNotFoundError
+            | Self::DescriptorNotFoundError
+            | Self::MountNotFoundError
+            | Self::SagaNotFoundError
+            | Self::SagaInstanceNotFoundError
+            | Self::SwapNotFoundError
+            | Self::ScheduleNotFoundError
+            | Self::ChallengeNotFoundError
+            | Self::SessionNotFoundError
+            | Self::RoleNotFoundError
+            | Self::AclNotFoundError
+            | Self::AccessGrantNotFoundError
+            | Self::PolicyNotFoundError
+            | Self::MultisigNotFoundError
+            | Self::ApprovalNotFoundError => StatusCode::NOT_FOUND,
+            Self::UaAddrExistsError
+            | Self::MountExistsError
+            | Self::LockHeldError
+            | Self::NonceReusedError
+            | Self::AccountNotEmptyError => StatusCode::CONFLICT,
+            Self::DebitLimitExceededError => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::InvalidCursorError => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NamespaceError {
+    #[error("Path must be absolute without empty, `.` or `..` segments")]
+    InvalidPath,
+
+    #[error("No mount covers the path")]
+    NotMounted,
+
+    #[error("Path doesn't point to an account")]
+    NotAnAccount,
+
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Account not found")]
+    AccountNotFound,
+
+    #[error("More than one account matches the path")]
+    AmbiguousAccount,
+
+    #[error("Error while accessing the storage")]
+    StorageError,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SwapError {
+    #[error("Swap not found")]
+    NotFound,
+
+    #[error("Swap is no longer open")]
+    NotOpen,
+
+    #[error("Leg of the swap is already funded")]
+    AlreadyFunded,
+
+    #[error("Swap is being worked on by another request")]
+    Busy,
+
+    #[error("Failed while locking the assets of the leg")]
+    FundingFailed,
+
+    #[error("Failed while exchanging the legs, the swap was rolled back")]
+    SettlementFailed,
+
+    #[error("Failed while rolling back the swap, the rollback is retried")]
+    RollbackFailed,
+
+    #[error("Error while accessing the storage")]
+    StorageError,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MultisigError {
+    #[error("Approval not found")]
+    NotFound,
+
+    #[error("Approval is no longer pending")]
+    NotPending,
+
+    #[error("Caller is not a signer of the approval")]
+    NotSigner,
+
+    #[error("Signer already voted on the approval")]
+    AlreadyVoted,
+
+    #[error("Approval is being worked on by another request")]
+    Busy,
+
+    #[error("Failed while locking the asset for the approval")]
+    LockFailed,
+
+    #[error("Failed while carrying out the approved transfer")]
+    ExecutionFailed,
+
+    #[error("Error while accessing the storage")]
+    StorageError,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AclError {
+    #[error("Account or asset not found")]
+    NotFound,
+
+    #[error("Permissions of the account or asset don't allow the operation")]
+    Denied,
+
+    #[error("Debit limit of the access grant would be exceeded")]
+    LimitExceeded,
+
+    #[error("Error while accessing the storage")]
+    StorageError,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PolicyError {
+    #[error("Rejected by policy `{policy}`, rule {rule}: {reason}")]
+    Rejected {
+        policy: String,
+        rule: String,
+        reason: String,
+    },
+
+    #[error("Account not found")]
+    AccountNotFound,
+
+    #[error("Error while accessing the storage")]
+    StorageError,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LedgerError {
+    #[error("Amount must be greater than zero")]
+    InvalidAmount,
+
+    #[error("Insufficient funds in the account")]
+    InsufficientFunds,
+
+    #[error("Account not found")]
+    AccountNotFound,
+
+    #[error("Asset not found")]
+    AssetNotFound,
+
+    #[error("Account is locked by another holder")]
+    AccountLocked,
+
+    #[error("Asset is locked by another holder")]
+    AssetLocked,
+
+    #[error("{0}")]
+    PolicyRejected(String),
+
+    #[error("Token manager of the account is deactivated")]
+    TokenManagerDeactivated,
+
+    #[error("Amount is above the multisig threshold of the account")]
+    ApprovalRequired,
+
+    #[error("Error while accessing the storage")]
+    StorageError,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RuntimeError {
+    #[error("Workflow module exceeds the maximum allowed size")]
+    ModuleTooLarge,
+
+    #[error("Invalid workflow module")]
+    InvalidModule,
+
+    #[error("Failed while instantiating the workflow module")]
+    InstantiationFailed,
+
+    #[error("Entrypoint not exported by the workflow module")]
+    EntrypointNotFound,
+
+    #[error("Entrypoint signature doesn't match the provided arguments")]
+    EntrypointSignatureMismatch,
+
+    #[error("Failed while executing the workflow")]
+    ExecutionFailed,
+}
+
+#[derive(thiserror::Error, Debug, Clone, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum ApiError {
     #[error("Error while creating the user")]
     CreateUserError,
@@ -72,10 +367,44 @@ pub enum ApiError {
 
     #[error("Error while listing the token managers")]
     ListTokenManagersError,
+    #[error("Failed while fetching the token manager")]
+    FetchTokenManagerError,
+    #[error("Failed while updating the token manager")]
+    UpdateTokenManagerError,
+    #[error("Failed while deactivating the token manager")]
+    DeactivateTokenManagerError,
+    #[error("Token manager is deactivated")]
+    TokenManagerDeactivatedError,
+    #[error("Failed while listing the supported assets")]
+    ListSupportedAssetsError,
+    #[error("Failed while fetching the supported asset")]
+    FetchSupportedAssetError,
+    #[error("Failed while updating the supported asset")]
+    UpdateSupportedAssetError,
+    #[error("Failed while deprecating the supported asset")]
+    DeprecateSupportedAssetError,
+    #[error("Supported asset is deprecated")]
+    SupportedAssetDeprecatedError,
     #[error("Account Creation Error")]
     AccountCreationError,
     #[error("Failed while fetching the account")]
     FetchAccountError,
+    #[error("Failed while listing the accounts")]
+    ListAccountsError,
+    #[error("Failed while updating the account")]
+    UpdateAccountError,
+    #[error("Failed while closing the account")]
+    CloseAccountError,
+    #[error("Assets can only be swept to another account holding the same asset type")]
+    InvalidSweepTargetError,
+    #[error("Account still holds assets, they have to be swept to another account first")]
+    AccountNotEmptyError,
+    #[error("Failed while updating the user")]
+    UpdateUserError,
+    #[error("Failed while closing the user")]
+    CloseUserError,
+    #[error("User is closed")]
+    UserClosedError,
     #[error("not implemented")]
     NotImplemented,
     #[error("Failed while creating assets")]
@@ -85,52 +414,450 @@ pub enum ApiError {
 
     #[error("Asset type not supported by the token manager")]
     AssetTypeNotSupportedError,
+
+    #[error("Failed while burning the asset")]
+    BurnAssetError,
+
+    #[error("Failed while registering the webhook")]
+    CreateWebhookError,
+    #[error("Webhook url must be an absolute http(s) url of a public host")]
+    InvalidWebhookUrlError,
+    #[error("Failed while fetching the webhook")]
+    FetchWebhookError,
+    #[error("Failed while deleting the webhook")]
+    DeleteWebhookError,
+    #[error("Failed while fetching webhook deliveries")]
+    FetchDeliveryError,
+    #[error("Failed while replaying the webhook delivery")]
+    ReplayDeliveryError,
+
+    #[error("Failed while fetching the sync status")]
+    FetchSyncStatusError,
+    #[error("Failed while queueing the resync snapshot")]
+    ResyncError,
+
+    #[error("Failed while fetching the proof")]
+    FetchProofError,
+    #[error("Failed while verifying the proof chain")]
+    VerifyProofError,
+
+    #[error("No merkle root has been published yet")]
+    MerkleRootUnavailableError,
+    #[error("Failed while building the inclusion proof")]
+    AssetProofError,
+    #[error("Asset is not part of the latest published root")]
+    AssetNotCommittedError,
+
+    #[error("Failed while uploading the workflow")]
+    UploadWorkflowError,
+    #[error("Workflow module is invalid or too large")]
+    InvalidWorkflowModuleError,
+    #[error("Failed while fetching the workflow")]
+    FetchWorkflowError,
+    #[error("Failed while executing the workflow")]
+    ExecuteWorkflowError,
+
+    #[error("Failed while creating the descriptor")]
+    IntendError,
+    #[error("Account is locked by another holder")]
+    AccountLockedError,
+    #[error("Descriptor not found or expired")]
+    DescriptorNotFoundError,
+    #[error("Descriptor doesn't allow this operation")]
+    DescriptorPurposeError,
+    #[error("Failed while operating on the descriptor")]
+    DescriptorOperationError,
+
+    #[error("Failed while mounting the token manager")]
+    CreateMountError,
+    #[error("Failed while unmounting the path")]
+    DeleteMountError,
+    #[error("Failed while fetching the mounts")]
+    FetchMountError,
+    #[error("Failed while resolving the path")]
+    ResolvePathError,
+    #[error("Path must be absolute without empty, `.` or `..` segments")]
+    InvalidPathError,
+
+    #[error("Failed while creating the saga")]
+    CreateSagaError,
+    #[error("Saga needs at least one step, with positive amounts and valid accounts")]
+    InvalidSagaError,
+    #[error("Failed while fetching the saga")]
+    FetchSagaError,
+    #[error("Failed while starting the saga")]
+    StartSagaError,
+    #[error("Failed while updating the saga instance")]
+    UpdateSagaError,
+    #[error("Operation not allowed in the current state of the saga instance")]
+    SagaStateError,
+
+    #[error("Failed while creating the swap")]
+    CreateSwapError,
+    #[error("Swap legs need positive amounts and distinct users")]
+    InvalidSwapError,
+    #[error("Failed while fetching the swap")]
+    FetchSwapError,
+    #[error("Failed while funding the swap")]
+    FundSwapError,
+    #[error("Operation not allowed in the current state of the swap")]
+    SwapStateError,
+    #[error("Failed while cancelling the swap")]
+    CancelSwapError,
+
+    #[error("Failed while creating the schedule")]
+    CreateScheduleError,
+    #[error(
+        "Schedule needs a positive amount, a start that isn't in the past and existing accounts"
+    )]
+    InvalidScheduleError,
+    #[error("Failed while fetching the schedule")]
+    FetchScheduleError,
+    #[error("Failed while updating the schedule")]
+    UpdateScheduleError,
+    #[error("Failed while deleting the schedule")]
+    DeleteScheduleError,
+    #[error("Schedule is being run, try again shortly")]
+    ScheduleBusyError,
+
+    #[error("Request must carry a signer, signature, timestamp and nonce")]
+    MissingSignatureError,
+    #[error("Request signature is invalid")]
+    InvalidSignatureError,
+    #[error("Request timestamp is outside the accepted window")]
+    StaleSignatureError,
+    #[error("Request nonce was already used")]
+    ReplayedRequestError,
+    #[error("Caller is not allowed to act on this resource")]
+    ForbiddenError,
+
+    #[error("Request must be signed or carry a session token")]
+    UnauthenticatedError,
+    #[error("Session token is invalid or expired")]
+    InvalidSessionError,
+    #[error("Failed while issuing the login challenge")]
+    ChallengeError,
+    #[error("Login challenge is unknown, expired or not signed by the subject")]
+    LoginError,
+    #[error("Failed while logging out")]
+    LogoutError,
+
+    #[error("Failed while listing the role grants")]
+    ListRolesError,
+    #[error("Failed while granting the role")]
+    GrantRoleError,
+    #[error("Failed while revoking the role")]
+    RevokeRoleError,
+    #[error("Role grant is invalid")]
+    InvalidRoleError,
+
+    #[error("Debit limit of the access grant would be exceeded")]
+    DebitLimitError,
+    #[error("Failed while fetching the permissions")]
+    FetchPermissionsError,
+    #[error("Failed while updating the permissions")]
+    UpdatePermissionsError,
+    #[error("Permissions must be written as `vdct`, with `-` for those not given")]
+    InvalidPermissionsError,
+    #[error("Failed while fetching the asset")]
+    FetchAssetError,
+    #[error("Failed while listing the assets")]
+    ListAssetsError,
+
+    #[error("{0}")]
+    PolicyRejectedError(String),
+    #[error("Failed while creating the policy")]
+    CreatePolicyError,
+    #[error("Policy target or rule is invalid")]
+    InvalidPolicyError,
+    #[error("Failed while fetching the policy")]
+    FetchPolicyError,
+    #[error("Failed while deleting the policy")]
+    DeletePolicyError,
+
+    #[error("Failed while fetching the multisig setup")]
+    FetchMultisigError,
+    #[error("Failed while updating the multisig setup")]
+    UpdateMultisigError,
+    #[error("Multisig needs distinct registered signers and a quorum between one and all of them")]
+    InvalidMultisigError,
+    #[error("Failed while requesting the approval")]
+    RequestApprovalError,
+    #[error("Failed while fetching the approval")]
+    FetchApprovalError,
+    #[error("Approval is no longer pending or was already voted on")]
+    ApprovalStateError,
+    #[error(
+        "Amount is above the multisig threshold of the account, transfer assets one at a time to have them approved"
+    )]
+    ApprovalRequiredError,
+    #[error("Failed while voting on the approval")]
+    VoteApprovalError,
+
+    #[error("Amount must be greater than zero")]
+    InvalidAmountError,
+    #[error("Insufficient funds in the account")]
+    InsufficientFundsError,
+    #[error("Asset is locked by another holder")]
+    AssetLockedError,
+    #[error("Account not found")]
+    AccountNotFoundError,
+    #[error("Asset not found")]
+    AssetNotFoundError,
+
+    #[error("Request body could not be read or is too large")]
+    RequestBodyError,
+    #[error("Request is invalid")]
+    InvalidRequestError(Vec<crate::validation::Violation>),
+    #[error("Failed while fetching the audit trail")]
+    FetchAuditError,
+
+    /// Failure of the storage behind `context`, answered with the status it calls for.
+    #[error("{error}")]
+    StorageError {
+        context: Box<ApiError>,
+        error: StorageError,
+    },
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> axum::response::Response {
+impl ApiError {
+    pub fn status_code(&self) -> StatusCode {
         match self {
-            ApiError::CreateUserError => {
-                axum::response::Json("Error while creating the user").into_response()
-            }
-            ApiError::GetUserError => axum::response::Json("Get user error").into_response(),
-            ApiError::CreateTokenManagerError => {
-                axum::response::Json("Error while creating a token manager").into_response()
-            }
-            ApiError::ListTokenManagersError => {
-                axum::response::Json("Error while listing the token managers").into_response()
-            }
-            ApiError::NotImplemented => (
-                axum::http::StatusCode::NOT_IMPLEMENTED,
-                axum::response::Json("not implemented"),
-            )
-                .into_response(),
-            ApiError::AccountCreationError => {
-                axum::response::Json("Account Creation Error").into_response()
-            }
-            ApiError::AssetTypeNotSupportedError => {
-                axum::response::Json("Asset type not supported by the token manager")
-                    .into_response()
-            }
-            ApiError::CreateAssetError => {
-                axum::response::Json("Failed while creating assets").into_response()
-            }
-            ApiError::ActionAssetError => {
-                axum::response::Json("Failed to act on the asset").into_response()
-            }
-            ApiError::CreateSupportedAssetError => {
-                axum::response::Json("Failed while creating supported asset").into_response()
-            },
-            ApiError::FetchAccountError => {
-                axum::response::Json("Failed while fetching the account").into_response()
+            Self::InvalidWebhookUrlError
+            | Self::InvalidWorkflowModuleError
+            | Self::InvalidPathError
+            | Self::InvalidSagaError
+            | Self::InvalidSwapError
+            | Self::InvalidScheduleError
+            | Self::MissingSignatureError
+            | Self::InvalidRoleError
+            | Self::InvalidPermissionsError
+            | Self::InvalidPolicyError
+            | Self::InvalidMultisigError
+            | Self::InvalidSweepTargetError
+            | Self::RequestBodyError
+            | Self::InvalidRequestError(_) => StatusCode::BAD_REQUEST,
+            Self::InvalidSignatureError
+            | Self::StaleSignatureError
+            | Self::UnauthenticatedError
+            | Self::InvalidSessionError
+            | Self::LoginError => StatusCode::UNAUTHORIZED,
+            Self::ForbiddenError | Self::DescriptorPurposeError => StatusCode::FORBIDDEN,
+            Self::DescriptorNotFoundError
+            | Self::MerkleRootUnavailableError
+            | Self::AssetNotCommittedError
+            | Self::AccountNotFoundError
+            | Self::AssetNotFoundError => StatusCode::NOT_FOUND,
+            Self::ReplayedRequestError
+            | Self::AccountLockedError
+            | Self::AssetLockedError
+            | Self::AccountNotEmptyError
+            | Self::UserClosedError
+            | Self::TokenManagerDeactivatedError
+            | Self::SupportedAssetDeprecatedError
+            | Self::SagaStateError
+            | Self::SwapStateError
+            | Self::ScheduleBusyError
+            | Self::ApprovalStateError => StatusCode::CONFLICT,
+            Self::AssetTypeNotSupportedError
+            | Self::DebitLimitError
+            | Self::ApprovalRequiredError
+            | Self::InvalidAmountError
+            | Self::InsufficientFundsError
+            | Self::PolicyRejectedError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::NotImplemented => StatusCode::NOT_IMPLEMENTED,
+            Self::StorageError { error, .. } => error.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Stable, machine readable name of the error, e.g. `create_user` or `user_not_found`.
+    pub fn code(&self) -> &'static str {
+        let name: &'static str = match self {
+            Self::StorageError { error, .. } => error.into(),
+            _ => self.into(),
+        };
+
+        name.strip_suffix("_error").unwrap_or(name)
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::StorageError { context, .. } => Some(serde_json::json!({
+                "operation": context.code(),
+                "operation_message": context.to_string(),
+            })),
+            Self::InvalidRequestError(violations) => {
+                Some(serde_json::json!({ "violations": violations }))
             }
+            _ => None,
         }
     }
 }
 
+/// Body of every error response.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ErrorResponse {
+    /// Stable, machine readable name of the error, e.g. `user_not_found`.
+    pub code: &'static str,
+    pub message: String,
+    /// Further information on some errors, such as the `violations` of an invalid request.
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        let body = ErrorResponse {
+            code: self.code(),
+            message: self.to_string(),
+            details: self.details(),
+            request_id: crate::request::current_id(),
+        };
+
+        let mut response = (self.status_code(), axum::response::Json(body)).into_response();
+
+        // kept on the response, so that outer layers such as the audit trail can tell failures
+        // apart from successful responses
+        response.extensions_mut().insert(self);
+
+        response
+    }
+}
+
 #[track_caller]
 pub fn log_convert(e: error_stack::Report<ApiError>) -> ApiError {
     error!(?e);
 
-    *e.current_context()
+    let context = e.current_context().clone();
+
+    // a client error of the storage underneath, such as a missing user, says more than a generic
+    // failure of the handler
+    match e.downcast_ref::<StorageError>() {
+        Some(error)
+            if context.status_code().is_server_error()
+                && !error.status_code().is_server_error() =>
+        {
+            ApiError::StorageError {
+                context: Box::new(context),
+                error: *error,
+            }
+        }
+        _ => context,
+    }
+}
+
+/// Like [`log_convert`], surfacing the rejection of a policy, which names the rule that failed, in
+/// place of `fallback`.
+#[track_caller]
+pub fn policy_convert(e: error_stack::Report<PolicyError>, fallback: ApiError) -> ApiError {
+    let context = match e.current_context() {
+        PolicyError::Rejected { .. } => {
+            ApiError::PolicyRejectedError(e.current_context().to_string())
+        }
+        _ => fallback,
+    };
+
+    log_convert(e.change_context(context))
+}
+
+/// Like [`policy_convert`], for ledger operations, telling apart what the caller can act on from
+/// actual failures.
+#[track_caller]
+pub fn ledger_convert(e: error_stack::Report<LedgerError>, fallback: ApiError) -> ApiError {
+    let context = match e.current_context() {
+        LedgerError::InvalidAmount => ApiError::InvalidAmountError,
+        LedgerError::InsufficientFunds => ApiError::InsufficientFundsError,
+        LedgerError::AccountNotFound => ApiError::AccountNotFoundError,
+        LedgerError::AssetNotFound => ApiError::AssetNotFoundError,
+        LedgerError::AccountLocked => ApiError::AccountLockedError,
+        LedgerError::AssetLocked => ApiError::AssetLockedError,
+        LedgerError::PolicyRejected(reason) => ApiError::PolicyRejectedError(reason.clone()),
+        LedgerError::TokenManagerDeactivated => ApiError::TokenManagerDeactivatedError,
+        LedgerError::ApprovalRequired => ApiError::ApprovalRequiredError,
+        LedgerError::StorageError => fallback,
+    };
+
+    log_convert(e.change_context(context))
+}
+
+#[cfg(test)]
+mod tests {
+    use error_stack::report;
+
+    use super::*;
+
+    #[test]
+    fn answers_ledger_errors_with_their_own_status() {
+        let cases = [
+            (
+                LedgerError::InvalidAmount,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_amount",
+            ),
+            (
+                LedgerError::InsufficientFunds,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "insufficient_funds",
+            ),
+            (
+                LedgerError::AccountLocked,
+                StatusCode::CONFLICT,
+                "account_locked",
+            ),
+            (
+                LedgerError::AssetLocked,
+                StatusCode::CONFLICT,
+                "asset_locked",
+            ),
+            (
+                LedgerError::AccountNotFound,
+                StatusCode::NOT_FOUND,
+                "account_not_found",
+            ),
+            (
+                LedgerError::AssetNotFound,
+                StatusCode::NOT_FOUND,
+                "asset_not_found",
+            ),
+        ];
+
+        for (error, status, code) in cases {
+            let converted = ledger_convert(report!(error), ApiError::ActionAssetError);
+
+            assert_eq!(converted.status_code(), status, "{code}");
+            assert_eq!(converted.code(), code);
+        }
+    }
+
+    #[test]
+    fn falls_back_on_storage_failures() {
+        let converted = ledger_convert(
+            report!(LedgerError::StorageError),
+            ApiError::ActionAssetError,
+        );
+
+        assert_eq!(converted.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(converted.code(), "action_asset");
+    }
+
+    #[test]
+    fn surfaces_client_errors_of_the_storage_behind_a_failure() {
+        let converted = log_convert(
+            report!(StorageError::AccountNotFoundError)
+                .change_context(ApiError::UpdateAccountError),
+        );
+
+        assert_eq!(converted.status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(converted.code(), "account_not_found");
+    }
+
+    #[test]
+    fn carries_the_status_into_the_response() {
+        let response = ApiError::AssetLockedError.into_response();
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(response.extensions().get::<ApiError>().is_some());
+    }
 }
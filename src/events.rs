@@ -0,0 +1,128 @@
+use nanoid::nanoid;
+
+use crate::error::{SResult, StorageError};
+use crate::state::AppState;
+use crate::storage::types::{
+    AssetCustody, AssetInfo, Counterparty, Delivery, DeliveryStatus, Event, EventType, SyncPayload,
+};
+use crate::utils::unix_timestamp;
+
+/// An operation on an asset that has already been committed to storage.
+pub struct AssetActivity {
+    pub event_type: EventType,
+    pub user_id: String,
+    pub account_id: String,
+    pub asset_id: String,
+    pub asset_info: AssetInfo,
+    pub counterparty: Option<Counterparty>,
+}
+
+/// Publishes the activity to the token managers tied to the accounts involved, returning the id
+/// of the recorded event.
+///
+/// Every event is committed to the proof store first. Besides webhooks, changes to custodial
+/// assets are appended to the sync outbox of the token manager.
+///
+/// Publishing is part of the operation on the asset, a failure is returned to the caller so that
+/// the operation is reported as failed rather than leaving a gap in the log or the outbox.
+pub async fn publish(
+    app_state: &AppState,
+    activity: AssetActivity,
+) -> SResult<String, StorageError> {
+    let user_interface = app_state.storage.get_user_interface().await?;
+
+    let user = user_interface.get_user(&activity.user_id).await?;
+
+    let (account, _) = user_interface
+        .get_account_interface(&activity.user_id)
+        .await?
+        .get_account(&activity.account_id)
+        .await?;
+
+    let mut involved = vec![(account.token_manager_id.clone(), account.asset_type)];
+
+    let event = Event {
+        event_id: nanoid!(),
+        event_type: activity.event_type,
+        token_manager_id: account.token_manager_id,
+        user_id: activity.user_id,
+        ua_addr: user.ua_addr,
+        account_id: activity.account_id,
+        asset_id: activity.asset_id,
+        asset_info: activity.asset_info,
+        counterparty: activity.counterparty,
+        created_at: unix_timestamp(),
+    };
+
+    if let Some(counterparty) = &event.counterparty {
+        let (peer_account, _) = user_interface
+            .get_account_interface_by_ua(&counterparty.ua_addr)
+            .await?
+            .get_account(&counterparty.account_id)
+            .await?;
+
+        let peer = (peer_account.token_manager_id, peer_account.asset_type);
+
+        if !involved.contains(&peer) {
+            involved.push(peer);
+        }
+    }
+
+    let sync_interface = app_state.storage.get_sync_interface().await?;
+
+    // everything that may fail is looked up before anything is written
+    let mut custodial = Vec::new();
+
+    for (token_manager_id, asset_type) in &involved {
+        if crate::sync::custody_of(app_state, token_manager_id, asset_type).await?
+            == AssetCustody::Custodial
+        {
+            custodial.push(token_manager_id.clone());
+        }
+    }
+
+    app_state.proofs.append(event.clone()).await?;
+
+    for token_manager_id in custodial {
+        sync_interface
+            .append(
+                &token_manager_id,
+                SyncPayload::Change(Box::new(event.clone())),
+            )
+            .await?;
+    }
+
+    let mut token_managers: Vec<String> = Vec::new();
+
+    for (token_manager_id, _) in involved {
+        if !token_managers.contains(&token_manager_id) {
+            token_managers.push(token_manager_id);
+        }
+    }
+
+    let webhook_interface = app_state.storage.get_webhook_interface().await?;
+
+    for token_manager_id in token_managers {
+        for (webhook_id, webhook) in webhook_interface.list_webhooks(&token_manager_id).await? {
+            if !webhook.accepts(event.event_type) {
+                continue;
+            }
+
+            webhook_interface
+                .enqueue_delivery(Delivery {
+                    delivery_id: nanoid!(),
+                    webhook_id,
+                    token_manager_id: token_manager_id.clone(),
+                    event: event.clone(),
+                    status: DeliveryStatus::Pending,
+                    attempts: 0,
+                    next_attempt_at: event.created_at,
+                    last_attempt_at: None,
+                    last_error: None,
+                })
+                .await?;
+        }
+    }
+
+    Ok(event.event_id)
+}
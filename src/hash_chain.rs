@@ -0,0 +1,119 @@
+//! Hash chain shared by the proof store and the audit trail.
+//!
+//! Every link commits to its position, the hash of the link before it, the time it was recorded
+//! and its payload, so that editing or dropping a link breaks every hash after it.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+/// Previous hash committed to by the first link of a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Link of a chain, carrying the payload it commits to.
+pub trait Link {
+    type Payload: Serialize;
+
+    /// What a link is called in violations, such as `entry`.
+    const NAME: &'static str;
+    /// Field the payload is hashed under.
+    const PAYLOAD_FIELD: &'static str;
+
+    fn index(&self) -> u64;
+    fn previous_hash(&self) -> &str;
+    fn hash(&self) -> &str;
+    fn recorded_at(&self) -> u64;
+    fn payload(&self) -> &Self::Payload;
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ChainViolation {
+    pub index: u64,
+    pub reason: String,
+}
+
+struct HashInput<'a, P> {
+    index: u64,
+    previous_hash: &'a str,
+    recorded_at: u64,
+    payload_field: &'static str,
+    payload: &'a P,
+}
+
+// written out by hand so that the payload keeps the field name it has always been hashed under
+impl<P: Serialize> Serialize for HashInput<'_, P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut input = serializer.serialize_struct("HashInput", 4)?;
+        input.serialize_field("index", &self.index)?;
+        input.serialize_field("previous_hash", self.previous_hash)?;
+        input.serialize_field("recorded_at", &self.recorded_at)?;
+        input.serialize_field(self.payload_field, self.payload)?;
+        input.end()
+    }
+}
+
+/// Hash of a link, committing to its position, the previous hash and the payload.
+pub fn hash<L: Link>(
+    index: u64,
+    previous_hash: &str,
+    recorded_at: u64,
+    payload: &L::Payload,
+) -> Result<String, serde_json::Error> {
+    let input = serde_json::to_vec(&HashInput {
+        index,
+        previous_hash,
+        recorded_at,
+        payload_field: L::PAYLOAD_FIELD,
+        payload,
+    })?;
+
+    Ok(hex::encode(Sha256::digest(input)))
+}
+
+/// Hash the next link of the chain has to commit to.
+pub fn head<L: Link>(links: &[L]) -> String {
+    links
+        .last()
+        .map(|link| link.hash().to_string())
+        .unwrap_or_else(|| GENESIS_HASH.to_string())
+}
+
+/// Walks the chain from genesis, recomputing every hash and link.
+pub fn verify<L: Link>(links: &[L]) -> Result<(), ChainViolation> {
+    let mut previous_hash = GENESIS_HASH;
+
+    for (position, link) in links.iter().enumerate() {
+        let violation = |reason: &str| ChainViolation {
+            index: link.index(),
+            reason: format!("{} {reason}", L::NAME),
+        };
+
+        if link.index() != position as u64 {
+            return Err(violation("is out of sequence"));
+        }
+
+        if link.previous_hash() != previous_hash {
+            return Err(violation(&format!(
+                "does not link to the previous {}",
+                L::NAME
+            )));
+        }
+
+        let hash = hash::<L>(
+            link.index(),
+            link.previous_hash(),
+            link.recorded_at(),
+            link.payload(),
+        )
+        .map_err(|_| violation("could not be hashed"))?;
+
+        if hash != link.hash() {
+            return Err(violation("hash does not match its contents"));
+        }
+
+        previous_hash = link.hash();
+    }
+
+    Ok(())
+}
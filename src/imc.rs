@@ -1,18 +1,38 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 
 use tokio::sync::RwLock;
 
 use crate::config::BackupConfig;
 use crate::error::{SResult, StorageError};
-use crate::storage::types::{AssetInfo, AssetType, TokenManagerRef};
+use crate::storage::types::{
+    Acl, Approval, AssetCustody, AssetInfo, AssetType, Challenge, Delivery, Descriptor, Lock,
+    Mount, Multisig, Policy, PolicyUsage, RoleGrant, SagaDefinition, SagaInstance, Schedule,
+    Session, Swap, SyncRecord, TokenManagerRef, Webhook, Workflow,
+};
 
+mod page;
 mod storage_impl;
 
 #[derive(Clone)]
 pub struct Storage {
     users: UserStore,
     token_managers: TokenManagerStore,
+    webhooks: WebhookStore,
+    sync: SyncStore,
+    workflows: WorkflowStore,
+    descriptors: DescriptorStore,
+    locks: LockStore,
+    mounts: MountStore,
+    sagas: SagaStore,
+    swaps: SwapStore,
+    schedules: ScheduleStore,
+    nonces: NonceStore,
+    sessions: SessionStore,
+    roles: RoleStore,
+    acls: AclStore,
+    policies: PolicyStore,
+    multisigs: MultisigStore,
 }
 
 #[derive(Clone)]
@@ -26,12 +46,106 @@ pub struct TokenManagerStore {
     map: Arc<RwLock<HashMap<String, TokenManager>>>,
 }
 
+#[derive(Clone)]
+pub struct WebhookStore {
+    webhooks: Arc<RwLock<HashMap<String, Webhook>>>,
+    deliveries: Arc<RwLock<HashMap<String, Delivery>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct WorkflowStore {
+    map: Arc<RwLock<HashMap<String, Workflow>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct DescriptorStore {
+    map: Arc<RwLock<HashMap<String, Descriptor>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct LockStore {
+    map: Arc<RwLock<HashMap<String, Lock>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct MountStore {
+    map: Arc<RwLock<HashMap<String, Mount>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct SagaStore {
+    sagas: Arc<RwLock<HashMap<String, SagaDefinition>>>,
+    instances: Arc<RwLock<HashMap<String, SagaInstance>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct SwapStore {
+    map: Arc<RwLock<HashMap<String, Swap>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct ScheduleStore {
+    map: Arc<RwLock<HashMap<String, Schedule>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct NonceStore {
+    map: Arc<RwLock<HashMap<String, u64>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct SessionStore {
+    challenges: Arc<RwLock<HashMap<String, Challenge>>>,
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct RoleStore {
+    map: Arc<RwLock<HashMap<String, RoleGrant>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct AclStore {
+    map: Arc<RwLock<HashMap<String, Acl>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct PolicyStore {
+    map: Arc<RwLock<HashMap<String, Policy>>>,
+    usage: Arc<RwLock<PolicyUsageMap>>,
+}
+
+/// Usage by policy id and resource.
+type PolicyUsageMap = HashMap<(String, String), Vec<PolicyUsage>>;
+
+#[derive(Clone, Default)]
+pub struct MultisigStore {
+    map: Arc<RwLock<HashMap<String, Multisig>>>,
+    approvals: Arc<RwLock<HashMap<String, Approval>>>,
+}
+
+#[derive(Clone)]
+pub struct SyncStore {
+    map: Arc<RwLock<HashMap<String, Outbox>>>,
+}
+
+#[derive(Default)]
+pub struct Outbox {
+    pub last_sequence: u64,
+    pub acknowledged_sequence: u64,
+    records: VecDeque<SyncRecord>,
+}
+
 pub struct User {
     pub id: String,
     pub name: String,
     pub email: String,
     pub public_key: String,
     pub ua_addr: String,
+    pub jurisdiction: Option<String>,
+    pub previous_keys: Vec<crate::storage::types::RetiredKey>,
+    pub status: crate::storage::types::UserStatus,
+    pub closed_at: Option<u64>,
     accounts: AccountStore,
 }
 
@@ -39,6 +153,11 @@ pub struct TokenManager {
     pub id: String,
     pub token_manager_name: String,
     pub public_key: String,
+    pub sync_url: Option<String>,
+    pub created_at: u64,
+    pub previous_keys: Vec<crate::storage::types::RetiredKey>,
+    pub status: crate::storage::types::TokenManagerStatus,
+    pub deactivated_at: Option<u64>,
     supported_assets: SupportedAssetStore,
 }
 
@@ -53,6 +172,7 @@ pub struct Account {
     pub account_name: String,
     pub token_manager_ref: TokenManagerRef,
     pub asset_type: AssetType,
+    pub created_at: u64,
     assets: AssetStore,
 }
 
@@ -64,7 +184,11 @@ pub struct SupportedAssetStore {
 pub struct SupportedAsset {
     pub id: String,
     pub asset_type: AssetType,
-    pub smart_contract_refs: Vec<u8>,
+    pub smart_contract_refs: Vec<crate::storage::types::ContractRef>,
+    pub custody: AssetCustody,
+    pub created_at: u64,
+    pub status: crate::storage::types::SupportedAssetStatus,
+    pub deprecated_at: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -89,6 +213,47 @@ impl Default for AssetStore {
 pub struct Asset {
     pub id: String,
     pub asset_info: AssetInfo,
+    pub created_at: u64,
+}
+
+impl User {
+    fn to_storage(&self) -> crate::storage::types::User {
+        crate::storage::types::User {
+            ua_addr: self.ua_addr.clone(),
+            email: self.email.clone(),
+            name: self.name.clone(),
+            public_key: self.public_key.clone(),
+            jurisdiction: self.jurisdiction.clone(),
+            previous_keys: self.previous_keys.clone(),
+            status: self.status,
+            closed_at: self.closed_at,
+        }
+    }
+}
+
+impl TokenManager {
+    fn to_storage(&self) -> crate::storage::types::TokenManager {
+        crate::storage::types::TokenManager {
+            token_manager_name: self.token_manager_name.clone(),
+            public_key: self.public_key.clone(),
+            sync_url: self.sync_url.clone(),
+            previous_keys: self.previous_keys.clone(),
+            status: self.status,
+            deactivated_at: self.deactivated_at,
+        }
+    }
+}
+
+impl SupportedAsset {
+    fn to_storage(&self) -> crate::storage::types::SupportedAsset {
+        crate::storage::types::SupportedAsset {
+            asset_type: self.asset_type.clone(),
+            smart_contract_refs: self.smart_contract_refs.clone(),
+            custody: self.custody,
+            status: self.status,
+            deprecated_at: self.deprecated_at,
+        }
+    }
 }
 
 impl AccountStore {
@@ -117,6 +282,21 @@ impl Storage {
             token_managers: TokenManagerStore {
                 map: Arc::new(RwLock::new(HashMap::new())),
             },
+            webhooks: WebhookStore::default(),
+            sync: SyncStore::default(),
+            workflows: WorkflowStore::default(),
+            descriptors: DescriptorStore::default(),
+            locks: LockStore::default(),
+            mounts: MountStore::default(),
+            sagas: SagaStore::default(),
+            swaps: SwapStore::default(),
+            schedules: ScheduleStore::default(),
+            nonces: NonceStore::default(),
+            sessions: SessionStore::default(),
+            roles: RoleStore::default(),
+            acls: AclStore::default(),
+            policies: PolicyStore::default(),
+            multisigs: MultisigStore::default(),
         }
     }
     pub fn setup_disk_backup(&self, _config: &BackupConfig) -> SResult<(), StorageError> {
@@ -166,3 +346,20 @@ impl Default for SupportedAssetStore {
         }
     }
 }
+
+impl Default for WebhookStore {
+    fn default() -> Self {
+        Self {
+            webhooks: Arc::new(RwLock::new(HashMap::new())),
+            deliveries: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl Default for SyncStore {
+    fn default() -> Self {
+        Self {
+            map: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Cursor pagination of the listings held in memory.
+
+use std::fmt::Display;
+
+use error_stack::{ensure, report, ResultExt};
+
+use crate::error::{SResult, StorageError};
+use crate::storage::types::{Entry, Page, PageQuery, SortOrder};
+
+/// Value of the sort key of an entry.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SortKey {
+    Number(u64),
+    Text(String),
+}
+
+/// Orders the entries by `key`, ties broken by id, and cuts out the page `query` asks for.
+pub fn paginate<S: Display, T>(
+    entries: Vec<Entry<T>>,
+    query: &PageQuery<S>,
+    key: impl Fn(&Entry<T>) -> SortKey,
+) -> SResult<Page<T>, StorageError> {
+    let sort = query.sort.to_string();
+
+    let after = query
+        .cursor
+        .as_deref()
+        .map(|cursor| decode_cursor(cursor, &sort, query.order))
+        .transpose()?;
+
+    let mut entries = entries
+        .into_iter()
+        .map(|entry| ((key(&entry), entry.id.clone()), entry))
+        .collect::<Vec<_>>();
+
+    entries.sort_by(|(left, _), (right, _)| match query.order {
+        SortOrder::Asc => left.cmp(right),
+        SortOrder::Desc => right.cmp(left),
+    });
+
+    let start = after.map_or(0, |after| {
+        entries.partition_point(|(position, _)| match query.order {
+            SortOrder::Asc => *position <= after,
+            SortOrder::Desc => *position >= after,
+        })
+    });
+
+    let mut page = entries
+        .into_iter()
+        .skip(start)
+        .take(query.limit + 1)
+        .collect::<Vec<_>>();
+
+    // the entry past the limit only tells that there is a next page
+    let next_cursor = match page.len() > query.limit {
+        true => {
+            page.truncate(query.limit);
+            page.last()
+                .map(|((key, id), _)| encode_cursor(&sort, query.order, key, id))
+        }
+        false => None,
+    };
+
+    Ok(Page {
+        items: page.into_iter().map(|(_, entry)| entry).collect(),
+        next_cursor,
+    })
+}
+
+/// Position of the last entry of a page, along with the sort and order of the listing so that it
+/// isn't taken up by another one. The key goes last as names may hold any character.
+fn encode_cursor(sort: &str, order: SortOrder, key: &SortKey, id: &str) -> String {
+    let key = match key {
+        SortKey::Number(number) => format!("n{number}"),
+        SortKey::Text(text) => format!("s{text}"),
+    };
+
+    hex::encode(format!("{sort}\n{order}\n{id}\n{key}"))
+}
+
+fn decode_cursor(
+    cursor: &str,
+    sort: &str,
+    order: SortOrder,
+) -> SResult<(SortKey, String), StorageError> {
+    let bytes = hex::decode(cursor).change_context(StorageError::InvalidCursorError)?;
+    let cursor = String::from_utf8(bytes).change_context(StorageError::InvalidCursorError)?;
+
+    let mut parts = cursor.splitn(4, '\n');
+    let (Some(cursor_sort), Some(cursor_order), Some(id), Some(key)) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(report!(StorageError::InvalidCursorError));
+    };
+
+    ensure!(
+        cursor_sort == sort && cursor_order == order.to_string(),
+        StorageError::InvalidCursorError
+    );
+
+    let key = match key.split_at_checked(1) {
+        Some(("n", number)) => SortKey::Number(
+            number
+                .parse::<u64>()
+                .change_context(StorageError::InvalidCursorError)?,
+        ),
+        Some(("s", text)) => SortKey::Text(text.to_string()),
+        _ => return Err(report!(StorageError::InvalidCursorError)),
+    };
+
+    Ok((key, id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seven entries, created in pairs so that the id has to break the ties.
+    fn entries() -> Vec<Entry<u64>> {
+        (0..7)
+            .map(|index| Entry {
+                id: format!("id-{index}"),
+                created_at: 100 + index / 2,
+                item: index,
+            })
+            .collect()
+    }
+
+    fn query(cursor: Option<String>, order: SortOrder) -> PageQuery<&'static str> {
+        PageQuery {
+            cursor,
+            limit: 3,
+            sort: "created_at",
+            order,
+        }
+    }
+
+    /// Follows the cursors until the last page, returning the items in the order they came.
+    fn walk(order: SortOrder) -> Vec<u64> {
+        let mut items = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let page = paginate(entries(), &query(cursor, order), |entry| {
+                SortKey::Number(entry.created_at)
+            })
+            .unwrap();
+
+            assert!(page.items.len() <= 3);
+            items.extend(page.items.into_iter().map(|entry| entry.item));
+
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return items,
+            }
+        }
+    }
+
+    #[test]
+    fn walks_every_entry_once_in_order() {
+        assert_eq!(walk(SortOrder::Asc), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(walk(SortOrder::Desc), [6, 5, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn ends_without_a_cursor_on_a_full_last_page() {
+        let mut query = query(None, SortOrder::Asc);
+        query.limit = 7;
+
+        let page = paginate(entries(), &query, |entry| SortKey::Number(entry.created_at)).unwrap();
+
+        assert_eq!(page.items.len(), 7);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn carries_text_keys_holding_any_character() {
+        let entries = ["b\nb", "a\na", "c\nc"]
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| Entry {
+                id: format!("id-{index}"),
+                created_at: 0,
+                item: name.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let mut query = query(None, SortOrder::Asc);
+        query.limit = 1;
+
+        let first = paginate(entries.clone(), &query, |entry| {
+            SortKey::Text(entry.item.clone())
+        })
+        .unwrap();
+        query.cursor = first.next_cursor;
+        let second = paginate(entries, &query, |entry| SortKey::Text(entry.item.clone())).unwrap();
+
+        assert_eq!(first.items[0].item, "a\na");
+        assert_eq!(second.items[0].item, "b\nb");
+    }
+
+    #[test]
+    fn refuses_cursors_of_another_listing() {
+        let first = paginate(entries(), &query(None, SortOrder::Asc), |entry| {
+            SortKey::Number(entry.created_at)
+        })
+        .unwrap();
+
+        for cursor in [first.next_cursor.unwrap(), "not a cursor".to_string()] {
+            let error = paginate(entries(), &query(Some(cursor), SortOrder::Desc), |entry| {
+                SortKey::Number(entry.created_at)
+            })
+            .unwrap_err();
+
+            assert_eq!(*error.current_context(), StorageError::InvalidCursorError);
+        }
+    }
+}
@@ -6,15 +6,30 @@ use nanoid::nanoid;
 
 use crate::error::{SResult, StorageError};
 use crate::imc::User;
-use crate::storage::types::{AssetInfo, TotalAssets};
+use crate::storage::types::{
+    AccessGrant, AccountFilter, AccountSort, Acl, Approval, AssetFilter, AssetInfo, AssetSort,
+    AssetType, Challenge, Delivery, DeliveryStatus, Descriptor, Entry, Lock, Mode, Mount, Multisig,
+    Page, PageQuery, Policy, PolicyUsage, RoleGrant, SagaDefinition, SagaInstance, SagaStatus,
+    Schedule, ScheduleStatus, Session, SupportedAssetFilter, SupportedAssetSort,
+    SupportedAssetStatus, Swap, SyncPayload, SyncRecord, SyncStatus, TokenManagerFilter,
+    TokenManagerSort, TokenManagerStatus, TotalAssets, UserStatus, Webhook, Workflow,
+};
 use crate::storage::{
-    AccountInterface, AssetInterface, StorageInterface, SupportedAssetInterface,
-    TokenManagerInterface, UserInterface,
+    AccountInterface, AclInterface, AssetInterface, DescriptorInterface, LockInterface,
+    MountInterface, MultisigInterface, NonceInterface, PolicyInterface, RoleInterface,
+    SagaInterface, ScheduleInterface, SessionInterface, StorageInterface, SupportedAssetInterface,
+    SwapInterface, SyncInterface, TokenManagerInterface, UserInterface, WebhookInterface,
+    WorkflowInterface,
 };
 
+use super::page::{paginate, SortKey};
 use super::{
-    AccountStore, Asset, AssetStore, Storage, SupportedAssetStore, TokenManagerStore, UserStore,
+    AccountStore, AclStore, Asset, AssetStore, DescriptorStore, LockStore, MountStore,
+    MultisigStore, NonceStore, PolicyStore, RoleStore, SagaStore, ScheduleStore, SessionStore,
+    Storage, SupportedAssetStore, SwapStore, SyncStore, TokenManagerStore, UserStore, WebhookStore,
+    WorkflowStore,
 };
+use crate::utils::unix_timestamp;
 
 #[async_trait::async_trait]
 impl StorageInterface for Storage {
@@ -29,6 +44,96 @@ impl StorageInterface for Storage {
     ) -> SResult<Box<dyn TokenManagerInterface + Send + Sync>, StorageError> {
         Ok(Box::new(self.token_managers.clone()))
     }
+
+    async fn get_webhook_interface(
+        &self,
+    ) -> SResult<Box<dyn WebhookInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.webhooks.clone()))
+    }
+
+    async fn get_sync_interface(
+        &self,
+    ) -> SResult<Box<dyn SyncInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.sync.clone()))
+    }
+
+    async fn get_workflow_interface(
+        &self,
+    ) -> SResult<Box<dyn WorkflowInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.workflows.clone()))
+    }
+
+    async fn get_descriptor_interface(
+        &self,
+    ) -> SResult<Box<dyn DescriptorInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.descriptors.clone()))
+    }
+
+    async fn get_lock_interface(
+        &self,
+    ) -> SResult<Box<dyn LockInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.locks.clone()))
+    }
+
+    async fn get_mount_interface(
+        &self,
+    ) -> SResult<Box<dyn MountInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.mounts.clone()))
+    }
+
+    async fn get_saga_interface(
+        &self,
+    ) -> SResult<Box<dyn SagaInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.sagas.clone()))
+    }
+
+    async fn get_swap_interface(
+        &self,
+    ) -> SResult<Box<dyn SwapInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.swaps.clone()))
+    }
+
+    async fn get_schedule_interface(
+        &self,
+    ) -> SResult<Box<dyn ScheduleInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.schedules.clone()))
+    }
+
+    async fn get_nonce_interface(
+        &self,
+    ) -> SResult<Box<dyn NonceInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.nonces.clone()))
+    }
+
+    async fn get_session_interface(
+        &self,
+    ) -> SResult<Box<dyn SessionInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.sessions.clone()))
+    }
+
+    async fn get_role_interface(
+        &self,
+    ) -> SResult<Box<dyn RoleInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.roles.clone()))
+    }
+
+    async fn get_acl_interface(
+        &self,
+    ) -> SResult<Box<dyn AclInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.acls.clone()))
+    }
+
+    async fn get_policy_interface(
+        &self,
+    ) -> SResult<Box<dyn PolicyInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.policies.clone()))
+    }
+
+    async fn get_multisig_interface(
+        &self,
+    ) -> SResult<Box<dyn MultisigInterface + Send + Sync>, StorageError> {
+        Ok(Box::new(self.multisigs.clone()))
+    }
 }
 
 #[async_trait::async_trait]
@@ -52,6 +157,10 @@ impl UserInterface for UserStore {
             id: user_id.clone(),
             name: user.name,
             public_key: user.public_key,
+            jurisdiction: user.jurisdiction,
+            previous_keys: Vec::new(),
+            status: UserStatus::Active,
+            closed_at: None,
         };
 
         self.map.write().await.insert(user_id.clone(), new_user);
@@ -66,12 +175,51 @@ impl UserInterface for UserStore {
             .get(user_id)
             .ok_or(report!(StorageError::UserNotFoundError))?;
 
-        Ok(crate::storage::types::User {
-            ua_addr: user.ua_addr.clone(),
-            email: user.email.clone(),
-            name: user.name.clone(),
-            public_key: user.public_key.clone(),
-        })
+        Ok(user.to_storage())
+    }
+
+    async fn list_users(
+        &self,
+    ) -> SResult<Vec<(String, crate::storage::types::User)>, StorageError> {
+        let store = self.map.read().await;
+
+        Ok(store
+            .values()
+            .map(|user| (user.id.clone(), user.to_storage()))
+            .collect())
+    }
+
+    async fn update_user(
+        &self,
+        user_id: &str,
+        user: crate::storage::types::User,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let stored = store
+            .get_mut(user_id)
+            .ok_or(report!(StorageError::UserNotFoundError))?;
+
+        stored.name = user.name;
+        stored.email = user.email;
+        stored.public_key = user.public_key;
+        stored.previous_keys = user.previous_keys;
+
+        Ok(())
+    }
+
+    async fn close_user(&self, user_id: &str, closed_at: u64) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let user = store
+            .get_mut(user_id)
+            .ok_or(report!(StorageError::UserNotFoundError))?;
+
+        // the unique address stays in `set`, so that it can't be registered again
+        user.status = UserStatus::Closed;
+        user.closed_at = Some(closed_at);
+
+        Ok(())
     }
 
     async fn get_account_interface(
@@ -108,6 +256,16 @@ impl UserInterface for UserStore {
 
         Ok(set.contains(ua_addr))
     }
+
+    async fn get_user_id_by_ua(&self, ua_addr: &str) -> SResult<String, StorageError> {
+        let store = self.map.read().await;
+
+        store
+            .iter()
+            .find(|(_, user)| user.ua_addr == ua_addr)
+            .map(|(user_id, _)| user_id.clone())
+            .ok_or(report!(StorageError::UserNotFoundError))
+    }
 }
 
 #[async_trait::async_trait]
@@ -125,6 +283,7 @@ impl AccountInterface for AccountStore {
             token_manager_id: acc.token_manager_id,
             token_manager_ref: acc.token_manager_ref,
             asset_type: acc.asset_type,
+            created_at: unix_timestamp(),
         };
 
         self.map
@@ -151,21 +310,22 @@ impl AccountInterface for AccountStore {
             .get(account_id)
             .ok_or(report!(StorageError::AccountNotFoundError))?;
 
-        let all_assets: Vec<AssetInfo> = self
+        let all_assets: Vec<(String, AssetInfo)> = self
             .get_asset_interface(account_id)
             .await?
             .list_assets()
             .await?;
 
-        let output = all_assets
-            .into_iter()
-            .fold(TotalAssets::new(), |mut acc, cur| match cur {
-                AssetInfo::Cash { amount, currency } => {
-                    acc.money.amount += amount;
-                    acc.money.currency = currency;
-                    acc
-                }
-            });
+        let output =
+            all_assets
+                .into_iter()
+                .fold(TotalAssets::new(), |mut acc, (_, cur)| match cur {
+                    AssetInfo::Cash { amount, currency } => {
+                        acc.money.amount += amount;
+                        acc.money.currency = currency;
+                        acc
+                    }
+                });
 
         Ok((
             crate::storage::types::Account {
@@ -178,6 +338,113 @@ impl AccountInterface for AccountStore {
         ))
     }
 
+    async fn list_accounts(
+        &self,
+    ) -> SResult<Vec<(String, crate::storage::types::Account)>, StorageError> {
+        let store = self.map.read().await;
+
+        Ok(store
+            .values()
+            .map(|account| {
+                (
+                    account.id.clone(),
+                    crate::storage::types::Account {
+                        account_name: account.account_name.clone(),
+                        token_manager_id: account.token_manager_id.clone(),
+                        token_manager_ref: account.token_manager_ref.clone(),
+                        asset_type: account.asset_type.clone(),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn query_accounts(
+        &self,
+        query: &PageQuery<AccountSort>,
+        filter: &AccountFilter,
+    ) -> SResult<Page<crate::storage::types::Account>, StorageError> {
+        let store = self.map.read().await;
+
+        let entries = store
+            .values()
+            .filter(|account| {
+                filter
+                    .asset_type
+                    .as_ref()
+                    .is_none_or(|asset_type| &account.asset_type == asset_type)
+                    && filter
+                        .token_manager_id
+                        .as_ref()
+                        .is_none_or(|token_manager_id| {
+                            &account.token_manager_id == token_manager_id
+                        })
+                    && filter
+                        .created_after
+                        .is_none_or(|created_after| account.created_at > created_after)
+            })
+            .map(|account| Entry {
+                id: account.id.clone(),
+                created_at: account.created_at,
+                item: crate::storage::types::Account {
+                    account_name: account.account_name.clone(),
+                    token_manager_id: account.token_manager_id.clone(),
+                    token_manager_ref: account.token_manager_ref.clone(),
+                    asset_type: account.asset_type.clone(),
+                },
+            })
+            .collect();
+
+        paginate(entries, query, |entry| match query.sort {
+            AccountSort::CreatedAt => SortKey::Number(entry.created_at),
+            AccountSort::Name => SortKey::Text(entry.item.account_name.clone()),
+        })
+    }
+
+    async fn update_account(
+        &self,
+        account_id: &str,
+        acc: crate::storage::types::Account,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let account = store
+            .get_mut(account_id)
+            .ok_or(report!(StorageError::AccountNotFoundError))?;
+
+        account.account_name = acc.account_name;
+        account.token_manager_ref = acc.token_manager_ref;
+
+        Ok(())
+    }
+
+    async fn delete_account(
+        &self,
+        account_id: &str,
+    ) -> SResult<crate::storage::types::Account, StorageError> {
+        let mut store = self.map.write().await;
+
+        let account = store
+            .get(account_id)
+            .ok_or(report!(StorageError::AccountNotFoundError))?;
+
+        ensure!(
+            account.assets.map.read().await.is_empty(),
+            StorageError::AccountNotEmptyError
+        );
+
+        let account = store
+            .remove(account_id)
+            .ok_or(report!(StorageError::AccountNotFoundError))?;
+
+        Ok(crate::storage::types::Account {
+            account_name: account.account_name,
+            token_manager_id: account.token_manager_id,
+            token_manager_ref: account.token_manager_ref,
+            asset_type: account.asset_type,
+        })
+    }
+
     async fn get_asset_interface(
         &self,
         account_id: &str,
@@ -194,22 +461,65 @@ impl AccountInterface for AccountStore {
 
 #[async_trait::async_trait]
 impl AssetInterface for AssetStore {
-    async fn list_assets(&self) -> SResult<Vec<AssetInfo>, StorageError> {
+    async fn list_assets(&self) -> SResult<Vec<(String, AssetInfo)>, StorageError> {
         let output = self.map.read().await;
 
         Ok(output
             .values()
-            .map(|value| &value.asset_info)
-            .cloned()
+            .map(|value| (value.id.clone(), value.asset_info.clone()))
             .collect())
     }
 
+    async fn query_assets(
+        &self,
+        query: &PageQuery<AssetSort>,
+        filter: &AssetFilter,
+    ) -> SResult<Page<AssetInfo>, StorageError> {
+        let store = self.map.read().await;
+
+        let entries = store
+            .values()
+            .filter(|asset| {
+                let AssetInfo::Cash { currency, amount } = &asset.asset_info;
+
+                filter
+                    .asset_type
+                    .as_ref()
+                    .is_none_or(|asset_type| asset_type == &AssetType::Cash)
+                    && filter
+                        .currency
+                        .as_ref()
+                        .is_none_or(|filter| filter == currency)
+                    && filter
+                        .min_amount
+                        .is_none_or(|min_amount| *amount >= min_amount)
+                    && filter
+                        .max_amount
+                        .is_none_or(|max_amount| *amount <= max_amount)
+                    && filter
+                        .created_after
+                        .is_none_or(|created_after| asset.created_at > created_after)
+            })
+            .map(|asset| Entry {
+                id: asset.id.clone(),
+                created_at: asset.created_at,
+                item: asset.asset_info.clone(),
+            })
+            .collect();
+
+        paginate(entries, query, |entry| match (query.sort, &entry.item) {
+            (AssetSort::CreatedAt, _) => SortKey::Number(entry.created_at),
+            (AssetSort::Amount, AssetInfo::Cash { amount, .. }) => SortKey::Number(*amount),
+        })
+    }
+
     async fn create_asset(&self, asset: AssetInfo) -> SResult<String, StorageError> {
         let asset_id = nanoid!(5);
 
         let new_asset = Asset {
             id: asset_id.clone(),
             asset_info: asset,
+            created_at: unix_timestamp(),
         };
 
         self.map.write().await.insert(asset_id.clone(), new_asset);
@@ -226,6 +536,21 @@ impl AssetInterface for AssetStore {
 
         Ok(asset.asset_info)
     }
+
+    async fn restore_asset(&self, asset_id: &str, asset: AssetInfo) -> SResult<(), StorageError> {
+        let restored = Asset {
+            id: asset_id.to_string(),
+            asset_info: asset,
+            created_at: unix_timestamp(),
+        };
+
+        self.map
+            .write()
+            .await
+            .insert(asset_id.to_string(), restored);
+
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -239,8 +564,13 @@ impl TokenManagerInterface for TokenManagerStore {
         let new_token_manager = super::TokenManager {
             id: token_manager_id.clone(),
             public_key: token_manager.public_key,
+            sync_url: token_manager.sync_url,
             supported_assets: SupportedAssetStore::new(),
             token_manager_name: token_manager.token_manager_name,
+            created_at: unix_timestamp(),
+            previous_keys: Vec::new(),
+            status: TokenManagerStatus::Active,
+            deactivated_at: None,
         };
 
         self.map
@@ -261,10 +591,7 @@ impl TokenManagerInterface for TokenManagerStore {
             .get(token_manager_id)
             .ok_or(report!(StorageError::TokenManagerNotFoundError))?;
 
-        Ok(crate::storage::types::TokenManager {
-            public_key: token_manager.public_key.clone(),
-            token_manager_name: token_manager.token_manager_name.clone(),
-        })
+        Ok(token_manager.to_storage())
     }
 
     async fn list_token_manager(
@@ -275,12 +602,75 @@ impl TokenManagerInterface for TokenManagerStore {
         Ok(store
             .values()
             .map(|token_manager| crate::storage::types::TokenManagerInfo {
-                token_manager_id: token_manager.public_key.clone(),
+                token_manager_id: token_manager.id.clone(),
                 token_manager_name: token_manager.token_manager_name.clone(),
             })
             .collect())
     }
 
+    async fn update_token_manager(
+        &self,
+        token_manager_id: &str,
+        token_manager: crate::storage::types::TokenManager,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let stored = store
+            .get_mut(token_manager_id)
+            .ok_or(report!(StorageError::TokenManagerNotFoundError))?;
+
+        stored.token_manager_name = token_manager.token_manager_name;
+        stored.public_key = token_manager.public_key;
+        stored.sync_url = token_manager.sync_url;
+        stored.previous_keys = token_manager.previous_keys;
+
+        Ok(())
+    }
+
+    async fn deactivate_token_manager(
+        &self,
+        token_manager_id: &str,
+        deactivated_at: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let token_manager = store
+            .get_mut(token_manager_id)
+            .ok_or(report!(StorageError::TokenManagerNotFoundError))?;
+
+        token_manager.status = TokenManagerStatus::Deactivated;
+        token_manager.deactivated_at = Some(deactivated_at);
+
+        Ok(())
+    }
+
+    async fn query_token_managers(
+        &self,
+        query: &PageQuery<TokenManagerSort>,
+        filter: &TokenManagerFilter,
+    ) -> SResult<Page<crate::storage::types::TokenManager>, StorageError> {
+        let store = self.map.read().await;
+
+        let entries = store
+            .values()
+            .filter(|token_manager| {
+                filter
+                    .created_after
+                    .is_none_or(|created_after| token_manager.created_at > created_after)
+            })
+            .map(|token_manager| Entry {
+                id: token_manager.id.clone(),
+                created_at: token_manager.created_at,
+                item: token_manager.to_storage(),
+            })
+            .collect();
+
+        paginate(entries, query, |entry| match query.sort {
+            TokenManagerSort::CreatedAt => SortKey::Number(entry.created_at),
+            TokenManagerSort::Name => SortKey::Text(entry.item.token_manager_name.clone()),
+        })
+    }
+
     async fn get_supported_asset_interface(
         &self,
         token_manager_id: &str,
@@ -307,6 +697,10 @@ impl SupportedAssetInterface for SupportedAssetStore {
             asset_type: asset.asset_type,
             id: supported_asset_id.clone(),
             smart_contract_refs: asset.smart_contract_refs,
+            custody: asset.custody,
+            created_at: unix_timestamp(),
+            status: SupportedAssetStatus::Active,
+            deprecated_at: None,
         };
 
         self.map
@@ -320,30 +714,1083 @@ impl SupportedAssetInterface for SupportedAssetStore {
     async fn get_supported_asset(
         &self,
         supported_asset_id: &str,
-    ) -> SResult<crate::storage::types::SupportedAsset, StorageError> {
+    ) -> SResult<Entry<crate::storage::types::SupportedAsset>, StorageError> {
         let store = self.map.read().await;
 
         let supported_asset = store
             .get(supported_asset_id)
             .ok_or(report!(StorageError::SupportedAssetNotFoundError))?;
 
-        Ok(crate::storage::types::SupportedAsset {
-            asset_type: supported_asset.asset_type.clone(),
-            smart_contract_refs: supported_asset.smart_contract_refs.clone(),
+        Ok(Entry {
+            id: supported_asset.id.clone(),
+            created_at: supported_asset.created_at,
+            item: supported_asset.to_storage(),
         })
     }
 
+    async fn update_supported_asset(
+        &self,
+        supported_asset_id: &str,
+        asset: crate::storage::types::SupportedAsset,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let supported_asset = store
+            .get_mut(supported_asset_id)
+            .ok_or(report!(StorageError::SupportedAssetNotFoundError))?;
+
+        supported_asset.smart_contract_refs = asset.smart_contract_refs;
+        supported_asset.custody = asset.custody;
+
+        Ok(())
+    }
+
+    async fn deprecate_supported_asset(
+        &self,
+        supported_asset_id: &str,
+        deprecated_at: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let supported_asset = store
+            .get_mut(supported_asset_id)
+            .ok_or(report!(StorageError::SupportedAssetNotFoundError))?;
+
+        supported_asset.status = SupportedAssetStatus::Deprecated;
+        supported_asset.deprecated_at = Some(deprecated_at);
+
+        Ok(())
+    }
+
     async fn list_supported_assets(
         &self,
-    ) -> SResult<Vec<crate::storage::types::SupportedAsset>, StorageError> {
+    ) -> SResult<Vec<(String, crate::storage::types::SupportedAsset)>, StorageError> {
         let store = self.map.read().await;
 
         Ok(store
             .values()
-            .map(|supported_asset| crate::storage::types::SupportedAsset {
-                asset_type: supported_asset.asset_type.clone(),
-                smart_contract_refs: supported_asset.smart_contract_refs.clone(),
+            .map(|supported_asset| (supported_asset.id.clone(), supported_asset.to_storage()))
+            .collect())
+    }
+
+    async fn query_supported_assets(
+        &self,
+        query: &PageQuery<SupportedAssetSort>,
+        filter: &SupportedAssetFilter,
+    ) -> SResult<Page<crate::storage::types::SupportedAsset>, StorageError> {
+        let store = self.map.read().await;
+
+        let entries = store
+            .values()
+            .filter(|supported_asset| {
+                filter
+                    .asset_type
+                    .as_ref()
+                    .is_none_or(|asset_type| &supported_asset.asset_type == asset_type)
+                    && filter
+                        .custody
+                        .is_none_or(|custody| supported_asset.custody == custody)
+                    && filter
+                        .status
+                        .is_none_or(|status| supported_asset.status == status)
+                    && filter
+                        .created_after
+                        .is_none_or(|created_after| supported_asset.created_at > created_after)
+            })
+            .map(|supported_asset| Entry {
+                id: supported_asset.id.clone(),
+                created_at: supported_asset.created_at,
+                item: supported_asset.to_storage(),
             })
+            .collect();
+
+        paginate(entries, query, |entry| match query.sort {
+            SupportedAssetSort::CreatedAt => SortKey::Number(entry.created_at),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookInterface for WebhookStore {
+    async fn create_webhook(&self, webhook: Webhook) -> SResult<String, StorageError> {
+        let webhook_id = nanoid!(5);
+
+        self.webhooks
+            .write()
+            .await
+            .insert(webhook_id.clone(), webhook);
+
+        Ok(webhook_id)
+    }
+
+    async fn get_webhook(
+        &self,
+        token_manager_id: &str,
+        webhook_id: &str,
+    ) -> SResult<Webhook, StorageError> {
+        let store = self.webhooks.read().await;
+
+        store
+            .get(webhook_id)
+            .filter(|webhook| webhook.token_manager_id == token_manager_id)
+            .cloned()
+            .ok_or(report!(StorageError::WebhookNotFoundError))
+    }
+
+    async fn list_webhooks(
+        &self,
+        token_manager_id: &str,
+    ) -> SResult<Vec<(String, Webhook)>, StorageError> {
+        let store = self.webhooks.read().await;
+
+        Ok(store
+            .iter()
+            .filter(|(_, webhook)| webhook.token_manager_id == token_manager_id)
+            .map(|(webhook_id, webhook)| (webhook_id.clone(), webhook.clone()))
             .collect())
     }
+
+    async fn delete_webhook(
+        &self,
+        token_manager_id: &str,
+        webhook_id: &str,
+    ) -> SResult<Webhook, StorageError> {
+        let mut store = self.webhooks.write().await;
+
+        ensure!(
+            store
+                .get(webhook_id)
+                .is_some_and(|webhook| webhook.token_manager_id == token_manager_id),
+            StorageError::WebhookNotFoundError
+        );
+
+        store
+            .remove(webhook_id)
+            .ok_or(report!(StorageError::WebhookNotFoundError))
+    }
+
+    async fn enqueue_delivery(&self, delivery: Delivery) -> SResult<String, StorageError> {
+        let delivery_id = delivery.delivery_id.clone();
+
+        self.deliveries
+            .write()
+            .await
+            .insert(delivery_id.clone(), delivery);
+
+        Ok(delivery_id)
+    }
+
+    async fn get_delivery(&self, delivery_id: &str) -> SResult<Delivery, StorageError> {
+        let store = self.deliveries.read().await;
+
+        store
+            .get(delivery_id)
+            .cloned()
+            .ok_or(report!(StorageError::DeliveryNotFoundError))
+    }
+
+    async fn list_deliveries(&self, webhook_id: &str) -> SResult<Vec<Delivery>, StorageError> {
+        let store = self.deliveries.read().await;
+
+        let mut output = store
+            .values()
+            .filter(|delivery| delivery.webhook_id == webhook_id)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        output.sort_by_key(|delivery| delivery.event.created_at);
+
+        Ok(output)
+    }
+
+    async fn due_deliveries(&self, now: u64, limit: usize) -> SResult<Vec<Delivery>, StorageError> {
+        let store = self.deliveries.read().await;
+
+        let mut output = store
+            .values()
+            .filter(|delivery| {
+                delivery.status == DeliveryStatus::Pending && delivery.next_attempt_at <= now
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        output.sort_by_key(|delivery| delivery.next_attempt_at);
+        output.truncate(limit);
+
+        Ok(output)
+    }
+
+    async fn update_delivery(&self, delivery: Delivery) -> SResult<(), StorageError> {
+        let mut store = self.deliveries.write().await;
+
+        let entry = store
+            .get_mut(&delivery.delivery_id)
+            .ok_or(report!(StorageError::DeliveryNotFoundError))?;
+
+        *entry = delivery;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl SyncInterface for SyncStore {
+    async fn append(
+        &self,
+        token_manager_id: &str,
+        payload: SyncPayload,
+    ) -> SResult<u64, StorageError> {
+        let mut store = self.map.write().await;
+
+        let outbox = store.entry(token_manager_id.to_string()).or_default();
+
+        outbox.last_sequence += 1;
+
+        outbox.records.push_back(SyncRecord {
+            sequence: outbox.last_sequence,
+            token_manager_id: token_manager_id.to_string(),
+            payload,
+            created_at: unix_timestamp(),
+        });
+
+        Ok(outbox.last_sequence)
+    }
+
+    async fn pending(
+        &self,
+        token_manager_id: &str,
+        limit: usize,
+    ) -> SResult<Vec<SyncRecord>, StorageError> {
+        let store = self.map.read().await;
+
+        Ok(store
+            .get(token_manager_id)
+            .map(|outbox| outbox.records.iter().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn acknowledge(
+        &self,
+        token_manager_id: &str,
+        sequence: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        if let Some(outbox) = store.get_mut(token_manager_id) {
+            outbox.acknowledged_sequence = outbox.acknowledged_sequence.max(sequence);
+
+            while outbox
+                .records
+                .front()
+                .is_some_and(|record| record.sequence <= sequence)
+            {
+                outbox.records.pop_front();
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn pending_token_managers(&self) -> SResult<Vec<String>, StorageError> {
+        let store = self.map.read().await;
+
+        Ok(store
+            .iter()
+            .filter(|(_, outbox)| !outbox.records.is_empty())
+            .map(|(token_manager_id, _)| token_manager_id.clone())
+            .collect())
+    }
+
+    async fn status(&self, token_manager_id: &str) -> SResult<SyncStatus, StorageError> {
+        let store = self.map.read().await;
+
+        Ok(store
+            .get(token_manager_id)
+            .map(|outbox| SyncStatus {
+                last_sequence: outbox.last_sequence,
+                acknowledged_sequence: outbox.acknowledged_sequence,
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait::async_trait]
+impl WorkflowInterface for WorkflowStore {
+    async fn create_workflow(&self, workflow: Workflow) -> SResult<String, StorageError> {
+        let workflow_id = nanoid!(5);
+
+        self.map.write().await.insert(workflow_id.clone(), workflow);
+
+        Ok(workflow_id)
+    }
+
+    async fn get_workflow(&self, workflow_id: &str) -> SResult<Workflow, StorageError> {
+        self.map
+            .read()
+            .await
+            .get(workflow_id)
+            .cloned()
+            .ok_or(report!(StorageError::WorkflowNotFoundError))
+    }
+
+    async fn list_workflows(&self) -> SResult<Vec<(String, Workflow)>, StorageError> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .iter()
+            .map(|(id, workflow)| (id.clone(), workflow.clone()))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl DescriptorInterface for DescriptorStore {
+    async fn create_descriptor(&self, descriptor: Descriptor) -> SResult<String, StorageError> {
+        // descriptors act as bearer handles, so they get the full length id
+        let descriptor_id = nanoid!();
+
+        self.map
+            .write()
+            .await
+            .insert(descriptor_id.clone(), descriptor);
+
+        Ok(descriptor_id)
+    }
+
+    async fn get_descriptor(&self, descriptor_id: &str) -> SResult<Descriptor, StorageError> {
+        self.map
+            .read()
+            .await
+            .get(descriptor_id)
+            .cloned()
+            .ok_or(report!(StorageError::DescriptorNotFoundError))
+    }
+
+    async fn delete_descriptor(&self, descriptor_id: &str) -> SResult<Descriptor, StorageError> {
+        self.map
+            .write()
+            .await
+            .remove(descriptor_id)
+            .ok_or(report!(StorageError::DescriptorNotFoundError))
+    }
+
+    async fn list_descriptors(&self) -> SResult<Vec<(String, Descriptor)>, StorageError> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .iter()
+            .map(|(id, descriptor)| (id.clone(), descriptor.clone()))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl LockInterface for LockStore {
+    async fn acquire(
+        &self,
+        resource: &str,
+        holder: &str,
+        expires_at: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let available = store
+            .get(resource)
+            .is_none_or(|lock| lock.holder == holder || lock.expires_at <= unix_timestamp());
+
+        ensure!(available, StorageError::LockHeldError);
+
+        store.insert(
+            resource.to_string(),
+            Lock {
+                holder: holder.to_string(),
+                expires_at,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn release(&self, resource: &str, holder: &str) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        if store
+            .get(resource)
+            .is_some_and(|lock| lock.holder == holder)
+        {
+            store.remove(resource);
+        }
+
+        Ok(())
+    }
+
+    async fn get_lock(&self, resource: &str) -> SResult<Option<Lock>, StorageError> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .get(resource)
+            .filter(|lock| lock.expires_at > unix_timestamp())
+            .cloned())
+    }
+}
+
+#[async_trait::async_trait]
+impl MountInterface for MountStore {
+    async fn mount(&self, path: &str, mount: Mount) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        ensure!(!store.contains_key(path), StorageError::MountExistsError);
+
+        store.insert(path.to_string(), mount);
+
+        Ok(())
+    }
+
+    async fn unmount(&self, path: &str) -> SResult<Mount, StorageError> {
+        self.map
+            .write()
+            .await
+            .remove(path)
+            .ok_or(report!(StorageError::MountNotFoundError))
+    }
+
+    async fn list_mounts(&self) -> SResult<Vec<(String, Mount)>, StorageError> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .iter()
+            .map(|(path, mount)| (path.clone(), mount.clone()))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl SagaInterface for SagaStore {
+    async fn create_saga(&self, saga: SagaDefinition) -> SResult<String, StorageError> {
+        let saga_id = nanoid!(5);
+
+        self.sagas.write().await.insert(saga_id.clone(), saga);
+
+        Ok(saga_id)
+    }
+
+    async fn get_saga(&self, saga_id: &str) -> SResult<SagaDefinition, StorageError> {
+        self.sagas
+            .read()
+            .await
+            .get(saga_id)
+            .cloned()
+            .ok_or(report!(StorageError::SagaNotFoundError))
+    }
+
+    async fn list_sagas(&self) -> SResult<Vec<(String, SagaDefinition)>, StorageError> {
+        Ok(self
+            .sagas
+            .read()
+            .await
+            .iter()
+            .map(|(id, saga)| (id.clone(), saga.clone()))
+            .collect())
+    }
+
+    async fn create_instance(&self, instance: SagaInstance) -> SResult<String, StorageError> {
+        let instance_id = nanoid!(5);
+
+        self.instances
+            .write()
+            .await
+            .insert(instance_id.clone(), instance);
+
+        Ok(instance_id)
+    }
+
+    async fn get_instance(&self, instance_id: &str) -> SResult<SagaInstance, StorageError> {
+        self.instances
+            .read()
+            .await
+            .get(instance_id)
+            .cloned()
+            .ok_or(report!(StorageError::SagaInstanceNotFoundError))
+    }
+
+    async fn list_instances(
+        &self,
+        saga_id: &str,
+    ) -> SResult<Vec<(String, SagaInstance)>, StorageError> {
+        Ok(self
+            .instances
+            .read()
+            .await
+            .iter()
+            .filter(|(_, instance)| instance.saga_id == saga_id)
+            .map(|(id, instance)| (id.clone(), instance.clone()))
+            .collect())
+    }
+
+    async fn update_instance(
+        &self,
+        instance_id: &str,
+        instance: SagaInstance,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.instances.write().await;
+
+        let entry = store
+            .get_mut(instance_id)
+            .ok_or(report!(StorageError::SagaInstanceNotFoundError))?;
+
+        *entry = instance;
+
+        Ok(())
+    }
+
+    async fn active_instances(
+        &self,
+        limit: usize,
+    ) -> SResult<Vec<(String, SagaInstance)>, StorageError> {
+        let store = self.instances.read().await;
+
+        let mut output: Vec<_> = store
+            .iter()
+            .filter(|(_, instance)| {
+                matches!(
+                    instance.status,
+                    SagaStatus::Running | SagaStatus::Compensating
+                )
+            })
+            .map(|(id, instance)| (id.clone(), instance.clone()))
+            .collect();
+
+        output.sort_by_key(|(_, instance)| instance.created_at);
+        output.truncate(limit);
+
+        Ok(output)
+    }
+}
+
+#[async_trait::async_trait]
+impl SwapInterface for SwapStore {
+    async fn create_swap(&self, swap: Swap) -> SResult<String, StorageError> {
+        let swap_id = nanoid!(5);
+
+        self.map.write().await.insert(swap_id.clone(), swap);
+
+        Ok(swap_id)
+    }
+
+    async fn get_swap(&self, swap_id: &str) -> SResult<Swap, StorageError> {
+        self.map
+            .read()
+            .await
+            .get(swap_id)
+            .cloned()
+            .ok_or(report!(StorageError::SwapNotFoundError))
+    }
+
+    async fn list_swaps(&self) -> SResult<Vec<(String, Swap)>, StorageError> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .iter()
+            .map(|(id, swap)| (id.clone(), swap.clone()))
+            .collect())
+    }
+
+    async fn update_swap(&self, swap_id: &str, swap: Swap) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let entry = store
+            .get_mut(swap_id)
+            .ok_or(report!(StorageError::SwapNotFoundError))?;
+
+        *entry = swap;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ScheduleInterface for ScheduleStore {
+    async fn create_schedule(&self, schedule: Schedule) -> SResult<String, StorageError> {
+        let schedule_id = nanoid!(5);
+
+        self.map.write().await.insert(schedule_id.clone(), schedule);
+
+        Ok(schedule_id)
+    }
+
+    async fn get_schedule(&self, schedule_id: &str) -> SResult<Schedule, StorageError> {
+        self.map
+            .read()
+            .await
+            .get(schedule_id)
+            .cloned()
+            .ok_or(report!(StorageError::ScheduleNotFoundError))
+    }
+
+    async fn list_schedules(
+        &self,
+        user_id: &str,
+    ) -> SResult<Vec<(String, Schedule)>, StorageError> {
+        Ok(self
+            .map
+            .read()
+            .await
+            .iter()
+            .filter(|(_, schedule)| schedule.user_id == user_id)
+            .map(|(id, schedule)| (id.clone(), schedule.clone()))
+            .collect())
+    }
+
+    async fn update_schedule(
+        &self,
+        schedule_id: &str,
+        schedule: Schedule,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+
+        let entry = store
+            .get_mut(schedule_id)
+            .ok_or(report!(StorageError::ScheduleNotFoundError))?;
+
+        *entry = schedule;
+
+        Ok(())
+    }
+
+    async fn delete_schedule(&self, schedule_id: &str) -> SResult<Schedule, StorageError> {
+        self.map
+            .write()
+            .await
+            .remove(schedule_id)
+            .ok_or(report!(StorageError::ScheduleNotFoundError))
+    }
+
+    async fn due_schedules(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> SResult<Vec<(String, Schedule)>, StorageError> {
+        let store = self.map.read().await;
+
+        let mut output: Vec<_> = store
+            .iter()
+            .filter(|(_, schedule)| {
+                schedule.status == ScheduleStatus::Active
+                    && schedule.next_run_at.is_some_and(|at| at <= now)
+            })
+            .map(|(id, schedule)| (id.clone(), schedule.clone()))
+            .collect();
+
+        output.sort_by_key(|(_, schedule)| schedule.next_run_at);
+        output.truncate(limit);
+
+        Ok(output)
+    }
+}
+
+#[async_trait::async_trait]
+impl NonceInterface for NonceStore {
+    async fn consume(&self, nonce: &str, expires_at: u64) -> SResult<(), StorageError> {
+        let now = unix_timestamp();
+        let mut store = self.map.write().await;
+
+        store.retain(|_, expires_at| *expires_at > now);
+
+        ensure!(!store.contains_key(nonce), StorageError::NonceReusedError);
+
+        store.insert(nonce.to_string(), expires_at);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionInterface for SessionStore {
+    async fn create_challenge(&self, challenge: Challenge) -> SResult<String, StorageError> {
+        let challenge_id = nanoid!();
+        let now = unix_timestamp();
+        let mut store = self.challenges.write().await;
+
+        store.retain(|_, challenge| challenge.expires_at > now);
+        store.insert(challenge_id.clone(), challenge);
+
+        Ok(challenge_id)
+    }
+
+    async fn take_challenge(&self, challenge_id: &str) -> SResult<Challenge, StorageError> {
+        self.challenges
+            .write()
+            .await
+            .remove(challenge_id)
+            .ok_or(report!(StorageError::ChallengeNotFoundError))
+    }
+
+    async fn create_session(&self, session: Session) -> SResult<String, StorageError> {
+        let session_id = nanoid!();
+        let now = unix_timestamp();
+        let mut store = self.sessions.write().await;
+
+        store.retain(|_, session| session.expires_at > now);
+        store.insert(session_id.clone(), session);
+
+        Ok(session_id)
+    }
+
+    async fn get_session(&self, session_id: &str) -> SResult<Session, StorageError> {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or(report!(StorageError::SessionNotFoundError))
+    }
+
+    async fn delete_session(&self, session_id: &str) -> SResult<Session, StorageError> {
+        self.sessions
+            .write()
+            .await
+            .remove(session_id)
+            .ok_or(report!(StorageError::SessionNotFoundError))
+    }
+
+    async fn revoke_sessions(&self, subject: &str) -> SResult<(), StorageError> {
+        self.sessions
+            .write()
+            .await
+            .retain(|_, session| session.subject != subject);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl RoleInterface for RoleStore {
+    async fn grant_role(&self, grant: RoleGrant) -> SResult<String, StorageError> {
+        let mut store = self.map.write().await;
+
+        let existing = store
+            .iter()
+            .find(|(_, held)| held.subject == grant.subject && held.role == grant.role)
+            .map(|(grant_id, _)| grant_id.clone());
+
+        if let Some(grant_id) = existing {
+            return Ok(grant_id);
+        }
+
+        let grant_id = nanoid!();
+        store.insert(grant_id.clone(), grant);
+
+        Ok(grant_id)
+    }
+
+    async fn revoke_role(&self, grant_id: &str) -> SResult<RoleGrant, StorageError> {
+        self.map
+            .write()
+            .await
+            .remove(grant_id)
+            .ok_or(report!(StorageError::RoleNotFoundError))
+    }
+
+    async fn list_roles(
+        &self,
+        subject: Option<&str>,
+    ) -> SResult<Vec<(String, RoleGrant)>, StorageError> {
+        let mut output: Vec<_> = self
+            .map
+            .read()
+            .await
+            .iter()
+            .filter(|(_, grant)| subject.is_none_or(|subject| grant.subject == subject))
+            .map(|(grant_id, grant)| (grant_id.clone(), grant.clone()))
+            .collect();
+
+        output.sort_by_key(|(_, grant)| grant.granted_at);
+
+        Ok(output)
+    }
+}
+
+#[async_trait::async_trait]
+impl AclInterface for AclStore {
+    async fn get_acl(&self, resource: &str) -> SResult<Acl, StorageError> {
+        self.map
+            .read()
+            .await
+            .get(resource)
+            .cloned()
+            .ok_or(report!(StorageError::AclNotFoundError))
+    }
+
+    async fn set_mode(&self, resource: &str, mode: Mode) -> SResult<Acl, StorageError> {
+        let mut store = self.map.write().await;
+        let acl = store.entry(resource.to_string()).or_default();
+
+        acl.mode = mode;
+
+        Ok(acl.clone())
+    }
+
+    async fn put_grant(
+        &self,
+        resource: &str,
+        mode: Mode,
+        ua_addr: &str,
+        grant: AccessGrant,
+    ) -> SResult<Acl, StorageError> {
+        let mut store = self.map.write().await;
+        let acl = store.entry(resource.to_string()).or_insert_with(|| Acl {
+            mode,
+            grants: Default::default(),
+        });
+
+        acl.grants.insert(ua_addr.to_string(), grant);
+
+        Ok(acl.clone())
+    }
+
+    async fn delete_grant(
+        &self,
+        resource: &str,
+        ua_addr: &str,
+    ) -> SResult<AccessGrant, StorageError> {
+        self.map
+            .write()
+            .await
+            .get_mut(resource)
+            .and_then(|acl| acl.grants.remove(ua_addr))
+            .ok_or(report!(StorageError::AccessGrantNotFoundError))
+    }
+
+    async fn delete_acl(&self, resource: &str) -> SResult<(), StorageError> {
+        self.map.write().await.remove(resource);
+
+        Ok(())
+    }
+
+    async fn charge_grant(
+        &self,
+        resource: &str,
+        ua_addr: &str,
+        amount: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+        let grant = store
+            .get_mut(resource)
+            .and_then(|acl| acl.grants.get_mut(ua_addr))
+            .ok_or(report!(StorageError::AccessGrantNotFoundError))?;
+
+        let debited = grant.debited.saturating_add(amount);
+
+        ensure!(
+            grant.debit_limit.is_none_or(|limit| debited <= limit),
+            StorageError::DebitLimitExceededError
+        );
+
+        grant.debited = debited;
+
+        Ok(())
+    }
+
+    async fn refund_grant(
+        &self,
+        resource: &str,
+        ua_addr: &str,
+        amount: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.map.write().await;
+        let grant = store
+            .get_mut(resource)
+            .and_then(|acl| acl.grants.get_mut(ua_addr))
+            .ok_or(report!(StorageError::AccessGrantNotFoundError))?;
+
+        grant.debited = grant.debited.saturating_sub(amount);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl PolicyInterface for PolicyStore {
+    async fn create_policy(&self, policy: Policy) -> SResult<String, StorageError> {
+        let policy_id = nanoid!();
+
+        self.map.write().await.insert(policy_id.clone(), policy);
+
+        Ok(policy_id)
+    }
+
+    async fn get_policy(&self, policy_id: &str) -> SResult<Policy, StorageError> {
+        self.map
+            .read()
+            .await
+            .get(policy_id)
+            .cloned()
+            .ok_or(report!(StorageError::PolicyNotFoundError))
+    }
+
+    async fn list_policies(&self) -> SResult<Vec<(String, Policy)>, StorageError> {
+        let mut output: Vec<_> = self
+            .map
+            .read()
+            .await
+            .iter()
+            .map(|(policy_id, policy)| (policy_id.clone(), policy.clone()))
+            .collect();
+
+        output.sort_by_key(|(_, policy)| policy.created_at);
+
+        Ok(output)
+    }
+
+    async fn delete_policy(&self, policy_id: &str) -> SResult<Policy, StorageError> {
+        let policy = self
+            .map
+            .write()
+            .await
+            .remove(policy_id)
+            .ok_or(report!(StorageError::PolicyNotFoundError))?;
+
+        self.usage
+            .write()
+            .await
+            .retain(|(id, _), _| id != policy_id);
+
+        Ok(policy)
+    }
+
+    async fn record_usage(
+        &self,
+        policy_id: &str,
+        resource: &str,
+        usage: PolicyUsage,
+        keep_since: u64,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.usage.write().await;
+        let entries = store
+            .entry((policy_id.to_string(), resource.to_string()))
+            .or_default();
+
+        entries.retain(|entry| entry.at >= keep_since);
+        entries.push(usage);
+
+        Ok(())
+    }
+
+    async fn release_usage(
+        &self,
+        policy_id: &str,
+        resource: &str,
+        usage_id: &str,
+    ) -> SResult<(), StorageError> {
+        if let Some(entries) = self
+            .usage
+            .write()
+            .await
+            .get_mut(&(policy_id.to_string(), resource.to_string()))
+        {
+            entries.retain(|entry| entry.usage_id != usage_id);
+        }
+
+        Ok(())
+    }
+
+    async fn usage(
+        &self,
+        policy_id: &str,
+        resource: &str,
+        since: u64,
+    ) -> SResult<Vec<PolicyUsage>, StorageError> {
+        Ok(self
+            .usage
+            .read()
+            .await
+            .get(&(policy_id.to_string(), resource.to_string()))
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.at >= since)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait::async_trait]
+impl MultisigInterface for MultisigStore {
+    async fn get_multisig(&self, resource: &str) -> SResult<Option<Multisig>, StorageError> {
+        Ok(self.map.read().await.get(resource).cloned())
+    }
+
+    async fn set_multisig(&self, resource: &str, multisig: Multisig) -> SResult<(), StorageError> {
+        self.map
+            .write()
+            .await
+            .insert(resource.to_string(), multisig);
+
+        Ok(())
+    }
+
+    async fn delete_multisig(&self, resource: &str) -> SResult<Multisig, StorageError> {
+        self.map
+            .write()
+            .await
+            .remove(resource)
+            .ok_or(report!(StorageError::MultisigNotFoundError))
+    }
+
+    async fn create_approval(&self, approval: Approval) -> SResult<String, StorageError> {
+        let approval_id = nanoid!(5);
+
+        self.approvals
+            .write()
+            .await
+            .insert(approval_id.clone(), approval);
+
+        Ok(approval_id)
+    }
+
+    async fn get_approval(&self, approval_id: &str) -> SResult<Approval, StorageError> {
+        self.approvals
+            .read()
+            .await
+            .get(approval_id)
+            .cloned()
+            .ok_or(report!(StorageError::ApprovalNotFoundError))
+    }
+
+    async fn list_approvals(&self) -> SResult<Vec<(String, Approval)>, StorageError> {
+        let mut output: Vec<_> = self
+            .approvals
+            .read()
+            .await
+            .iter()
+            .map(|(id, approval)| (id.clone(), approval.clone()))
+            .collect();
+
+        output.sort_by_key(|(_, approval)| approval.created_at);
+
+        Ok(output)
+    }
+
+    async fn update_approval(
+        &self,
+        approval_id: &str,
+        approval: Approval,
+    ) -> SResult<(), StorageError> {
+        let mut store = self.approvals.write().await;
+
+        let entry = store
+            .get_mut(approval_id)
+            .ok_or(report!(StorageError::ApprovalNotFoundError))?;
+
+        *entry = approval;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,831 @@
+use error_stack::{ensure, ResultExt};
+
+use crate::error::{LedgerError, PolicyError, SResult, StorageError};
+use crate::events::{self, AssetActivity};
+use crate::state::AppState;
+use crate::storage::types::{
+    AssetInfo, Counterparty, EventType, Holding, Money, PolicyOperation, TokenManagerStatus,
+};
+use crate::storage::AssetInterface;
+use crate::{multisig, policy};
+
+/// Every asset held on the ledger along with the account and user holding it.
+pub async fn holdings(app_state: &AppState) -> SResult<Vec<Holding>, StorageError> {
+    let user_interface = app_state.storage.get_user_interface().await?;
+
+    let mut output = Vec::new();
+
+    for (user_id, user) in user_interface.list_users().await? {
+        let account_interface = user_interface.get_account_interface(&user_id).await?;
+
+        for (account_id, account) in account_interface.list_accounts().await? {
+            let assets = account_interface
+                .get_asset_interface(&account_id)
+                .await?
+                .list_assets()
+                .await?;
+
+            output.extend(assets.into_iter().map(|(asset_id, asset_info)| Holding {
+                user_id: user_id.clone(),
+                ua_addr: user.ua_addr.clone(),
+                account_id: account_id.clone(),
+                token_manager_id: account.token_manager_id.clone(),
+                asset_type: account.asset_type.clone(),
+                asset_id,
+                asset_info,
+            }));
+        }
+    }
+
+    Ok(output)
+}
+
+/// Cash held by the account, in the currency of the account.
+pub async fn balance(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+) -> SResult<Money, LedgerError> {
+    let (_, total_assets) = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?
+        .get_account(account_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?;
+
+    Ok(total_assets.money)
+}
+
+/// Token manager behind the account, the only one that may mint into it.
+pub async fn token_manager_of(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+) -> SResult<String, LedgerError> {
+    let (account, _) = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?
+        .get_account(account_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?;
+
+    Ok(account.token_manager_id)
+}
+
+/// Mints `amount` of cash, in the currency of the account, into the account. Callers have to make
+/// sure the token manager behind the account asked for it, which has to be active, and the
+/// policies of the account have to accept the mint.
+pub async fn credit(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+) -> SResult<String, LedgerError> {
+    let currency = balance(app_state, user_id, account_id).await?.currency;
+
+    let (asset_id, _) = mint(
+        app_state,
+        user_id,
+        account_id,
+        AssetInfo::Cash { currency, amount },
+    )
+    .await?;
+
+    Ok(asset_id)
+}
+
+/// Like [`credit`], for an asset given in full. Returns the id of the asset along with the id of
+/// the recorded event.
+pub async fn mint(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_info: AssetInfo,
+) -> SResult<(String, String), LedgerError> {
+    let AssetInfo::Cash { amount, .. } = asset_info;
+    ensure!(amount > 0, LedgerError::InvalidAmount);
+
+    let token_manager_id = token_manager_of(app_state, user_id, account_id).await?;
+    let token_manager = app_state
+        .storage
+        .get_token_manager_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_token_manager(&token_manager_id)
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    ensure!(
+        token_manager.status == TokenManagerStatus::Active,
+        LedgerError::TokenManagerDeactivated
+    );
+
+    let admission = policy::admit_mint(app_state, user_id, account_id, amount)
+        .await
+        .map_err(policy_error)?;
+
+    let issued = issue(app_state, user_id, account_id, asset_info).await;
+
+    admission.conclude(app_state, &issued).await;
+
+    issued
+}
+
+/// Puts back `amount` of cash that [`debit`] burned from the account, when undoing it. Nothing
+/// new is issued, so neither the mint policies nor the status of the token manager stand in the
+/// way of returning it.
+pub async fn restore(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+) -> SResult<String, LedgerError> {
+    ensure!(amount > 0, LedgerError::InvalidAmount);
+
+    let currency = balance(app_state, user_id, account_id).await?.currency;
+
+    let (asset_id, _) = issue(
+        app_state,
+        user_id,
+        account_id,
+        AssetInfo::Cash { currency, amount },
+    )
+    .await?;
+
+    Ok(asset_id)
+}
+
+async fn issue(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_info: AssetInfo,
+) -> SResult<(String, String), LedgerError> {
+    let asset_store = asset_interface(app_state, user_id, account_id).await?;
+
+    let asset_id = asset_store
+        .create_asset(asset_info.clone())
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    let transaction_id = publish(
+        app_state,
+        AssetActivity {
+            event_type: EventType::Mint,
+            user_id: user_id.to_string(),
+            account_id: account_id.to_string(),
+            asset_id: asset_id.clone(),
+            asset_info,
+            counterparty: None,
+        },
+        async { asset_store.delete_asset(&asset_id).await.map(drop) },
+    )
+    .await?;
+
+    Ok((asset_id, transaction_id))
+}
+
+/// Publishes the activity of a change already written to the ledger. The change is undone when
+/// publishing fails, so that the ledger never holds changes missing from the log.
+async fn publish(
+    app_state: &AppState,
+    activity: AssetActivity,
+    undo: impl std::future::Future<Output = SResult<(), StorageError>>,
+) -> SResult<String, LedgerError> {
+    let error = match events::publish(app_state, activity).await {
+        Ok(transaction_id) => return Ok(transaction_id),
+        Err(error) => error.change_context(LedgerError::StorageError),
+    };
+
+    match undo.await {
+        Ok(()) => Err(error),
+        Err(undo_error) => {
+            let mut error = error;
+            error.extend_one(undo_error.change_context(LedgerError::StorageError));
+
+            Err(error)
+        }
+    }
+}
+
+/// Name under which locks on the account are taken.
+pub fn account_resource(user_id: &str, account_id: &str) -> String {
+    format!("accounts/{user_id}/{account_id}")
+}
+
+/// Name under which locks on a single asset are taken.
+pub fn asset_resource(user_id: &str, account_id: &str, asset_id: &str) -> String {
+    format!("assets/{user_id}/{account_id}/{asset_id}")
+}
+
+/// Fails when the account, or the asset when given, is locked by anyone but `holder`.
+pub async fn ensure_unlocked(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: Option<&str>,
+    holder: Option<&str>,
+) -> SResult<(), LedgerError> {
+    ensure!(
+        is_available(app_state, &account_resource(user_id, account_id), holder).await?,
+        LedgerError::AccountLocked
+    );
+
+    if let Some(asset_id) = asset_id {
+        ensure!(
+            is_available(
+                app_state,
+                &asset_resource(user_id, account_id, asset_id),
+                holder
+            )
+            .await?,
+            LedgerError::AssetLocked
+        );
+    }
+
+    Ok(())
+}
+
+async fn is_available(
+    app_state: &AppState,
+    resource: &str,
+    holder: Option<&str>,
+) -> SResult<bool, LedgerError> {
+    let lock = app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_lock(resource)
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    Ok(lock
+        .filter(|lock| Some(lock.holder.as_str()) != holder)
+        .is_none())
+}
+
+/// Locks a single asset of the account on behalf of `holder` until `expires_at`.
+pub async fn lock_asset(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+    holder: &str,
+    expires_at: u64,
+) -> SResult<(), LedgerError> {
+    let (_, asset_info) = find_asset(app_state, user_id, account_id, asset_id)
+        .await?
+        .ok_or(LedgerError::AssetNotFound)?;
+
+    ensure_unlocked(app_state, user_id, account_id, None, Some(holder)).await?;
+
+    app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .acquire(
+            &asset_resource(user_id, account_id, asset_id),
+            holder,
+            expires_at,
+        )
+        .await
+        .change_context(LedgerError::AssetLocked)?;
+
+    events::publish(
+        app_state,
+        AssetActivity {
+            event_type: EventType::Lock,
+            user_id: user_id.to_string(),
+            account_id: account_id.to_string(),
+            asset_id: asset_id.to_string(),
+            asset_info,
+            counterparty: None,
+        },
+    )
+    .await
+    .change_context(LedgerError::StorageError)?;
+
+    Ok(())
+}
+
+/// Releases the lock `holder` has on a single asset of the account. Nothing is released when the
+/// lock has expired and been taken over since.
+pub async fn unlock_asset(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+    holder: &str,
+) -> SResult<(), LedgerError> {
+    let resource = asset_resource(user_id, account_id, asset_id);
+    let lock_interface = app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    let held = lock_interface
+        .get_lock(&resource)
+        .await
+        .change_context(LedgerError::StorageError)?
+        .is_some_and(|lock| lock.holder == holder);
+
+    if !held {
+        return Ok(());
+    }
+
+    lock_interface
+        .release(&resource, holder)
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    // the holder may have moved the asset out of the account while it was locked
+    if let Some((_, asset_info)) = find_asset(app_state, user_id, account_id, asset_id).await? {
+        events::publish(
+            app_state,
+            AssetActivity {
+                event_type: EventType::Unlock,
+                user_id: user_id.to_string(),
+                account_id: account_id.to_string(),
+                asset_id: asset_id.to_string(),
+                asset_info,
+                counterparty: None,
+            },
+        )
+        .await
+        .change_context(LedgerError::StorageError)?;
+    }
+
+    Ok(())
+}
+
+/// The asset of the account with the given id, if the account holds it.
+pub async fn find_asset(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+) -> SResult<Option<(String, AssetInfo)>, LedgerError> {
+    Ok(asset_interface(app_state, user_id, account_id)
+        .await?
+        .list_assets()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .into_iter()
+        .find(|(id, _)| id == asset_id))
+}
+
+/// Sets aside cash adding up to exactly `amount`, locking the assets holding it on behalf of
+/// `holder` until `expires_at`. Returns the ids of the locked assets.
+pub async fn earmark(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+    holder: &str,
+    expires_at: u64,
+) -> SResult<Vec<String>, LedgerError> {
+    ensure_within_threshold(app_state, user_id, account_id, amount).await?;
+
+    let mut output = Vec::new();
+
+    for (asset_id, _) in gather(app_state, user_id, account_id, amount, Some(holder)).await? {
+        lock_asset(
+            app_state, user_id, account_id, &asset_id, holder, expires_at,
+        )
+        .await?;
+        output.push(asset_id);
+    }
+
+    Ok(output)
+}
+
+/// Burns a single asset of the account, returning it along with the id of the recorded event.
+pub async fn burn(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+    holder: Option<&str>,
+) -> SResult<(AssetInfo, String), LedgerError> {
+    if let Some((_, AssetInfo::Cash { amount, .. })) =
+        find_asset(app_state, user_id, account_id, asset_id).await?
+    {
+        ensure_within_threshold(app_state, user_id, account_id, amount).await?;
+    }
+
+    destroy(app_state, user_id, account_id, asset_id, holder).await
+}
+
+/// Like [`burn`], for callers that already checked the amount against the multisig threshold,
+/// or only burn the asset to split it.
+async fn destroy(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+    holder: Option<&str>,
+) -> SResult<(AssetInfo, String), LedgerError> {
+    ensure_unlocked(app_state, user_id, account_id, Some(asset_id), holder).await?;
+
+    let asset_store = asset_interface(app_state, user_id, account_id).await?;
+
+    let asset_info = asset_store
+        .delete_asset(asset_id)
+        .await
+        .change_context(LedgerError::AssetNotFound)?;
+
+    let transaction_id = publish(
+        app_state,
+        AssetActivity {
+            event_type: EventType::Burn,
+            user_id: user_id.to_string(),
+            account_id: account_id.to_string(),
+            asset_id: asset_id.to_string(),
+            asset_info: asset_info.clone(),
+            counterparty: None,
+        },
+        asset_store.restore_asset(asset_id, asset_info.clone()),
+    )
+    .await?;
+
+    Ok((asset_info, transaction_id))
+}
+
+/// Burns `amount` of cash from the account.
+///
+/// Operations taking value out of an account are refused while the account or the assets
+/// involved are locked, unless they are made on behalf of the `holder` of the lock.
+pub async fn debit(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+    holder: Option<&str>,
+) -> SResult<(), LedgerError> {
+    ensure_within_threshold(app_state, user_id, account_id, amount).await?;
+
+    for (asset_id, _) in gather(app_state, user_id, account_id, amount, holder).await? {
+        destroy(app_state, user_id, account_id, &asset_id, holder).await?;
+    }
+
+    Ok(())
+}
+
+/// Moves `amount` of cash from the account to an account of the peer, returning the ids of the
+/// assets created on the peer side. The policies of both accounts have to accept the transfer.
+pub async fn transfer(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    peer_ua_addr: &str,
+    peer_account_id: &str,
+    amount: u64,
+    holder: Option<&str>,
+) -> SResult<Vec<String>, LedgerError> {
+    // resolve the peer first, so that nothing moves when the peer doesn't exist
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_account_interface_by_ua(peer_ua_addr)
+        .await
+        .change_context(LedgerError::AccountNotFound)?
+        .get_asset_interface(peer_account_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?;
+
+    let admission = policy::admit_transfer(
+        app_state,
+        PolicyOperation::Transfer,
+        user_id,
+        account_id,
+        peer_ua_addr,
+        peer_account_id,
+        amount,
+    )
+    .await
+    .map_err(policy_error)?;
+
+    let output = async {
+        ensure_within_threshold(app_state, user_id, account_id, amount).await?;
+
+        let mut output = Vec::new();
+
+        for (asset_id, _) in gather(app_state, user_id, account_id, amount, holder).await? {
+            output.push(
+                move_asset(
+                    app_state,
+                    user_id,
+                    account_id,
+                    &asset_id,
+                    peer_ua_addr,
+                    peer_account_id,
+                    holder,
+                )
+                .await?,
+            );
+        }
+
+        Ok(output)
+    }
+    .await;
+
+    admission.conclude(app_state, &output).await;
+
+    output
+}
+
+/// Refuses taking `amount` out of an account when it is above the multisig threshold of the
+/// account. Such transfers are held for the signers through the asset verbs instead.
+pub async fn ensure_within_threshold(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+) -> SResult<(), LedgerError> {
+    let needs_approval = multisig::setup(app_state, user_id, account_id)
+        .await
+        .change_context(LedgerError::StorageError)?
+        .is_some_and(|multisig| amount > multisig.threshold);
+
+    ensure!(!needs_approval, LedgerError::ApprovalRequired);
+
+    Ok(())
+}
+
+/// Moves everything the account holds to the target account, returning the ids of the assets
+/// created there. Like any transfer, it has to be accepted by the policies of both accounts and
+/// leaves assets locked by others alone.
+pub async fn sweep(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    target_ua_addr: &str,
+    target_account_id: &str,
+) -> SResult<Vec<String>, LedgerError> {
+    let amount = balance(app_state, user_id, account_id).await?.amount;
+
+    if amount == 0 {
+        return Ok(Vec::new());
+    }
+
+    transfer(
+        app_state,
+        user_id,
+        account_id,
+        target_ua_addr,
+        target_account_id,
+        amount,
+        None,
+    )
+    .await
+}
+
+/// Checks what [`sweep`] would be refused for, without moving anything, for callers that close
+/// several accounts and can't stop halfway.
+pub async fn ensure_sweepable(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    target_ua_addr: &str,
+    target_account_id: &str,
+) -> SResult<(), LedgerError> {
+    let amount = balance(app_state, user_id, account_id).await?.amount;
+
+    if amount == 0 {
+        return Ok(());
+    }
+
+    ensure_within_threshold(app_state, user_id, account_id, amount).await?;
+
+    // the admission is only looked at, the sweep itself reserves its own
+    let admission = policy::admit_transfer(
+        app_state,
+        PolicyOperation::Transfer,
+        user_id,
+        account_id,
+        target_ua_addr,
+        target_account_id,
+        amount,
+    )
+    .await
+    .map_err(policy_error)?;
+    admission.release(app_state).await;
+
+    Ok(())
+}
+
+pub fn policy_error(error: error_stack::Report<PolicyError>) -> error_stack::Report<LedgerError> {
+    let context = match error.current_context() {
+        PolicyError::Rejected { .. } => {
+            LedgerError::PolicyRejected(error.current_context().to_string())
+        }
+        PolicyError::AccountNotFound => LedgerError::AccountNotFound,
+        PolicyError::StorageError => LedgerError::StorageError,
+    };
+
+    error.change_context(context)
+}
+
+/// Has the policies of both accounts admit moving the assets to the peer, as a transfer of their
+/// total amount. The admission is to be concluded once the assets are moved.
+pub async fn admit_move(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_ids: &[String],
+    peer_ua_addr: &str,
+    peer_account_id: &str,
+) -> SResult<policy::Admission, LedgerError> {
+    let amount = asset_interface(app_state, user_id, account_id)
+        .await?
+        .list_assets()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .into_iter()
+        .filter(|(asset_id, _)| asset_ids.contains(asset_id))
+        .map(|(_, AssetInfo::Cash { amount, .. })| amount)
+        .sum();
+
+    policy::admit_transfer(
+        app_state,
+        PolicyOperation::Transfer,
+        user_id,
+        account_id,
+        peer_ua_addr,
+        peer_account_id,
+        amount,
+    )
+    .await
+    .map_err(policy_error)
+}
+
+/// Moves a single asset to an account of the peer, returning the id of the asset on the peer
+/// side.
+pub async fn move_asset(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    asset_id: &str,
+    peer_ua_addr: &str,
+    peer_account_id: &str,
+    holder: Option<&str>,
+) -> SResult<String, LedgerError> {
+    ensure_unlocked(app_state, user_id, account_id, Some(asset_id), holder).await?;
+
+    let my_asset_store = asset_interface(app_state, user_id, account_id).await?;
+
+    let peer_asset_store = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_account_interface_by_ua(peer_ua_addr)
+        .await
+        .change_context(LedgerError::AccountNotFound)?
+        .get_asset_interface(peer_account_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?;
+
+    let asset_info = my_asset_store
+        .delete_asset(asset_id)
+        .await
+        .change_context(LedgerError::AssetNotFound)?;
+
+    let peer_asset_id = peer_asset_store
+        .create_asset(asset_info.clone())
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    publish(
+        app_state,
+        AssetActivity {
+            event_type: EventType::Transfer,
+            user_id: user_id.to_string(),
+            account_id: account_id.to_string(),
+            asset_id: asset_id.to_string(),
+            asset_info: asset_info.clone(),
+            counterparty: Some(Counterparty {
+                ua_addr: peer_ua_addr.to_string(),
+                account_id: peer_account_id.to_string(),
+                asset_id: peer_asset_id.clone(),
+            }),
+        },
+        async {
+            peer_asset_store.delete_asset(&peer_asset_id).await?;
+            my_asset_store.restore_asset(asset_id, asset_info).await
+        },
+    )
+    .await?;
+
+    Ok(peer_asset_id)
+}
+
+async fn asset_interface(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+) -> SResult<Box<dyn AssetInterface + Send + Sync>, LedgerError> {
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?
+        .get_asset_interface(account_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)
+}
+
+/// Selects cash assets of the account adding up to exactly `amount`, splitting the last asset
+/// when it holds more than what is needed. Assets locked by anyone but `holder` are left alone.
+async fn gather(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+    holder: Option<&str>,
+) -> SResult<Vec<(String, AssetInfo)>, LedgerError> {
+    ensure!(amount > 0, LedgerError::InvalidAmount);
+    ensure_unlocked(app_state, user_id, account_id, None, holder).await?;
+
+    let currency = balance(app_state, user_id, account_id).await?.currency;
+
+    let asset_interface = asset_interface(app_state, user_id, account_id).await?;
+
+    let mut assets = asset_interface
+        .list_assets()
+        .await
+        .change_context(LedgerError::StorageError)?;
+
+    assets.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut selected = Vec::new();
+    let mut total = 0_u64;
+
+    for (asset_id, asset_info) in assets {
+        if total >= amount {
+            break;
+        }
+
+        let spendable = match &asset_info {
+            AssetInfo::Cash {
+                currency: asset_currency,
+                ..
+            } => asset_currency == &currency,
+        } && is_available(
+            app_state,
+            &asset_resource(user_id, account_id, &asset_id),
+            holder,
+        )
+        .await?;
+
+        if let (true, AssetInfo::Cash { amount, .. }) = (spendable, &asset_info) {
+            total = total.saturating_add(*amount);
+            selected.push((asset_id, asset_info));
+        }
+    }
+
+    ensure!(total >= amount, LedgerError::InsufficientFunds);
+
+    let excess = total - amount;
+
+    if excess > 0 {
+        if let Some((last_id, AssetInfo::Cash { currency, amount })) = selected.pop() {
+            destroy(app_state, user_id, account_id, &last_id, holder).await?;
+
+            // the remainder stays behind, the other part is what is needed
+            for (part, needed) in [(excess, false), (amount - excess, true)] {
+                let asset_info = AssetInfo::Cash {
+                    currency: currency.clone(),
+                    amount: part,
+                };
+
+                let (asset_id, _) =
+                    issue(app_state, user_id, account_id, asset_info.clone()).await?;
+
+                if needed {
+                    selected.push((asset_id, asset_info));
+                }
+            }
+        }
+    }
+
+    Ok(selected)
+}
@@ -1,7 +1,27 @@
+pub mod acl;
 pub mod app;
+pub mod audit;
+pub mod auth;
 pub mod config;
 pub mod error;
+pub mod events;
+pub mod hash_chain;
 pub mod imc;
+pub mod ledger;
 pub mod logging;
+pub mod merkle;
+pub mod multisig;
+pub mod namespace;
+pub mod policy;
+pub mod proofs;
+pub mod request;
+pub mod runtime;
+pub mod saga;
+pub mod schedule;
 pub mod state;
 pub mod storage;
+pub mod swap;
+pub mod sync;
+pub mod utils;
+pub mod validation;
+pub mod webhooks;
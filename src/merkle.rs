@@ -0,0 +1,421 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use error_stack::ResultExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+
+use crate::config::MerkleSettings;
+use crate::error::{ConfigurationError, SResult};
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{AssetInfo, Holding};
+use crate::utils::unix_timestamp;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+type Hash = [u8; 32];
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct MerkleLeaf {
+    pub ua_addr: String,
+    pub account_id: String,
+    pub asset_id: String,
+    pub asset_info: AssetInfo,
+}
+
+impl From<Holding> for MerkleLeaf {
+    fn from(value: Holding) -> Self {
+        Self {
+            ua_addr: value.ua_addr,
+            account_id: value.account_id,
+            asset_id: value.asset_id,
+            asset_info: value.asset_info,
+        }
+    }
+}
+
+/// Side on which the sibling hash is combined with the running hash.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+#[schema(as = ProofSide)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProofStep {
+    pub side: Side,
+    pub hash: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    pub steps: Vec<ProofStep>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SignedRoot {
+    pub root: String,
+    pub leaf_count: u64,
+    pub computed_at: u64,
+    /// Hex encoded ed25519 key of the server that signed the root.
+    pub public_key: String,
+    pub signature: String,
+}
+
+impl SignedRoot {
+    fn message(root: &str, leaf_count: u64, computed_at: u64) -> Vec<u8> {
+        format!("{root}:{leaf_count}:{computed_at}").into_bytes()
+    }
+}
+
+fn update_field(hasher: &mut Sha256, value: &[u8]) {
+    hasher.update((value.len() as u64).to_be_bytes());
+    hasher.update(value);
+}
+
+pub fn leaf_hash(leaf: &MerkleLeaf) -> Hash {
+    let mut hasher = Sha256::new();
+
+    hasher.update([LEAF_PREFIX]);
+    update_field(&mut hasher, leaf.ua_addr.as_bytes());
+    update_field(&mut hasher, leaf.account_id.as_bytes());
+    update_field(&mut hasher, leaf.asset_id.as_bytes());
+
+    match &leaf.asset_info {
+        AssetInfo::Cash { currency, amount } => {
+            update_field(&mut hasher, b"cash");
+            update_field(&mut hasher, currency.to_string().as_bytes());
+            hasher.update(amount.to_be_bytes());
+        }
+    }
+
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+
+    hasher.finalize().into()
+}
+
+/// Binary merkle tree, an unpaired node at the end of a level is carried up unchanged.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: &[MerkleLeaf]) -> Self {
+        let mut levels = vec![leaves.iter().map(leaf_hash).collect::<Vec<_>>()];
+
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .map(|level| {
+                    level
+                        .chunks(2)
+                        .map(|pair| match pair {
+                            [left, right] => node_hash(left, right),
+                            [single] => *single,
+                            _ => unreachable!("chunks of two"),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_else(|| Sha256::digest([]).into())
+    }
+
+    pub fn proof(&self, leaf_index: usize) -> Option<InclusionProof> {
+        let leaves = self.levels.first()?;
+
+        if leaf_index >= leaves.len() {
+            return None;
+        }
+
+        let mut index = leaf_index;
+        let mut steps = Vec::new();
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+
+            if let Some(hash) = level.get(sibling) {
+                steps.push(ProofStep {
+                    side: if sibling > index {
+                        Side::Right
+                    } else {
+                        Side::Left
+                    },
+                    hash: hex::encode(hash),
+                });
+            }
+
+            index /= 2;
+        }
+
+        Some(InclusionProof {
+            leaf_index: leaf_index as u64,
+            steps,
+        })
+    }
+}
+
+/// Checks that `leaf` is included in the tree with the given hex encoded `root`.
+pub fn verify_inclusion(leaf: &MerkleLeaf, proof: &InclusionProof, root: &str) -> bool {
+    let mut running = leaf_hash(leaf);
+
+    for step in &proof.steps {
+        let Some(sibling) = hex::decode(&step.hash)
+            .ok()
+            .and_then(|bytes| Hash::try_from(bytes).ok())
+        else {
+            return false;
+        };
+
+        running = match step.side {
+            Side::Left => node_hash(&sibling, &running),
+            Side::Right => node_hash(&running, &sibling),
+        };
+    }
+
+    hex::encode(running) == root
+}
+
+/// Checks that the root was signed by the key it carries.
+pub fn verify_signed_root(signed_root: &SignedRoot) -> bool {
+    let Some(public_key) = hex::decode(&signed_root.public_key)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+    else {
+        return false;
+    };
+
+    let Some(signature) = hex::decode(&signed_root.signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+    else {
+        return false;
+    };
+
+    public_key
+        .verify(
+            &SignedRoot::message(
+                &signed_root.root,
+                signed_root.leaf_count,
+                signed_root.computed_at,
+            ),
+            &signature,
+        )
+        .is_ok()
+}
+
+struct PublishedTree {
+    root: SignedRoot,
+    leaves: Vec<MerkleLeaf>,
+    tree: MerkleTree,
+}
+
+/// Holds the server signing key and the latest published tree.
+#[derive(Clone)]
+pub struct MerklePublisher {
+    signing_key: Arc<SigningKey>,
+    latest: Arc<RwLock<Option<PublishedTree>>>,
+}
+
+impl MerklePublisher {
+    pub fn new(settings: &MerkleSettings) -> SResult<Self, ConfigurationError> {
+        let signing_key = match &settings.signing_key {
+            Some(seed) => {
+                let seed = hex::decode(seed).change_context(ConfigurationError::SigningKeyError)?;
+                let seed =
+                    <[u8; 32]>::try_from(seed).map_err(|_| ConfigurationError::SigningKeyError)?;
+
+                SigningKey::from_bytes(&seed)
+            }
+            None => {
+                warn!("No merkle signing key configured, generating an ephemeral key");
+                SigningKey::generate(&mut rand::rngs::OsRng)
+            }
+        };
+
+        Ok(Self {
+            signing_key: Arc::new(signing_key),
+            latest: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    /// Builds a tree over the leaves, signs its root and makes it the latest published tree.
+    pub async fn publish(&self, mut leaves: Vec<MerkleLeaf>) -> SignedRoot {
+        leaves.sort_by(|a, b| {
+            (&a.ua_addr, &a.account_id, &a.asset_id).cmp(&(&b.ua_addr, &b.account_id, &b.asset_id))
+        });
+
+        let tree = MerkleTree::new(&leaves);
+        let root = hex::encode(tree.root());
+        let leaf_count = leaves.len() as u64;
+        let computed_at = unix_timestamp();
+
+        let signature = self
+            .signing_key
+            .sign(&SignedRoot::message(&root, leaf_count, computed_at));
+
+        let signed_root = SignedRoot {
+            root,
+            leaf_count,
+            computed_at,
+            public_key: hex::encode(self.signing_key.verifying_key().as_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        };
+
+        *self.latest.write().await = Some(PublishedTree {
+            root: signed_root.clone(),
+            leaves,
+            tree,
+        });
+
+        signed_root
+    }
+
+    pub async fn latest_root(&self) -> Option<SignedRoot> {
+        self.latest
+            .read()
+            .await
+            .as_ref()
+            .map(|published| published.root.clone())
+    }
+
+    /// Inclusion proof of the asset against the latest published root.
+    pub async fn prove(
+        &self,
+        account_id: &str,
+        asset_id: &str,
+    ) -> Option<(MerkleLeaf, InclusionProof, SignedRoot)> {
+        let latest = self.latest.read().await;
+        let published = latest.as_ref()?;
+
+        let index = published
+            .leaves
+            .iter()
+            .position(|leaf| leaf.account_id == account_id && leaf.asset_id == asset_id)?;
+
+        Some((
+            published.leaves[index].clone(),
+            published.tree.proof(index)?,
+            published.root.clone(),
+        ))
+    }
+}
+
+/// Periodically publishes a signed root over every holding on the ledger.
+pub async fn worker(app_state: AppState) {
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        app_state.config.merkle.interval_secs.max(1),
+    ));
+
+    loop {
+        interval.tick().await;
+
+        match crate::ledger::holdings(&app_state).await {
+            Ok(holdings) => {
+                let signed_root = app_state
+                    .merkle
+                    .publish(holdings.into_iter().map(MerkleLeaf::from).collect())
+                    .await;
+
+                debug!(root = signed_root.root, "Published merkle root");
+            }
+            Err(error) => error!(?error, "Failed while computing the merkle root"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::types::Currency;
+
+    fn leaves(count: usize) -> Vec<MerkleLeaf> {
+        (0..count)
+            .map(|index| MerkleLeaf {
+                ua_addr: format!("user{index}@ua"),
+                account_id: format!("account{index}"),
+                asset_id: format!("asset{index}"),
+                asset_info: AssetInfo::Cash {
+                    currency: Currency::USD,
+                    amount: 10 * (index as u64 + 1),
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn proves_every_leaf_of_an_odd_tree() {
+        for count in [1, 3, 5, 7] {
+            let leaves = leaves(count);
+            let tree = MerkleTree::new(&leaves);
+            let root = hex::encode(tree.root());
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+
+                assert!(verify_inclusion(leaf, &proof, &root), "{index} of {count}");
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_a_tampered_leaf() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::new(&leaves);
+        let root = hex::encode(tree.root());
+        let proof = tree.proof(4).unwrap();
+
+        let mut leaf = leaves[4].clone();
+        leaf.asset_info = AssetInfo::Cash {
+            currency: Currency::USD,
+            amount: 1_000_000,
+        };
+
+        assert!(!verify_inclusion(&leaf, &proof, &root));
+        assert!(!verify_inclusion(&leaves[3], &proof, &root));
+    }
+
+    #[test]
+    fn rejects_a_tampered_step() {
+        let leaves = leaves(4);
+        let tree = MerkleTree::new(&leaves);
+        let root = hex::encode(tree.root());
+
+        let mut proof = tree.proof(1).unwrap();
+        proof.steps[0].hash = hex::encode(leaf_hash(&leaves[2]));
+        assert!(!verify_inclusion(&leaves[1], &proof, &root));
+
+        let mut proof = tree.proof(1).unwrap();
+        proof.steps[0].side = Side::Right;
+        assert!(!verify_inclusion(&leaves[1], &proof, &root));
+    }
+}
@@ -0,0 +1,533 @@
+//! M-of-N approval of transfers out of treasury accounts.
+//!
+//! Transfers above the threshold of the account are held back as approvals, with the asset locked
+//! on their behalf so that nothing else can move it in the meantime. The transfer is carried out as
+//! soon as enough signers approve it, and dropped once so many reject it that the quorum can no
+//! longer be reached or its deadline passes.
+//!
+//! Changes that make transfers out of the account easier, such as raising the threshold or
+//! changing the signers, go through the same approvals, decided by the signers they replace.
+
+use error_stack::{ensure, ResultExt};
+use nanoid::nanoid;
+
+use crate::error::{LedgerError, MultisigError, SResult};
+use crate::state::AppState;
+use crate::storage::types::{Approval, ApprovalAction, ApprovalStatus, Multisig, PolicyOperation};
+use crate::storage::MultisigInterface;
+use crate::utils::unix_timestamp;
+use crate::{acl, ledger, policy};
+
+/// How long a request may hold an approval exclusively before others can step in.
+const GUARD_SECS: u64 = 30;
+
+/// Signers of the account, `None` when transfers out of it need no approval.
+pub async fn setup(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+) -> SResult<Option<Multisig>, MultisigError> {
+    interface(app_state)
+        .await?
+        .get_multisig(&ledger::account_resource(user_id, account_id))
+        .await
+        .change_context(MultisigError::StorageError)
+}
+
+/// Whether going from the `current` signers to `next` makes transfers out of the account easier,
+/// in which case the current signers have to approve it. Any change to who the signers are counts,
+/// as a signer added by the owner is as good as a vote of the owner.
+pub fn loosens(current: &Multisig, next: Option<&Multisig>) -> bool {
+    let Some(next) = next else {
+        return true;
+    };
+
+    let mut current_signers = current.signers.iter().collect::<Vec<_>>();
+    let mut next_signers = next.signers.iter().collect::<Vec<_>>();
+    current_signers.sort();
+    next_signers.sort();
+
+    next.threshold > current.threshold
+        || next.required < current.required
+        || next_signers != current_signers
+}
+
+/// Holds the action back until the signers decide, locking the asset on behalf of a transfer. The
+/// charge of the approval is given back when the asset can't be locked.
+pub async fn request(
+    app_state: &AppState,
+    approval: Approval,
+) -> SResult<(String, Approval), MultisigError> {
+    let approval_id = interface(app_state)
+        .await?
+        .create_approval(approval.clone())
+        .await
+        .change_context(MultisigError::StorageError)?;
+
+    let ApprovalAction::Transfer { asset_id, .. } = &approval.action else {
+        return Ok((approval_id, approval));
+    };
+
+    let locked = ledger::lock_asset(
+        app_state,
+        &approval.user_id,
+        &approval.account_id,
+        asset_id,
+        &approval_id,
+        approval.expires_at,
+    )
+    .await;
+
+    match locked {
+        Ok(()) => Ok((approval_id, approval)),
+        Err(error) => {
+            let mut approval = approval;
+            approval.last_error = Some(error.current_context().to_string());
+            conclude(
+                app_state,
+                &approval_id,
+                &mut approval,
+                ApprovalStatus::Failed,
+            )
+            .await?;
+
+            Err(error.change_context(MultisigError::LockFailed))
+        }
+    }
+}
+
+/// Fetches the approval, expiring it first when its deadline passed while it was still pending.
+pub async fn get(app_state: &AppState, approval_id: &str) -> SResult<Approval, MultisigError> {
+    let approval = interface(app_state)
+        .await?
+        .get_approval(approval_id)
+        .await
+        .change_context(MultisigError::NotFound)?;
+
+    refresh(app_state, approval_id, approval).await
+}
+
+pub async fn refresh(
+    app_state: &AppState,
+    approval_id: &str,
+    mut approval: Approval,
+) -> SResult<Approval, MultisigError> {
+    if approval.status == ApprovalStatus::Pending && approval.expires_at <= unix_timestamp() {
+        conclude(
+            app_state,
+            approval_id,
+            &mut approval,
+            ApprovalStatus::Expired,
+        )
+        .await?;
+    }
+
+    Ok(approval)
+}
+
+/// Drops the approval while it is still pending, when the account it was requested for is closed.
+pub async fn cancel(app_state: &AppState, approval_id: &str) -> SResult<(), MultisigError> {
+    let guard = enter(app_state, approval_id).await?;
+
+    let output = async {
+        let mut approval = get(app_state, approval_id).await?;
+
+        if approval.status == ApprovalStatus::Pending {
+            conclude(
+                app_state,
+                approval_id,
+                &mut approval,
+                ApprovalStatus::Cancelled,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+    .await;
+
+    leave(app_state, approval_id, &guard).await?;
+
+    output
+}
+
+/// Records the approval of the signer, carrying the action out once the quorum is reached.
+pub async fn approve(
+    app_state: &AppState,
+    approval_id: &str,
+    ua_addr: &str,
+) -> SResult<Approval, MultisigError> {
+    let guard = enter(app_state, approval_id).await?;
+    let output = vote(app_state, approval_id, ua_addr, true).await;
+    leave(app_state, approval_id, &guard).await?;
+
+    output
+}
+
+/// Records the rejection of the signer, dropping the action once the quorum is out of reach.
+pub async fn reject(
+    app_state: &AppState,
+    approval_id: &str,
+    ua_addr: &str,
+) -> SResult<Approval, MultisigError> {
+    let guard = enter(app_state, approval_id).await?;
+    let output = vote(app_state, approval_id, ua_addr, false).await;
+    leave(app_state, approval_id, &guard).await?;
+
+    output
+}
+
+async fn vote(
+    app_state: &AppState,
+    approval_id: &str,
+    ua_addr: &str,
+    approve: bool,
+) -> SResult<Approval, MultisigError> {
+    let mut approval = get(app_state, approval_id).await?;
+
+    ensure!(
+        approval.status == ApprovalStatus::Pending,
+        MultisigError::NotPending
+    );
+    ensure!(
+        approval.signers.iter().any(|signer| signer == ua_addr),
+        MultisigError::NotSigner
+    );
+    ensure!(
+        !approval
+            .approvals
+            .iter()
+            .chain(&approval.rejections)
+            .any(|signer| signer == ua_addr),
+        MultisigError::AlreadyVoted
+    );
+
+    if approve {
+        approval.approvals.push(ua_addr.to_string());
+    } else {
+        approval.rejections.push(ua_addr.to_string());
+    }
+
+    let required = approval.required as usize;
+    let vetoed = approval.rejections.len() > approval.signers.len().saturating_sub(required);
+
+    if vetoed {
+        conclude(
+            app_state,
+            approval_id,
+            &mut approval,
+            ApprovalStatus::Rejected,
+        )
+        .await?;
+    } else if approval.approvals.len() >= required {
+        execute(app_state, approval_id, &mut approval).await?;
+    } else {
+        update(app_state, approval_id, &approval).await?;
+    }
+
+    Ok(approval)
+}
+
+/// Carries out the approved action. Policies are evaluated again for transfers, as they may have
+/// changed or been used up while the signers were deciding.
+async fn execute(
+    app_state: &AppState,
+    approval_id: &str,
+    approval: &mut Approval,
+) -> SResult<(), MultisigError> {
+    let Approval {
+        user_id,
+        account_id,
+        action,
+        ..
+    } = &mut *approval;
+
+    let executed = match action {
+        ApprovalAction::Transfer {
+            asset_id,
+            amount,
+            peer_ua_addr,
+            peer_account_id,
+            peer_asset_id,
+        } => {
+            async {
+                let admission = policy::admit_transfer(
+                    app_state,
+                    PolicyOperation::Verb,
+                    user_id,
+                    account_id,
+                    peer_ua_addr,
+                    peer_account_id,
+                    *amount,
+                )
+                .await
+                .map_err(ledger::policy_error)
+                .change_context(MultisigError::ExecutionFailed)?;
+
+                let moved = ledger::move_asset(
+                    app_state,
+                    user_id,
+                    account_id,
+                    asset_id,
+                    peer_ua_addr,
+                    peer_account_id,
+                    Some(approval_id),
+                )
+                .await;
+
+                admission.conclude(app_state, &moved).await;
+                *peer_asset_id = Some(moved.change_context(MultisigError::ExecutionFailed)?);
+
+                Ok::<_, error_stack::Report<MultisigError>>(())
+            }
+            .await
+        }
+        ApprovalAction::Setup { multisig } => {
+            let resource = ledger::account_resource(user_id, account_id);
+            let interface = interface(app_state).await?;
+
+            match multisig {
+                Some(multisig) => {
+                    multisig.updated_at = unix_timestamp();
+                    interface.set_multisig(&resource, multisig.clone()).await
+                }
+                None => interface.delete_multisig(&resource).await.map(drop),
+            }
+            .change_context(MultisigError::ExecutionFailed)
+        }
+    };
+
+    match executed {
+        Ok(()) => conclude(app_state, approval_id, approval, ApprovalStatus::Executed).await,
+        Err(error) => {
+            approval.last_error = Some(
+                error
+                    .downcast_ref::<LedgerError>()
+                    .map_or_else(|| error.current_context().to_string(), ToString::to_string),
+            );
+            conclude(app_state, approval_id, approval, ApprovalStatus::Failed).await?;
+
+            Err(error)
+        }
+    }
+}
+
+/// Settles the approval with its final status, unlocking the asset of a transfer and giving back
+/// the charge unless the transfer went through.
+async fn conclude(
+    app_state: &AppState,
+    approval_id: &str,
+    approval: &mut Approval,
+    status: ApprovalStatus,
+) -> SResult<(), MultisigError> {
+    if let ApprovalAction::Transfer { asset_id, .. } = &approval.action {
+        ledger::unlock_asset(
+            app_state,
+            &approval.user_id,
+            &approval.account_id,
+            asset_id,
+            approval_id,
+        )
+        .await
+        .change_context(MultisigError::StorageError)?;
+    }
+
+    if status != ApprovalStatus::Executed {
+        acl::refund(app_state, approval.charge.take())
+            .await
+            .change_context(MultisigError::StorageError)?;
+    }
+
+    approval.status = status;
+    approval.decided_at = Some(unix_timestamp());
+
+    update(app_state, approval_id, approval).await
+}
+
+/// Takes the approval for the duration of a request, so that concurrent votes can't carry the
+/// action out twice.
+async fn enter(app_state: &AppState, approval_id: &str) -> SResult<String, MultisigError> {
+    let guard = nanoid!();
+
+    app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(MultisigError::StorageError)?
+        .acquire(
+            &format!("approvals/{approval_id}"),
+            &guard,
+            unix_timestamp() + GUARD_SECS,
+        )
+        .await
+        .change_context(MultisigError::Busy)?;
+
+    Ok(guard)
+}
+
+async fn leave(app_state: &AppState, approval_id: &str, guard: &str) -> SResult<(), MultisigError> {
+    app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(MultisigError::StorageError)?
+        .release(&format!("approvals/{approval_id}"), guard)
+        .await
+        .change_context(MultisigError::StorageError)
+}
+
+async fn update(
+    app_state: &AppState,
+    approval_id: &str,
+    approval: &Approval,
+) -> SResult<(), MultisigError> {
+    interface(app_state)
+        .await?
+        .update_approval(approval_id, approval.clone())
+        .await
+        .change_context(MultisigError::StorageError)
+}
+
+async fn interface(
+    app_state: &AppState,
+) -> SResult<Box<dyn MultisigInterface + Send + Sync>, MultisigError> {
+    app_state
+        .storage
+        .get_multisig_interface()
+        .await
+        .change_context(MultisigError::StorageError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNERS: [&str; 3] = ["a@ua", "b@ua", "c@ua"];
+
+    fn multisig(threshold: u64, required: u32, signers: &[&str]) -> Multisig {
+        Multisig {
+            threshold,
+            required,
+            signers: signers.iter().map(ToString::to_string).collect(),
+            updated_at: 0,
+        }
+    }
+
+    /// Two of three signers have to approve lifting the threshold of the account.
+    async fn requested(app_state: &AppState) -> String {
+        let now = unix_timestamp();
+        let approval = Approval {
+            user_id: "user".to_string(),
+            account_id: "account".to_string(),
+            action: ApprovalAction::Setup {
+                multisig: Some(multisig(500, 2, &SIGNERS)),
+            },
+            requested_by: Some("users/user".to_string()),
+            signers: SIGNERS.iter().map(ToString::to_string).collect(),
+            required: 2,
+            approvals: Vec::new(),
+            rejections: Vec::new(),
+            charge: None,
+            status: ApprovalStatus::Pending,
+            created_at: now,
+            expires_at: now + 60,
+            decided_at: None,
+            last_error: None,
+        };
+
+        request(app_state, approval).await.unwrap().0
+    }
+
+    #[test]
+    fn tightening_needs_no_approval() {
+        let current = multisig(100, 2, &SIGNERS);
+
+        assert!(!loosens(&current, Some(&multisig(50, 2, &SIGNERS))));
+        assert!(!loosens(&current, Some(&multisig(100, 3, &SIGNERS))));
+        assert!(!loosens(
+            &current,
+            Some(&multisig(100, 2, &["c@ua", "a@ua", "b@ua"]))
+        ));
+    }
+
+    #[test]
+    fn loosening_needs_approval() {
+        let current = multisig(100, 2, &SIGNERS);
+
+        assert!(loosens(&current, None));
+        assert!(loosens(&current, Some(&multisig(200, 2, &SIGNERS))));
+        assert!(loosens(&current, Some(&multisig(100, 1, &SIGNERS))));
+        assert!(loosens(
+            &current,
+            Some(&multisig(100, 2, &["a@ua", "b@ua", "mallory@ua"]))
+        ));
+    }
+
+    #[tokio::test]
+    async fn executes_once_the_quorum_approves() {
+        let app_state = AppState::for_tests();
+        let approval_id = requested(&app_state).await;
+
+        let approval = approve(&app_state, &approval_id, "a@ua").await.unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Pending);
+        assert!(setup(&app_state, "user", "account")
+            .await
+            .unwrap()
+            .is_none());
+
+        let approval = approve(&app_state, &approval_id, "b@ua").await.unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Executed);
+        assert_eq!(
+            setup(&app_state, "user", "account")
+                .await
+                .unwrap()
+                .map(|multisig| multisig.threshold),
+            Some(500)
+        );
+
+        let late = approve(&app_state, &approval_id, "c@ua").await.unwrap_err();
+        assert!(matches!(late.current_context(), MultisigError::NotPending));
+    }
+
+    #[tokio::test]
+    async fn counts_every_signer_once() {
+        let app_state = AppState::for_tests();
+        let approval_id = requested(&app_state).await;
+
+        approve(&app_state, &approval_id, "a@ua").await.unwrap();
+
+        let again = approve(&app_state, &approval_id, "a@ua").await.unwrap_err();
+        assert!(matches!(
+            again.current_context(),
+            MultisigError::AlreadyVoted
+        ));
+
+        let outsider = approve(&app_state, &approval_id, "mallory@ua")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            outsider.current_context(),
+            MultisigError::NotSigner
+        ));
+
+        let approval = get(&app_state, &approval_id).await.unwrap();
+        assert_eq!(approval.approvals, ["a@ua"]);
+        assert_eq!(approval.status, ApprovalStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn drops_the_action_once_the_quorum_is_out_of_reach() {
+        let app_state = AppState::for_tests();
+        let approval_id = requested(&app_state).await;
+
+        let approval = reject(&app_state, &approval_id, "a@ua").await.unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Pending);
+
+        let approval = reject(&app_state, &approval_id, "b@ua").await.unwrap();
+        assert_eq!(approval.status, ApprovalStatus::Rejected);
+        assert!(setup(&app_state, "user", "account")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,174 @@
+//! Path based addressing of accounts and assets.
+//!
+//! Token managers are mounted under a path prefix, after which everything they manage can be
+//! addressed as `<mount>/<ua_addr>/<account>[/<asset_id>]`. The account segment is either the id
+//! of the account or the asset type it holds, e.g. `/udam/upi/alice@upi/cash`.
+
+use error_stack::{ensure, report, ResultExt};
+
+use crate::error::{NamespaceError, SResult};
+use crate::state::AppState;
+use crate::storage::types::AccountRef;
+use crate::storage::AssetInterface;
+
+/// Splits the path into its segments, rejecting anything that isn't a clean absolute path.
+pub fn segments(path: &str) -> SResult<Vec<&str>, NamespaceError> {
+    let rest = path
+        .strip_prefix('/')
+        .ok_or(report!(NamespaceError::InvalidPath))?;
+
+    let output: Vec<&str> = rest.strip_suffix('/').unwrap_or(rest).split('/').collect();
+
+    ensure!(
+        output
+            .iter()
+            .all(|segment| !segment.is_empty() && *segment != "." && *segment != ".."),
+        NamespaceError::InvalidPath
+    );
+
+    Ok(output)
+}
+
+/// Canonical form of the path, as used for keys of the mount table.
+pub fn normalize(path: &str) -> SResult<String, NamespaceError> {
+    Ok(format!("/{}", segments(path)?.join("/")))
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedPath {
+    pub mount: String,
+    pub token_manager_id: String,
+    pub user_id: String,
+    pub ua_addr: String,
+    pub account_id: String,
+    pub asset_id: Option<String>,
+}
+
+impl ResolvedPath {
+    pub async fn asset_interface(
+        &self,
+        app_state: &AppState,
+    ) -> SResult<Box<dyn AssetInterface + Send + Sync>, NamespaceError> {
+        app_state
+            .storage
+            .get_user_interface()
+            .await
+            .change_context(NamespaceError::StorageError)?
+            .get_account_interface(&self.user_id)
+            .await
+            .change_context(NamespaceError::UserNotFound)?
+            .get_asset_interface(&self.account_id)
+            .await
+            .change_context(NamespaceError::AccountNotFound)
+    }
+}
+
+/// Resolves the path through the longest mount covering it.
+pub async fn resolve(app_state: &AppState, path: &str) -> SResult<ResolvedPath, NamespaceError> {
+    let segments = segments(path)?;
+
+    let mounts = app_state
+        .storage
+        .get_mount_interface()
+        .await
+        .change_context(NamespaceError::StorageError)?
+        .list_mounts()
+        .await
+        .change_context(NamespaceError::StorageError)?;
+
+    let (mount, token_manager_id, depth) = mounts
+        .into_iter()
+        .filter_map(|(mount_path, mount)| {
+            let depth = self::segments(&mount_path)
+                .ok()
+                .filter(|mount_segments| segments.starts_with(mount_segments))?
+                .len();
+
+            Some((mount_path, mount.token_manager_id, depth))
+        })
+        .max_by_key(|(_, _, depth)| *depth)
+        .ok_or(report!(NamespaceError::NotMounted))?;
+
+    let (ua_addr, account, asset_id) = match &segments[depth..] {
+        [ua_addr, account] => (*ua_addr, *account, None),
+        [ua_addr, account, asset_id] => (*ua_addr, *account, Some(asset_id.to_string())),
+        _ => return Err(report!(NamespaceError::NotAnAccount)),
+    };
+
+    let user_interface = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(NamespaceError::StorageError)?;
+
+    let user_id = user_interface
+        .get_user_id_by_ua(ua_addr)
+        .await
+        .change_context(NamespaceError::UserNotFound)?;
+
+    let accounts = user_interface
+        .get_account_interface(&user_id)
+        .await
+        .change_context(NamespaceError::UserNotFound)?
+        .list_accounts()
+        .await
+        .change_context(NamespaceError::StorageError)?;
+
+    let managed: Vec<_> = accounts
+        .into_iter()
+        .filter(|(_, value)| value.token_manager_id == token_manager_id)
+        .collect();
+
+    let matching: Vec<String> = match managed.iter().find(|(account_id, _)| account_id == account) {
+        Some((account_id, _)) => vec![account_id.clone()],
+        None => managed
+            .into_iter()
+            .filter(|(_, value)| value.asset_type.to_string() == account)
+            .map(|(account_id, _)| account_id)
+            .collect(),
+    };
+
+    let account_id = match matching.as_slice() {
+        [account_id] => account_id.clone(),
+        [] => return Err(report!(NamespaceError::AccountNotFound)),
+        _ => return Err(report!(NamespaceError::AmbiguousAccount)),
+    };
+
+    Ok(ResolvedPath {
+        mount,
+        token_manager_id,
+        user_id,
+        ua_addr: ua_addr.to_string(),
+        account_id,
+        asset_id,
+    })
+}
+
+/// Resolves a path that has to point to an account rather than to a single asset.
+pub async fn resolve_account(
+    app_state: &AppState,
+    path: &str,
+) -> SResult<ResolvedPath, NamespaceError> {
+    let resolved = resolve(app_state, path).await?;
+
+    ensure!(resolved.asset_id.is_none(), NamespaceError::NotAnAccount);
+
+    Ok(resolved)
+}
+
+impl AccountRef {
+    /// Resolves the reference into the ids of the user and the account.
+    pub async fn resolve(&self, app_state: &AppState) -> SResult<(String, String), NamespaceError> {
+        match self {
+            Self::Ids {
+                user_id,
+                account_id,
+            } => Ok((user_id.clone(), account_id.clone())),
+            Self::Path { path } => {
+                let resolved = resolve_account(app_state, path).await?;
+
+                Ok((resolved.user_id, resolved.account_id))
+            }
+        }
+    }
+}
@@ -0,0 +1,540 @@
+//! Policies attached to supported assets and accounts, evaluated before mints, transfers and verbs.
+//!
+//! Every policy holds a single declarative rule. An operation only goes ahead when each policy
+//! applying to the accounts on either side of it accepts, and a rejection names the policy and the
+//! rule that failed. Velocity rules reserve the operation as they admit it, under a lock on the
+//! usage of the policy, so that concurrent operations can't all pass the same remaining headroom.
+//! The reservation is released when the operation doesn't go through.
+
+use std::time::Duration;
+
+use error_stack::{report, ResultExt};
+use nanoid::nanoid;
+
+use crate::error::{PolicyError, SResult};
+use crate::ledger;
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{Policy, PolicyOperation, PolicyRule, PolicyTarget, PolicyUsage};
+use crate::storage::PolicyInterface;
+use crate::utils::unix_timestamp;
+
+/// How long an admission may hold the usage of a policy while counting against it.
+const GUARD_SECS: u64 = 5;
+/// Attempts at taking the usage of a policy held by a concurrent admission.
+const GUARD_ATTEMPTS: u32 = 50;
+
+/// Operation accepted by the policies, holding its reservations on the velocity policies until it
+/// is concluded.
+#[must_use]
+pub struct Admission {
+    amount: u64,
+    /// Usage reserved on velocity policies, by policy id, resource and usage id.
+    reserved: Vec<(String, String, String)>,
+}
+
+impl Admission {
+    /// Keeps the reservations when the operation went through and releases them otherwise.
+    pub async fn conclude<T, E>(self, app_state: &AppState, outcome: &Result<T, E>) {
+        if outcome.is_err() {
+            self.release(app_state).await;
+        }
+    }
+
+    /// Gives back the usage reserved for an operation that didn't go through.
+    pub async fn release(self, app_state: &AppState) {
+        if self.reserved.is_empty() {
+            return;
+        }
+
+        let policy_interface = match app_state.storage.get_policy_interface().await {
+            Ok(policy_interface) => policy_interface,
+            Err(error) => {
+                warn!(?error, "Failed while releasing the policy usage");
+                return;
+            }
+        };
+
+        for (policy_id, resource, usage_id) in self.reserved {
+            if let Err(error) = policy_interface
+                .release_usage(&policy_id, &resource, &usage_id)
+                .await
+            {
+                warn!(?error, policy_id, "Failed while releasing the policy usage");
+            }
+        }
+    }
+}
+
+/// Evaluates the policies of the account before minting into it.
+pub async fn admit_mint(
+    app_state: &AppState,
+    user_id: &str,
+    account_id: &str,
+    amount: u64,
+) -> SResult<Admission, PolicyError> {
+    let holder = get_user(app_state, user_id).await?;
+
+    let mut admission = Admission {
+        amount,
+        reserved: Vec::new(),
+    };
+
+    let verdict = admit(
+        app_state,
+        PolicyOperation::Mint,
+        user_id,
+        account_id,
+        holder.jurisdiction.as_deref(),
+        &mut admission,
+    )
+    .await;
+
+    if let Err(error) = verdict {
+        admission.release(app_state).await;
+
+        return Err(error);
+    }
+
+    Ok(admission)
+}
+
+/// Evaluates the policies of the accounts on both sides before moving `amount` between them.
+pub async fn admit_transfer(
+    app_state: &AppState,
+    operation: PolicyOperation,
+    user_id: &str,
+    account_id: &str,
+    peer_ua_addr: &str,
+    peer_account_id: &str,
+    amount: u64,
+) -> SResult<Admission, PolicyError> {
+    let user = get_user(app_state, user_id).await?;
+
+    let peer_user_id = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(PolicyError::StorageError)?
+        .get_user_id_by_ua(peer_ua_addr)
+        .await
+        .change_context(PolicyError::AccountNotFound)?;
+    let peer = get_user(app_state, &peer_user_id).await?;
+
+    let mut admission = Admission {
+        amount,
+        reserved: Vec::new(),
+    };
+
+    let verdict = async {
+        admit(
+            app_state,
+            operation,
+            user_id,
+            account_id,
+            peer.jurisdiction.as_deref(),
+            &mut admission,
+        )
+        .await?;
+
+        admit(
+            app_state,
+            operation,
+            &peer_user_id,
+            peer_account_id,
+            user.jurisdiction.as_deref(),
+            &mut admission,
+        )
+        .await
+    }
+    .await;
+
+    // what the first account reserved is given back when the second one rejects
+    if let Err(error) = verdict {
+        admission.release(app_state).await;
+
+        return Err(error);
+    }
+
+    Ok(admission)
+}
+
+async fn get_user(
+    app_state: &AppState,
+    user_id: &str,
+) -> SResult<crate::storage::types::User, PolicyError> {
+    app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(PolicyError::StorageError)?
+        .get_user(user_id)
+        .await
+        .change_context(PolicyError::AccountNotFound)
+}
+
+/// Evaluates the policies applying to the account, with `counterparty` the jurisdiction of the
+/// party on the other side.
+async fn admit(
+    app_state: &AppState,
+    operation: PolicyOperation,
+    user_id: &str,
+    account_id: &str,
+    counterparty: Option<&str>,
+    admission: &mut Admission,
+) -> SResult<(), PolicyError> {
+    let (account, _) = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(PolicyError::StorageError)?
+        .get_account_interface(user_id)
+        .await
+        .change_context(PolicyError::AccountNotFound)?
+        .get_account(account_id)
+        .await
+        .change_context(PolicyError::AccountNotFound)?;
+
+    let policy_interface = app_state
+        .storage
+        .get_policy_interface()
+        .await
+        .change_context(PolicyError::StorageError)?;
+
+    let policies = policy_interface
+        .list_policies()
+        .await
+        .change_context(PolicyError::StorageError)?
+        .into_iter()
+        .filter(|(_, policy)| {
+            policy.operations.is_empty() || policy.operations.contains(&operation)
+        })
+        .filter(|(_, policy)| match &policy.target {
+            PolicyTarget::SupportedAsset {
+                token_manager_id,
+                asset_type,
+                ..
+            } => token_manager_id == &account.token_manager_id && asset_type == &account.asset_type,
+            PolicyTarget::Account {
+                user_id: target_user_id,
+                account_id: target_account_id,
+            } => target_user_id == user_id && target_account_id == account_id,
+        });
+
+    let resource = ledger::account_resource(user_id, account_id);
+    let amount = admission.amount;
+    let now = unix_timestamp();
+
+    for (policy_id, policy) in policies {
+        let verdict = match &policy.rule {
+            PolicyRule::MaxAmount { max_amount } => (amount <= *max_amount)
+                .then_some(())
+                .ok_or_else(|| format!("amount {amount} is above {max_amount}")),
+            PolicyRule::Jurisdictions { allowed } => counterparty
+                .filter(|jurisdiction| allowed.iter().any(|allowed| allowed == jurisdiction))
+                .map(|_| ())
+                .ok_or_else(|| {
+                    format!(
+                        "counterparty jurisdiction {} is not allowed",
+                        counterparty.unwrap_or("unknown")
+                    )
+                }),
+            PolicyRule::BusinessHours {
+                start_hour,
+                end_hour,
+                days,
+                utc_offset_minutes,
+            } => {
+                let local = now as i64 + i64::from(*utc_offset_minutes) * 60;
+                let hour = local.rem_euclid(86_400) / 3600;
+                // the epoch fell on a thursday
+                let day = crate::storage::types::Weekday::ALL
+                    [(local.div_euclid(86_400) + 3).rem_euclid(7) as usize];
+
+                let (start, end) = (i64::from(*start_hour), i64::from(*end_hour));
+                let within = if start < end {
+                    start <= hour && hour < end
+                } else {
+                    hour >= start || hour < end
+                };
+
+                (within && days.contains(&day))
+                    .then_some(())
+                    .ok_or_else(|| "operation is outside the business hours".to_string())
+            }
+            PolicyRule::Velocity {
+                window_secs,
+                max_amount,
+                max_count,
+            } => {
+                let guard = enter(app_state, &policy_id, &resource).await?;
+                let reserved = reserve(
+                    &*policy_interface,
+                    &policy_id,
+                    &resource,
+                    amount,
+                    now,
+                    *window_secs,
+                    *max_amount,
+                    *max_count,
+                )
+                .await;
+                let left = leave(app_state, &policy_id, &resource, &guard).await;
+
+                // taken into the admission first, so that it's released along with the others
+                let verdict = reserved?.map(|usage_id| {
+                    admission
+                        .reserved
+                        .push((policy_id.clone(), resource.clone(), usage_id));
+                });
+                left?;
+
+                verdict
+            }
+        };
+
+        if let Err(reason) = verdict {
+            return Err(rejection(&policy, reason));
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts the operation against a velocity policy when it fits in the window, returning the id of
+/// the reserved usage. To be called while holding the usage of the policy.
+#[allow(clippy::too_many_arguments)]
+async fn reserve(
+    policy_interface: &(dyn PolicyInterface + Send + Sync),
+    policy_id: &str,
+    resource: &str,
+    amount: u64,
+    now: u64,
+    window_secs: u64,
+    max_amount: Option<u64>,
+    max_count: Option<u64>,
+) -> SResult<Result<String, String>, PolicyError> {
+    let since = now.saturating_sub(window_secs);
+
+    let usage = policy_interface
+        .usage(policy_id, resource, since)
+        .await
+        .change_context(PolicyError::StorageError)?;
+
+    let total = usage
+        .iter()
+        .fold(amount, |total, usage| total.saturating_add(usage.amount));
+    let count = usage.len() as u64 + 1;
+
+    if max_amount.is_some_and(|max_amount| total > max_amount) {
+        return Ok(Err(format!("{total} would be moved within {window_secs}s")));
+    }
+
+    if max_count.is_some_and(|max_count| count > max_count) {
+        return Ok(Err(format!(
+            "{count} operations would be made within {window_secs}s"
+        )));
+    }
+
+    let usage_id = nanoid!();
+
+    policy_interface
+        .record_usage(
+            policy_id,
+            resource,
+            PolicyUsage {
+                usage_id: usage_id.clone(),
+                at: now,
+                amount,
+            },
+            since,
+        )
+        .await
+        .change_context(PolicyError::StorageError)?;
+
+    Ok(Ok(usage_id))
+}
+
+fn usage_resource(policy_id: &str, resource: &str) -> String {
+    format!("policies/{policy_id}/{resource}")
+}
+
+/// Takes the usage of the policy on the resource, waiting for concurrent admissions to finish
+/// counting against it.
+async fn enter(
+    app_state: &AppState,
+    policy_id: &str,
+    resource: &str,
+) -> SResult<String, PolicyError> {
+    let guard = nanoid!();
+    let lock_interface = app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(PolicyError::StorageError)?;
+
+    let mut attempts = 0;
+
+    loop {
+        let acquired = lock_interface
+            .acquire(
+                &usage_resource(policy_id, resource),
+                &guard,
+                unix_timestamp() + GUARD_SECS,
+            )
+            .await;
+
+        match acquired {
+            Ok(()) => return Ok(guard),
+            Err(error) if attempts + 1 >= GUARD_ATTEMPTS => {
+                return Err(error.change_context(PolicyError::StorageError))
+            }
+            Err(_) => {
+                attempts += 1;
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+    }
+}
+
+async fn leave(
+    app_state: &AppState,
+    policy_id: &str,
+    resource: &str,
+    guard: &str,
+) -> SResult<(), PolicyError> {
+    app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(PolicyError::StorageError)?
+        .release(&usage_resource(policy_id, resource), guard)
+        .await
+        .change_context(PolicyError::StorageError)
+}
+
+fn rejection(policy: &Policy, reason: String) -> error_stack::Report<PolicyError> {
+    let rule: &'static str = (&policy.rule).into();
+
+    report!(PolicyError::Rejected {
+        policy: policy.name.clone(),
+        rule: rule.to_string(),
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::types::{Account, AssetType, TokenManagerRef, User};
+
+    /// Account of a fresh user, limited to minting 100 within an hour.
+    async fn limited(app_state: &AppState) -> (String, String) {
+        let user = User {
+            email: "alice@example.com".to_string(),
+            name: "alice".to_string(),
+            public_key: String::new(),
+            ua_addr: "alice@ua".to_string(),
+            jurisdiction: None,
+            previous_keys: Vec::new(),
+            status: Default::default(),
+            closed_at: None,
+        };
+        let user_interface = app_state.storage.get_user_interface().await.unwrap();
+        let user_id = user_interface.create_user(user).await.unwrap();
+
+        let account = Account {
+            account_name: "cash".to_string(),
+            token_manager_id: "bank".to_string(),
+            asset_type: AssetType::Cash,
+            token_manager_ref: TokenManagerRef {
+                id: "ref".to_string(),
+                token_manager_name: "bank".to_string(),
+                internal_addr: "internal".to_string(),
+            },
+        };
+        let account_id = user_interface
+            .get_account_interface(&user_id)
+            .await
+            .unwrap()
+            .create_account(account)
+            .await
+            .unwrap();
+
+        let policy = Policy {
+            name: "hourly".to_string(),
+            target: PolicyTarget::Account {
+                user_id: user_id.clone(),
+                account_id: account_id.clone(),
+            },
+            rule: PolicyRule::Velocity {
+                window_secs: 3600,
+                max_amount: Some(100),
+                max_count: None,
+            },
+            operations: vec![PolicyOperation::Mint],
+            created_by: None,
+            created_at: unix_timestamp(),
+        };
+        app_state
+            .storage
+            .get_policy_interface()
+            .await
+            .unwrap()
+            .create_policy(policy)
+            .await
+            .unwrap();
+
+        (user_id, account_id)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_admissions_share_the_window() {
+        let app_state = AppState::for_tests();
+        let (user_id, account_id) = limited(&app_state).await;
+
+        let admissions = (0..6)
+            .map(|_| {
+                let (app_state, user_id, account_id) =
+                    (app_state.clone(), user_id.clone(), account_id.clone());
+
+                tokio::spawn(async move { admit_mint(&app_state, &user_id, &account_id, 30).await })
+            })
+            .collect::<Vec<_>>();
+
+        let mut admitted = Vec::new();
+        for admission in admissions {
+            if let Ok(admission) = admission.await.unwrap() {
+                admitted.push(admission);
+            }
+        }
+
+        assert_eq!(admitted.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_back_the_usage_of_operations_that_fail() {
+        let app_state = AppState::for_tests();
+        let (user_id, account_id) = limited(&app_state).await;
+
+        let admission = admit_mint(&app_state, &user_id, &account_id, 80)
+            .await
+            .unwrap();
+        assert!(admit_mint(&app_state, &user_id, &account_id, 80)
+            .await
+            .is_err());
+
+        admission
+            .conclude(&app_state, &Err::<(), _>("issuing failed"))
+            .await;
+
+        let admission = admit_mint(&app_state, &user_id, &account_id, 80)
+            .await
+            .unwrap();
+        admission.conclude(&app_state, &Ok::<_, ()>(())).await;
+
+        assert!(admit_mint(&app_state, &user_id, &account_id, 80)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use error_stack::{report, ResultExt};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::error::{ConfigurationError, SResult, StorageError};
+use crate::hash_chain::{self, Link};
+use crate::storage::types::{Event, EventType, Holding, ProofEntry};
+use crate::storage::ProofInterface;
+use crate::utils::unix_timestamp;
+
+/// Append-only, hash-chained log of committed operations, mirrored to a local file as json lines.
+#[derive(Clone)]
+pub struct ProofStore {
+    log: Arc<Mutex<ProofLog>>,
+    session_start: u64,
+}
+
+struct ProofLog {
+    entries: Vec<ProofEntry>,
+    file: Option<tokio::fs::File>,
+}
+
+impl ProofStore {
+    pub fn in_memory() -> Self {
+        Self {
+            log: Arc::new(Mutex::new(ProofLog {
+                entries: Vec::new(),
+                file: None,
+            })),
+            session_start: 0,
+        }
+    }
+
+    /// Loads the existing log from `path`, new entries are appended to the same file.
+    pub fn open(path: &Path) -> SResult<Self, ConfigurationError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).change_context(ConfigurationError::ProofStoreError)?;
+        }
+
+        let entries = match std::fs::read_to_string(path) {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str::<ProofEntry>)
+                .collect::<Result<Vec<_>, _>>()
+                .change_context(ConfigurationError::ProofStoreError)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                return Err(report!(error).change_context(ConfigurationError::ProofStoreError))
+            }
+        };
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .change_context(ConfigurationError::ProofStoreError)?;
+
+        Ok(Self {
+            session_start: entries.len() as u64,
+            log: Arc::new(Mutex::new(ProofLog {
+                entries,
+                file: Some(tokio::fs::File::from_std(file)),
+            })),
+        })
+    }
+}
+
+impl Link for ProofEntry {
+    type Payload = Event;
+
+    const NAME: &'static str = "entry";
+    const PAYLOAD_FIELD: &'static str = "event";
+
+    fn index(&self) -> u64 {
+        self.index
+    }
+
+    fn previous_hash(&self) -> &str {
+        &self.previous_hash
+    }
+
+    fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    fn recorded_at(&self) -> u64 {
+        self.recorded_at
+    }
+
+    fn payload(&self) -> &Event {
+        &self.event
+    }
+}
+
+/// Replays the proof log and reports the holdings whose stored state differs from what the log
+/// committed to, along with the assets the log expects that are missing from storage.
+pub fn audit_holdings(entries: &[ProofEntry], holdings: &[Holding]) -> Vec<String> {
+    let mut expected = std::collections::HashMap::new();
+
+    for entry in entries {
+        let event = &entry.event;
+
+        match event.event_type {
+            EventType::Mint => {
+                expected.insert(
+                    (event.account_id.clone(), event.asset_id.clone()),
+                    event.asset_info.clone(),
+                );
+            }
+            EventType::Burn => {
+                expected.remove(&(event.account_id.clone(), event.asset_id.clone()));
+            }
+            EventType::Transfer => {
+                expected.remove(&(event.account_id.clone(), event.asset_id.clone()));
+
+                if let Some(counterparty) = &event.counterparty {
+                    expected.insert(
+                        (
+                            counterparty.account_id.clone(),
+                            counterparty.asset_id.clone(),
+                        ),
+                        event.asset_info.clone(),
+                    );
+                }
+            }
+            EventType::Lock | EventType::Unlock => {}
+        }
+    }
+
+    let mut mismatched = Vec::new();
+
+    for holding in holdings {
+        let key = (holding.account_id.clone(), holding.asset_id.clone());
+
+        match expected.remove(&key) {
+            Some(asset_info) if asset_info == holding.asset_info => {}
+            _ => mismatched.push(holding.asset_id.clone()),
+        }
+    }
+
+    mismatched.extend(expected.into_keys().map(|(_, asset_id)| asset_id));
+    mismatched.sort();
+
+    mismatched
+}
+
+#[async_trait::async_trait]
+impl ProofInterface for ProofStore {
+    async fn append(&self, event: Event) -> SResult<ProofEntry, StorageError> {
+        let mut log = self.log.lock().await;
+
+        let index = log.entries.len() as u64;
+        let previous_hash = hash_chain::head(&log.entries);
+        let recorded_at = unix_timestamp();
+
+        let hash = hash_chain::hash::<ProofEntry>(index, &previous_hash, recorded_at, &event)
+            .change_context(StorageError::ProofWriteError)?;
+
+        let entry = ProofEntry {
+            index,
+            transaction_id: event.event_id.clone(),
+            previous_hash,
+            hash,
+            recorded_at,
+            event,
+        };
+
+        if let Some(file) = log.file.as_mut() {
+            let mut line =
+                serde_json::to_vec(&entry).change_context(StorageError::ProofWriteError)?;
+            line.push(b'\n');
+
+            file.write_all(&line)
+                .await
+                .change_context(StorageError::ProofWriteError)?;
+            file.flush()
+                .await
+                .change_context(StorageError::ProofWriteError)?;
+        }
+
+        log.entries.push(entry.clone());
+
+        Ok(entry)
+    }
+
+    async fn get_proof(&self, transaction_id: &str) -> SResult<ProofEntry, StorageError> {
+        let log = self.log.lock().await;
+
+        log.entries
+            .iter()
+            .find(|entry| entry.transaction_id == transaction_id)
+            .cloned()
+            .ok_or(report!(StorageError::ProofNotFoundError))
+    }
+
+    async fn list_proofs(&self) -> SResult<Vec<ProofEntry>, StorageError> {
+        Ok(self.log.lock().await.entries.clone())
+    }
+
+    fn session_start(&self) -> u64 {
+        self.session_start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_chain::GENESIS_HASH;
+    use crate::storage::types::{AssetInfo, Currency};
+
+    fn event(amount: u64) -> Event {
+        Event {
+            event_id: format!("event{amount}"),
+            event_type: EventType::Mint,
+            token_manager_id: "tm".to_string(),
+            user_id: "user".to_string(),
+            ua_addr: "user@ua".to_string(),
+            account_id: "account".to_string(),
+            asset_id: format!("asset{amount}"),
+            asset_info: AssetInfo::Cash {
+                currency: Currency::USD,
+                amount,
+            },
+            counterparty: None,
+            created_at: 0,
+        }
+    }
+
+    async fn chain(length: u64) -> Vec<ProofEntry> {
+        let store = ProofStore::in_memory();
+
+        for amount in 1..=length {
+            store.append(event(amount)).await.unwrap();
+        }
+
+        store.list_proofs().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn verifies_an_untouched_chain() {
+        let entries = chain(3).await;
+
+        assert_eq!(entries[0].previous_hash, GENESIS_HASH);
+        assert!(hash_chain::verify(&entries).is_ok());
+        assert!(hash_chain::verify::<ProofEntry>(&[]).is_ok());
+    }
+
+    #[tokio::test]
+    async fn detects_a_tampered_entry() {
+        let mut entries = chain(3).await;
+        entries[1].event.asset_info = AssetInfo::Cash {
+            currency: Currency::USD,
+            amount: 1_000,
+        };
+
+        let violation = hash_chain::verify(&entries).unwrap_err();
+
+        assert_eq!(violation.index, 1);
+        assert_eq!(violation.reason, "entry hash does not match its contents");
+    }
+
+    #[tokio::test]
+    async fn detects_a_broken_link() {
+        let mut entries = chain(3).await;
+        entries[2].previous_hash = entries[0].hash.clone();
+
+        let violation = hash_chain::verify(&entries).unwrap_err();
+
+        assert_eq!(violation.index, 2);
+        assert_eq!(
+            violation.reason,
+            "entry does not link to the previous entry"
+        );
+    }
+
+    #[tokio::test]
+    async fn detects_a_dropped_entry() {
+        let mut entries = chain(3).await;
+        entries.remove(1);
+
+        let violation = hash_chain::verify(&entries).unwrap_err();
+
+        assert_eq!(violation.index, 2);
+        assert_eq!(violation.reason, "entry is out of sequence");
+    }
+}
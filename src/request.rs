@@ -0,0 +1,41 @@
+//! Identification of the request being served, handed back to clients so that they can refer to
+//! it, e.g. when reporting an error.
+
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use nanoid::nanoid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the request the current task is serving, if any.
+pub fn current_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Serves the request under the id the client picked, or a fresh one, and echoes it back in the
+/// response headers.
+pub async fn assign_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| (1..=128).contains(&value.len()))
+        .map(ToString::to_string)
+        .unwrap_or_else(|| nanoid!());
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
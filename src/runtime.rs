@@ -0,0 +1,564 @@
+//! Embedded WebAssembly runtime for user supplied workflows.
+//!
+//! Workflows run inside a sandboxed interpreter without any access to the network or the file
+//! system. The only way a workflow can touch the ledger is through the host functions exported
+//! under the `finternet` module, all of which act on behalf of the user invoking the workflow.
+//! `credit` mints cash, so it is only carried out when the caller operates the token manager
+//! behind the account:
+//!
+//! | function   | signature                                                      |
+//! |------------|----------------------------------------------------------------|
+//! | `view`     | `(account_ptr: i32, account_len: i32) -> i64`                  |
+//! | `debit`    | `(account_ptr: i32, account_len: i32, amount: i64) -> i32`     |
+//! | `credit`   | `(account_ptr: i32, account_len: i32, amount: i64) -> i32`     |
+//! | `transfer` | `(account_ptr, account_len, peer_ua_ptr, peer_ua_len, peer_account_ptr, peer_account_len: i32, amount: i64) -> i32` |
+//!
+//! Strings are passed as UTF-8 slices of the exported `memory`. Accounts may also be referred to
+//! by their path in the namespace, in which case the peer account argument of `transfer` is
+//! ignored when the peer unique address is a path. A negative return value is one of the
+//! [`status`] codes.
+//!
+//! Host functions go through the same access checks as the HTTP verbs, with the caller of the
+//! workflow as principal. Execution is bounded by its fuel and by the memory and tables it may
+//! grow to, past its time limit it is stopped at the next call into the host.
+
+use std::time::{Duration, Instant};
+
+use error_stack::{ensure, report, ResultExt};
+use serde::Serialize;
+use utoipa::ToSchema;
+use wasmi::core::ValType;
+use wasmi::{
+    Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Val,
+};
+
+use crate::auth::Principal;
+use crate::config::RuntimeSettings;
+use crate::error::{AclError, LedgerError, NamespaceError, RuntimeError, SResult};
+use crate::state::AppState;
+use crate::storage::types::{GrantCharge, Permission};
+use crate::{acl, ledger, namespace};
+
+const HOST_MODULE: &str = "finternet";
+
+/// Strings handed over by a workflow are never expected to be longer than this.
+const MAX_STRING_LEN: usize = 1024;
+
+/// Values returned by host functions when the operation couldn't be carried out.
+pub mod status {
+    pub const OK: i32 = 0;
+    pub const INVALID_ARGUMENT: i32 = -1;
+    pub const NOT_FOUND: i32 = -2;
+    pub const INSUFFICIENT_FUNDS: i32 = -3;
+    pub const FAILED: i32 = -4;
+    pub const LOCKED: i32 = -5;
+    /// A policy of one of the accounts refused the operation.
+    pub const REJECTED: i32 = -6;
+    /// The caller of the workflow isn't allowed to carry out the operation.
+    pub const FORBIDDEN: i32 = -7;
+    /// The operation would exceed the debit limit of the access grant of the caller.
+    pub const LIMIT_EXCEEDED: i32 = -8;
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Completed,
+    /// The workflow trapped, ran out of fuel or time. Operations performed before that point
+    /// remain in effect.
+    Aborted,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum Operation {
+    Debit {
+        account_id: String,
+        amount: u64,
+    },
+    Credit {
+        account_id: String,
+        amount: u64,
+        asset_id: String,
+    },
+    Transfer {
+        account_id: String,
+        peer_ua_addr: String,
+        peer_account_id: String,
+        amount: u64,
+        peer_asset_ids: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExecutionReport {
+    pub status: ExecutionStatus,
+    pub result: Option<i64>,
+    pub error: Option<String>,
+    pub fuel_consumed: u64,
+    /// Ledger operations that were committed by the workflow, in order.
+    pub operations: Vec<Operation>,
+}
+
+struct HostState {
+    app_state: AppState,
+    handle: tokio::runtime::Handle,
+    principal: Principal,
+    user_id: String,
+    deadline: Instant,
+    limits: StoreLimits,
+    operations: Vec<Operation>,
+}
+
+fn engine() -> Engine {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+/// Checks that the module compiles and fits the configured limits.
+pub fn validate(settings: &RuntimeSettings, module: &[u8]) -> SResult<(), RuntimeError> {
+    ensure!(
+        module.len() <= settings.max_module_bytes,
+        RuntimeError::ModuleTooLarge
+    );
+
+    Module::new(&engine(), module).change_context(RuntimeError::InvalidModule)?;
+
+    Ok(())
+}
+
+/// Runs `entrypoint` of the module on behalf of `user_id`, as requested by `principal`.
+pub async fn execute(
+    app_state: &AppState,
+    module: Vec<u8>,
+    principal: Principal,
+    user_id: String,
+    entrypoint: String,
+    args: Vec<i64>,
+) -> SResult<ExecutionReport, RuntimeError> {
+    let settings = app_state.config.runtime.clone();
+    let timeout = Duration::from_millis(settings.timeout_ms);
+
+    let host_state = HostState {
+        app_state: app_state.clone(),
+        handle: tokio::runtime::Handle::current(),
+        principal,
+        user_id,
+        deadline: Instant::now() + timeout,
+        limits: StoreLimitsBuilder::new()
+            .memory_size(settings.max_memory_bytes)
+            .table_elements(settings.max_table_elements)
+            .instances(1)
+            .memories(1)
+            .tables(1)
+            .build(),
+        operations: Vec::new(),
+    };
+
+    // the interpreter can't be interrupted from the outside, running out of fuel is what stops a
+    // module stuck in a pure compute loop
+    tokio::task::spawn_blocking(move || run(host_state, &settings, &module, &entrypoint, &args))
+        .await
+        .change_context(RuntimeError::ExecutionFailed)?
+}
+
+fn run(
+    host_state: HostState,
+    settings: &RuntimeSettings,
+    bytes: &[u8],
+    entrypoint: &str,
+    args: &[i64],
+) -> SResult<ExecutionReport, RuntimeError> {
+    let engine = engine();
+    let module = Module::new(&engine, bytes).change_context(RuntimeError::InvalidModule)?;
+
+    let mut store = Store::new(&engine, host_state);
+    store.limiter(|state| &mut state.limits);
+    store
+        .set_fuel(settings.fuel)
+        .map_err(|_| report!(RuntimeError::InstantiationFailed))?;
+
+    let instance = linker(&engine)?
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .change_context(RuntimeError::InstantiationFailed)?;
+
+    let func = instance
+        .get_func(&store, entrypoint)
+        .ok_or(report!(RuntimeError::EntrypointNotFound))?;
+
+    let ty = func.ty(&store);
+
+    ensure!(
+        ty.params().len() == args.len()
+            && ty.params().iter().all(|param| *param == ValType::I64)
+            && ty.results().len() <= 1,
+        RuntimeError::EntrypointSignatureMismatch
+    );
+
+    let inputs: Vec<Val> = args.iter().copied().map(Val::I64).collect();
+    let mut outputs: Vec<Val> = ty.results().iter().copied().map(Val::default).collect();
+
+    let outcome = func.call(&mut store, &inputs, &mut outputs);
+
+    let fuel_consumed = settings
+        .fuel
+        .saturating_sub(store.get_fuel().unwrap_or_default());
+    let operations = std::mem::take(&mut store.data_mut().operations);
+
+    Ok(match outcome {
+        Ok(()) => ExecutionReport {
+            status: ExecutionStatus::Completed,
+            result: outputs.first().and_then(|value| match value {
+                Val::I32(value) => Some(i64::from(*value)),
+                Val::I64(value) => Some(*value),
+                _ => None,
+            }),
+            error: None,
+            fuel_consumed,
+            operations,
+        },
+        Err(error) => ExecutionReport {
+            status: ExecutionStatus::Aborted,
+            result: None,
+            error: Some(error.to_string()),
+            fuel_consumed,
+            operations,
+        },
+    })
+}
+
+fn linker(engine: &Engine) -> SResult<Linker<HostState>, RuntimeError> {
+    let mut linker = Linker::new(engine);
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "view",
+            |caller: Caller<'_, HostState>, ptr: i32, len: i32| -> Result<i64, wasmi::Error> {
+                check_deadline(&caller)?;
+
+                let Some(account_id) = read_str(&caller, ptr, len) else {
+                    return Ok(i64::from(status::INVALID_ARGUMENT));
+                };
+
+                let state = caller.data();
+                let account_id = match own_account(state, account_id).and_then(|account_id| {
+                    authorize(state, &account_id, Permission::View, 0).map(|_| account_id)
+                }) {
+                    Ok(account_id) => account_id,
+                    Err(code) => return Ok(i64::from(code)),
+                };
+
+                let balance = state.handle.block_on(ledger::balance(
+                    &state.app_state,
+                    &state.user_id,
+                    &account_id,
+                ));
+
+                Ok(match balance {
+                    Ok(money) => i64::try_from(money.amount).unwrap_or(i64::MAX),
+                    Err(error) => i64::from(status_of(error.current_context())),
+                })
+            },
+        )
+        .change_context(RuntimeError::InstantiationFailed)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "debit",
+            |mut caller: Caller<'_, HostState>,
+             ptr: i32,
+             len: i32,
+             amount: i64|
+             -> Result<i32, wasmi::Error> {
+                check_deadline(&caller)?;
+
+                let (Some(account_id), Ok(amount)) =
+                    (read_str(&caller, ptr, len), u64::try_from(amount))
+                else {
+                    return Ok(status::INVALID_ARGUMENT);
+                };
+
+                let state = caller.data();
+                let (account_id, charge) =
+                    match own_account(state, account_id).and_then(|account_id| {
+                        authorize(state, &account_id, Permission::Debit, amount)
+                            .map(|charge| (account_id, charge))
+                    }) {
+                        Ok(authorized) => authorized,
+                        Err(code) => return Ok(code),
+                    };
+
+                let outcome = state.handle.block_on(ledger::debit(
+                    &state.app_state,
+                    &state.user_id,
+                    &account_id,
+                    amount,
+                    None,
+                ));
+
+                Ok(record(&mut caller, outcome, charge, |()| {
+                    Operation::Debit { account_id, amount }
+                }))
+            },
+        )
+        .change_context(RuntimeError::InstantiationFailed)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "credit",
+            |mut caller: Caller<'_, HostState>,
+             ptr: i32,
+             len: i32,
+             amount: i64|
+             -> Result<i32, wasmi::Error> {
+                check_deadline(&caller)?;
+
+                let (Some(account_id), Ok(amount)) =
+                    (read_str(&caller, ptr, len), u64::try_from(amount))
+                else {
+                    return Ok(status::INVALID_ARGUMENT);
+                };
+
+                let state = caller.data();
+                let account_id = match own_account(state, account_id) {
+                    Ok(account_id) => account_id,
+                    Err(code) => return Ok(code),
+                };
+
+                // only the token manager behind the account may mint into it
+                let token_manager_id = match state.handle.block_on(ledger::token_manager_of(
+                    &state.app_state,
+                    &state.user_id,
+                    &account_id,
+                )) {
+                    Ok(token_manager_id) => token_manager_id,
+                    Err(error) => return Ok(status_of(error.current_context())),
+                };
+
+                if !state.principal.operates(&token_manager_id) {
+                    return Ok(status::FORBIDDEN);
+                }
+
+                // the owner may still refuse credits from its token manager
+                if let Err(code) = authorize(state, &account_id, Permission::Credit, 0) {
+                    return Ok(code);
+                }
+
+                let outcome = state.handle.block_on(ledger::credit(
+                    &state.app_state,
+                    &state.user_id,
+                    &account_id,
+                    amount,
+                ));
+
+                Ok(record(&mut caller, outcome, None, |asset_id| {
+                    Operation::Credit {
+                        account_id,
+                        amount,
+                        asset_id,
+                    }
+                }))
+            },
+        )
+        .change_context(RuntimeError::InstantiationFailed)?;
+
+    linker
+        .func_wrap(
+            HOST_MODULE,
+            "transfer",
+            |mut caller: Caller<'_, HostState>,
+             ptr: i32,
+             len: i32,
+             peer_ua_ptr: i32,
+             peer_ua_len: i32,
+             peer_account_ptr: i32,
+             peer_account_len: i32,
+             amount: i64|
+             -> Result<i32, wasmi::Error> {
+                check_deadline(&caller)?;
+
+                let (Some(account_id), Some(peer_ua_addr), Some(peer_account_id), Ok(amount)) = (
+                    read_str(&caller, ptr, len),
+                    read_str(&caller, peer_ua_ptr, peer_ua_len),
+                    read_str(&caller, peer_account_ptr, peer_account_len),
+                    u64::try_from(amount),
+                ) else {
+                    return Ok(status::INVALID_ARGUMENT);
+                };
+
+                let state = caller.data();
+                let resolved = own_account(state, account_id).and_then(|account_id| {
+                    peer_account(state, peer_ua_addr, peer_account_id).map(
+                        |(peer_ua_addr, peer_account_id)| {
+                            (account_id, peer_ua_addr, peer_account_id)
+                        },
+                    )
+                });
+                let (account_id, peer_ua_addr, peer_account_id) = match resolved {
+                    Ok(resolved) => resolved,
+                    Err(code) => return Ok(code),
+                };
+                let charge = match authorize(state, &account_id, Permission::Transfer, amount) {
+                    Ok(charge) => charge,
+                    Err(code) => return Ok(code),
+                };
+
+                let outcome = state.handle.block_on(ledger::transfer(
+                    &state.app_state,
+                    &state.user_id,
+                    &account_id,
+                    &peer_ua_addr,
+                    &peer_account_id,
+                    amount,
+                    None,
+                ));
+
+                Ok(record(&mut caller, outcome, charge, |peer_asset_ids| {
+                    Operation::Transfer {
+                        account_id,
+                        peer_ua_addr,
+                        peer_account_id,
+                        amount,
+                        peer_asset_ids,
+                    }
+                }))
+            },
+        )
+        .change_context(RuntimeError::InstantiationFailed)?;
+
+    Ok(linker)
+}
+
+/// Resolves the reference into an account id, paths must lead to an account of the invoking user.
+fn own_account(state: &HostState, reference: String) -> Result<String, i32> {
+    if !reference.starts_with('/') {
+        return Ok(reference);
+    }
+
+    match state
+        .handle
+        .block_on(namespace::resolve_account(&state.app_state, &reference))
+    {
+        Ok(resolved) if resolved.user_id == state.user_id => Ok(resolved.account_id),
+        Ok(_) => Err(status::NOT_FOUND),
+        Err(error) => Err(namespace_status(error.current_context())),
+    }
+}
+
+/// Resolves the peer into its unique address and account id.
+fn peer_account(
+    state: &HostState,
+    peer_ua_addr: String,
+    peer_account_id: String,
+) -> Result<(String, String), i32> {
+    if !peer_ua_addr.starts_with('/') {
+        return Ok((peer_ua_addr, peer_account_id));
+    }
+
+    state
+        .handle
+        .block_on(namespace::resolve_account(&state.app_state, &peer_ua_addr))
+        .map(|resolved| (resolved.ua_addr, resolved.account_id))
+        .map_err(|error| namespace_status(error.current_context()))
+}
+
+fn check_deadline(caller: &Caller<'_, HostState>) -> Result<(), wasmi::Error> {
+    if Instant::now() > caller.data().deadline {
+        return Err(wasmi::Error::new("workflow exceeded its time limit"));
+    }
+
+    Ok(())
+}
+
+fn read_str(caller: &Caller<'_, HostState>, ptr: i32, len: i32) -> Option<String> {
+    let ptr = usize::try_from(ptr).ok()?;
+    let len = usize::try_from(len)
+        .ok()
+        .filter(|len| *len <= MAX_STRING_LEN)?;
+
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+
+    let mut buffer = vec![0; len];
+    memory.read(caller, ptr, &mut buffer).ok()?;
+
+    String::from_utf8(buffer).ok()
+}
+
+/// Runs the access check the matching HTTP verb runs, returning what was charged to the grant of
+/// the caller.
+fn authorize(
+    state: &HostState,
+    account_id: &str,
+    permission: Permission,
+    amount: u64,
+) -> Result<Option<GrantCharge>, i32> {
+    state
+        .handle
+        .block_on(acl::check(
+            &state.app_state,
+            &state.principal,
+            &state.user_id,
+            account_id,
+            None,
+            permission,
+            amount,
+        ))
+        .map_err(|error| match error.current_context() {
+            AclError::NotFound => status::NOT_FOUND,
+            AclError::Denied => status::FORBIDDEN,
+            AclError::LimitExceeded => status::LIMIT_EXCEEDED,
+            AclError::StorageError => status::FAILED,
+        })
+}
+
+/// Records the committed operation, or gives back the charge when it failed.
+fn record<T>(
+    caller: &mut Caller<'_, HostState>,
+    outcome: SResult<T, LedgerError>,
+    charge: Option<GrantCharge>,
+    operation: impl FnOnce(T) -> Operation,
+) -> i32 {
+    match outcome {
+        Ok(value) => {
+            caller.data_mut().operations.push(operation(value));
+            status::OK
+        }
+        Err(error) => {
+            let state = caller.data();
+
+            match state.handle.block_on(acl::refund(&state.app_state, charge)) {
+                Ok(()) => status_of(error.current_context()),
+                Err(_) => status::FAILED,
+            }
+        }
+    }
+}
+
+fn namespace_status(error: &NamespaceError) -> i32 {
+    match error {
+        NamespaceError::InvalidPath | NamespaceError::NotAnAccount => status::INVALID_ARGUMENT,
+        NamespaceError::NotMounted
+        | NamespaceError::UserNotFound
+        | NamespaceError::AccountNotFound
+        | NamespaceError::AmbiguousAccount => status::NOT_FOUND,
+        NamespaceError::StorageError => status::FAILED,
+    }
+}
+
+fn status_of(error: &LedgerError) -> i32 {
+    match error {
+        LedgerError::InvalidAmount => status::INVALID_ARGUMENT,
+        LedgerError::AccountNotFound | LedgerError::AssetNotFound => status::NOT_FOUND,
+        LedgerError::InsufficientFunds => status::INSUFFICIENT_FUNDS,
+        LedgerError::AccountLocked | LedgerError::AssetLocked => status::LOCKED,
+        LedgerError::PolicyRejected(_)
+        | LedgerError::TokenManagerDeactivated
+        | LedgerError::ApprovalRequired => status::REJECTED,
+        LedgerError::StorageError => status::FAILED,
+    }
+}
@@ -0,0 +1,288 @@
+//! Saga engine running multi-step ledger flows.
+//!
+//! Instances are advanced by the worker one step at a time, with progress persisted after every
+//! step. When a step fails, or cancellation is requested, the steps completed so far are undone
+//! in reverse order by applying their inverse.
+
+use std::time::Duration;
+
+use error_stack::ResultExt;
+use nanoid::nanoid;
+
+use crate::error::{LedgerError, SResult, StorageError};
+use crate::ledger;
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{
+    AccountRef, SagaDefinition, SagaInstance, SagaStatus, SagaStep, StepState, StepStatus,
+};
+use crate::utils::unix_timestamp;
+
+/// How long a step or a request may hold on to an instance.
+const GUARD_SECS: u64 = 30;
+
+pub fn new_instance(saga_id: String, saga: &SagaDefinition) -> SagaInstance {
+    let now = unix_timestamp();
+
+    SagaInstance {
+        saga_id,
+        status: SagaStatus::Running,
+        steps: vec![StepState::default(); saga.steps.len()],
+        cancel_requested: false,
+        attempts: 1,
+        last_error: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+pub async fn worker(app_state: AppState) {
+    let settings = app_state.config.sagas.clone();
+
+    let mut interval =
+        tokio::time::interval(Duration::from_millis(settings.poll_interval_ms.max(10)));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = process_active(&app_state, settings.batch_size).await {
+            error!(?error, "Failed while advancing saga instances");
+        }
+    }
+}
+
+async fn process_active(app_state: &AppState, limit: usize) -> SResult<(), StorageError> {
+    let saga_interface = app_state.storage.get_saga_interface().await?;
+
+    for (instance_id, instance) in saga_interface.active_instances(limit).await? {
+        let saga = saga_interface.get_saga(&instance.saga_id).await?;
+
+        advance(app_state, &instance_id, &saga).await?;
+    }
+
+    Ok(())
+}
+
+/// Drives the instance until it settles, persisting it after every step. The instance is taken
+/// for the duration of each step, so that requests made through the api in between, such as a
+/// cancellation, are neither lost nor overwritten.
+async fn advance(
+    app_state: &AppState,
+    instance_id: &str,
+    saga: &SagaDefinition,
+) -> SResult<(), StorageError> {
+    loop {
+        // a request holds the instance, it's picked up again on the next poll
+        let Ok(guard) = enter(app_state, instance_id).await else {
+            return Ok(());
+        };
+
+        let output = step(app_state, instance_id, saga).await;
+        leave(app_state, instance_id, &guard).await?;
+
+        if !output? {
+            return Ok(());
+        }
+    }
+}
+
+/// Moves the instance by a single step, returning whether it had not settled yet.
+async fn step(
+    app_state: &AppState,
+    instance_id: &str,
+    saga: &SagaDefinition,
+) -> SResult<bool, StorageError> {
+    let saga_interface = app_state.storage.get_saga_interface().await?;
+    let mut instance = saga_interface.get_instance(instance_id).await?;
+
+    match instance.status {
+        SagaStatus::Running => {
+            if instance.cancel_requested {
+                instance.status = SagaStatus::Compensating;
+            } else {
+                match instance
+                    .steps
+                    .iter()
+                    .position(|step| step.status == StepStatus::Pending)
+                {
+                    None => instance.status = SagaStatus::Completed,
+                    Some(index) => {
+                        let state = &mut instance.steps[index];
+
+                        match execute(app_state, &saga.steps[index]).await {
+                            Ok(asset_ids) => {
+                                state.status = StepStatus::Completed;
+                                state.asset_ids = asset_ids;
+                            }
+                            Err(error) => {
+                                let message = error.current_context().to_string();
+
+                                state.status = StepStatus::Failed;
+                                state.error = Some(message.clone());
+                                instance.last_error = Some(message);
+                                instance.status = SagaStatus::Compensating;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        SagaStatus::Compensating => {
+            match instance
+                .steps
+                .iter()
+                .rposition(|step| step.status == StepStatus::Completed)
+            {
+                None => {
+                    instance.status = if instance.cancel_requested {
+                        SagaStatus::Cancelled
+                    } else {
+                        SagaStatus::Compensated
+                    };
+                }
+                Some(index) => {
+                    let state = &mut instance.steps[index];
+
+                    match compensate(app_state, &saga.steps[index]).await {
+                        Ok(()) => state.status = StepStatus::Compensated,
+                        Err(error) => {
+                            let message = format!(
+                                "compensation of step {index} failed: {}",
+                                error.current_context()
+                            );
+
+                            state.error = Some(message.clone());
+                            instance.last_error = Some(message);
+                            instance.status = SagaStatus::Failed;
+                        }
+                    }
+                }
+            }
+        }
+        SagaStatus::Completed
+        | SagaStatus::Compensated
+        | SagaStatus::Cancelled
+        | SagaStatus::Failed => return Ok(false),
+    }
+
+    instance.updated_at = unix_timestamp();
+    saga_interface
+        .update_instance(instance_id, instance)
+        .await?;
+
+    Ok(true)
+}
+
+/// Takes the instance for the duration of a step or a request, so that neither overwrites the
+/// other.
+pub async fn enter(app_state: &AppState, instance_id: &str) -> SResult<String, StorageError> {
+    let guard = nanoid!();
+
+    app_state
+        .storage
+        .get_lock_interface()
+        .await?
+        .acquire(
+            &format!("saga_instances/{instance_id}"),
+            &guard,
+            unix_timestamp() + GUARD_SECS,
+        )
+        .await?;
+
+    Ok(guard)
+}
+
+pub async fn leave(
+    app_state: &AppState,
+    instance_id: &str,
+    guard: &str,
+) -> SResult<(), StorageError> {
+    app_state
+        .storage
+        .get_lock_interface()
+        .await?
+        .release(&format!("saga_instances/{instance_id}"), guard)
+        .await
+}
+
+/// Applies the step, returning the ids of the assets it created.
+async fn execute(app_state: &AppState, step: &SagaStep) -> SResult<Vec<String>, LedgerError> {
+    match step {
+        SagaStep::Debit { account, amount } => {
+            let (user_id, account_id) = resolve(app_state, account).await?;
+
+            ledger::debit(app_state, &user_id, &account_id, *amount, None).await?;
+
+            Ok(Vec::new())
+        }
+        SagaStep::Credit { account, amount } => {
+            let (user_id, account_id) = resolve(app_state, account).await?;
+
+            let asset_id = ledger::credit(app_state, &user_id, &account_id, *amount).await?;
+
+            Ok(vec![asset_id])
+        }
+        SagaStep::Transfer { from, to, amount } => transfer(app_state, from, to, *amount).await,
+    }
+}
+
+/// Applies the inverse of the step.
+async fn compensate(app_state: &AppState, step: &SagaStep) -> SResult<(), LedgerError> {
+    match step {
+        SagaStep::Debit { account, amount } => {
+            let (user_id, account_id) = resolve(app_state, account).await?;
+
+            ledger::restore(app_state, &user_id, &account_id, *amount).await?;
+        }
+        SagaStep::Credit { account, amount } => {
+            let (user_id, account_id) = resolve(app_state, account).await?;
+
+            ledger::debit(app_state, &user_id, &account_id, *amount, None).await?;
+        }
+        SagaStep::Transfer { from, to, amount } => {
+            transfer(app_state, to, from, *amount).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn transfer(
+    app_state: &AppState,
+    from: &AccountRef,
+    to: &AccountRef,
+    amount: u64,
+) -> SResult<Vec<String>, LedgerError> {
+    let (user_id, account_id) = resolve(app_state, from).await?;
+    let (peer_user_id, peer_account_id) = resolve(app_state, to).await?;
+
+    let peer = app_state
+        .storage
+        .get_user_interface()
+        .await
+        .change_context(LedgerError::StorageError)?
+        .get_user(&peer_user_id)
+        .await
+        .change_context(LedgerError::AccountNotFound)?;
+
+    ledger::transfer(
+        app_state,
+        &user_id,
+        &account_id,
+        &peer.ua_addr,
+        &peer_account_id,
+        amount,
+        None,
+    )
+    .await
+}
+
+async fn resolve(
+    app_state: &AppState,
+    account: &AccountRef,
+) -> SResult<(String, String), LedgerError> {
+    account
+        .resolve(app_state)
+        .await
+        .change_context(LedgerError::AccountNotFound)
+}
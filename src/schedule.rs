@@ -0,0 +1,176 @@
+//! Scheduler executing standing transfer instructions.
+//!
+//! Due schedules are picked up by the worker and executed through the regular transfer path. A
+//! failed run is recorded in the history of the schedule and doesn't stop later occurrences;
+//! occurrences missed while the server was down are skipped rather than run back to back.
+
+use std::time::Duration;
+
+use nanoid::nanoid;
+
+use crate::error::{SResult, StorageError};
+use crate::ledger;
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{Schedule, ScheduleRun, ScheduleStatus};
+use crate::utils::unix_timestamp;
+
+/// How long a run or a change may hold on to a schedule.
+const GUARD_SECS: u64 = 30;
+
+pub async fn worker(app_state: AppState) {
+    let settings = app_state.config.schedules.clone();
+
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(settings.poll_interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = process_due(&app_state, settings.batch_size).await {
+            error!(?error, "Failed while running due schedules");
+        }
+    }
+}
+
+async fn process_due(app_state: &AppState, limit: usize) -> SResult<(), StorageError> {
+    let schedule_interface = app_state.storage.get_schedule_interface().await?;
+
+    for (schedule_id, _) in schedule_interface
+        .due_schedules(unix_timestamp(), limit)
+        .await?
+    {
+        // the schedule is being changed, it's picked up again on the next poll
+        let Ok(guard) = enter(app_state, &schedule_id).await else {
+            continue;
+        };
+
+        let output = run_due(app_state, &schedule_id).await;
+        leave(app_state, &schedule_id, &guard).await?;
+
+        if let Err(error) = output {
+            warn!(
+                ?error,
+                schedule_id, "Failed while recording the schedule run"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the schedule unless it was paused, moved or deleted since it was found to be due.
+async fn run_due(app_state: &AppState, schedule_id: &str) -> SResult<(), StorageError> {
+    let schedule_interface = app_state.storage.get_schedule_interface().await?;
+    let schedule = schedule_interface.get_schedule(schedule_id).await?;
+
+    let due = schedule.status == ScheduleStatus::Active
+        && schedule
+            .next_run_at
+            .is_some_and(|at| at <= unix_timestamp());
+
+    if !due {
+        return Ok(());
+    }
+
+    let schedule = run(app_state, schedule).await;
+
+    schedule_interface
+        .update_schedule(schedule_id, schedule)
+        .await
+}
+
+/// Takes the schedule for the duration of a run or a change, so that neither overwrites the
+/// other.
+pub async fn enter(app_state: &AppState, schedule_id: &str) -> SResult<String, StorageError> {
+    let guard = nanoid!();
+
+    app_state
+        .storage
+        .get_lock_interface()
+        .await?
+        .acquire(
+            &format!("schedules/{schedule_id}"),
+            &guard,
+            unix_timestamp() + GUARD_SECS,
+        )
+        .await?;
+
+    Ok(guard)
+}
+
+pub async fn leave(
+    app_state: &AppState,
+    schedule_id: &str,
+    guard: &str,
+) -> SResult<(), StorageError> {
+    app_state
+        .storage
+        .get_lock_interface()
+        .await?
+        .release(&format!("schedules/{schedule_id}"), guard)
+        .await
+}
+
+/// Executes the transfer of the schedule and moves it to its next occurrence.
+async fn run(app_state: &AppState, mut schedule: Schedule) -> Schedule {
+    let now = unix_timestamp();
+
+    let result = ledger::transfer(
+        app_state,
+        &schedule.user_id,
+        &schedule.account_id,
+        &schedule.peer_ua_addr,
+        &schedule.peer_account_id,
+        schedule.amount,
+        None,
+    )
+    .await;
+
+    let (peer_asset_ids, error) = match result {
+        Ok(peer_asset_ids) => (peer_asset_ids, None),
+        Err(error) => {
+            warn!(?error, "Scheduled transfer failed");
+            schedule.failures += 1;
+
+            (Vec::new(), Some(error.current_context().to_string()))
+        }
+    };
+
+    schedule.history.push(ScheduleRun {
+        scheduled_for: schedule.next_run_at.unwrap_or(now),
+        executed_at: now,
+        peer_asset_ids,
+        error,
+    });
+
+    let excess = schedule
+        .history
+        .len()
+        .saturating_sub(app_state.config.schedules.history_limit);
+    schedule.history.drain(..excess);
+
+    advance(&mut schedule, now);
+    schedule.updated_at = now;
+
+    schedule
+}
+
+/// Moves the schedule to its first occurrence after `now`, completing it when there is none.
+pub fn advance(schedule: &mut Schedule, now: u64) {
+    loop {
+        schedule.occurrence += 1;
+        schedule.next_run_at = schedule
+            .cadence
+            .occurrence(schedule.start_at, schedule.occurrence);
+
+        match schedule.next_run_at {
+            None => {
+                schedule.status = ScheduleStatus::Completed;
+                break;
+            }
+            Some(at) if at > now => break,
+            Some(_) => {}
+        }
+    }
+}
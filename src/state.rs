@@ -1,9 +1,18 @@
-use crate::storage::StorageInterface;
+use crate::audit::AuditStore;
+use crate::auth::SessionKey;
+use crate::error::{ConfigurationError, SResult};
+use crate::merkle::MerklePublisher;
+use crate::proofs::ProofStore;
+use crate::storage::{AuditInterface, ProofInterface, StorageInterface};
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: crate::config::Config,
     pub storage: Box<dyn StorageInterface + Send + Sync>,
+    pub proofs: Box<dyn ProofInterface + Send + Sync>,
+    pub audit: Box<dyn AuditInterface + Send + Sync>,
+    pub merkle: MerklePublisher,
+    pub session_key: SessionKey,
 }
 
 impl AppState {
@@ -11,10 +20,39 @@ impl AppState {
     //     Self { config, storage }
     // }
 
-    pub fn imc_backed(config: crate::config::Config) -> Self {
-        Self {
+    pub fn imc_backed(config: crate::config::Config) -> SResult<Self, ConfigurationError> {
+        let proofs = match &config.proofs.path {
+            Some(path) => ProofStore::open(path)?,
+            None => ProofStore::in_memory(),
+        };
+
+        let audit = match &config.audit.path {
+            Some(path) => AuditStore::open(path)?,
+            None => AuditStore::in_memory(),
+        };
+
+        let merkle = MerklePublisher::new(&config.merkle)?;
+        let session_key = SessionKey::new(&config.auth)?;
+
+        Ok(Self {
             config,
             storage: Box::new(crate::imc::Storage::new()),
-        }
+            proofs: Box::new(proofs),
+            audit: Box::new(audit),
+            merkle,
+            session_key,
+        })
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    /// In-memory state with the configuration of the development environment, for tests going
+    /// through the ledger and the storage behind it.
+    #[allow(clippy::expect_used)]
+    pub fn for_tests() -> Self {
+        let config = crate::config::Config::new().expect("development config should load");
+
+        Self::imc_backed(config).expect("in-memory state should build")
     }
 }
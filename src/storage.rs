@@ -2,7 +2,16 @@ use dyn_clone::DynClone;
 
 use crate::error::{SResult, StorageError};
 
-use self::types::{Account, TokenManager, TokenManagerInfo, TotalAssets, User};
+use self::types::{
+    AccessGrant, Account, Acl, Approval, AssetInfo, AuditEntry, AuditRecord, Challenge, Delivery,
+    Descriptor, Event, Lock, Mode, Mount, Multisig, Policy, PolicyUsage, ProofEntry, RoleGrant,
+    SagaDefinition, SagaInstance, Schedule, Session, Swap, SyncPayload, SyncRecord, SyncStatus,
+    TokenManager, TokenManagerInfo, TotalAssets, User, Webhook, Workflow,
+};
+use self::types::{
+    AccountFilter, AccountSort, AssetFilter, AssetSort, Entry, Page, PageQuery,
+    SupportedAssetFilter, SupportedAssetSort, TokenManagerFilter, TokenManagerSort,
+};
 
 pub mod types;
 
@@ -14,6 +23,50 @@ pub trait StorageInterface: DynClone {
     async fn get_token_manager_interface(
         &self,
     ) -> SResult<Box<dyn TokenManagerInterface + Send + Sync>, StorageError>;
+    async fn get_webhook_interface(
+        &self,
+    ) -> SResult<Box<dyn WebhookInterface + Send + Sync>, StorageError>;
+    async fn get_sync_interface(
+        &self,
+    ) -> SResult<Box<dyn SyncInterface + Send + Sync>, StorageError>;
+    async fn get_workflow_interface(
+        &self,
+    ) -> SResult<Box<dyn WorkflowInterface + Send + Sync>, StorageError>;
+    async fn get_descriptor_interface(
+        &self,
+    ) -> SResult<Box<dyn DescriptorInterface + Send + Sync>, StorageError>;
+    async fn get_lock_interface(
+        &self,
+    ) -> SResult<Box<dyn LockInterface + Send + Sync>, StorageError>;
+    async fn get_mount_interface(
+        &self,
+    ) -> SResult<Box<dyn MountInterface + Send + Sync>, StorageError>;
+    async fn get_saga_interface(
+        &self,
+    ) -> SResult<Box<dyn SagaInterface + Send + Sync>, StorageError>;
+    async fn get_swap_interface(
+        &self,
+    ) -> SResult<Box<dyn SwapInterface + Send + Sync>, StorageError>;
+    async fn get_schedule_interface(
+        &self,
+    ) -> SResult<Box<dyn ScheduleInterface + Send + Sync>, StorageError>;
+    async fn get_nonce_interface(
+        &self,
+    ) -> SResult<Box<dyn NonceInterface + Send + Sync>, StorageError>;
+    async fn get_session_interface(
+        &self,
+    ) -> SResult<Box<dyn SessionInterface + Send + Sync>, StorageError>;
+    async fn get_role_interface(
+        &self,
+    ) -> SResult<Box<dyn RoleInterface + Send + Sync>, StorageError>;
+    async fn get_acl_interface(&self)
+        -> SResult<Box<dyn AclInterface + Send + Sync>, StorageError>;
+    async fn get_policy_interface(
+        &self,
+    ) -> SResult<Box<dyn PolicyInterface + Send + Sync>, StorageError>;
+    async fn get_multisig_interface(
+        &self,
+    ) -> SResult<Box<dyn MultisigInterface + Send + Sync>, StorageError>;
 }
 
 #[async_trait::async_trait]
@@ -23,6 +76,15 @@ pub trait UserInterface {
 
     async fn get_user(&self, user_id: &str) -> SResult<User, StorageError>;
 
+    async fn list_users(&self) -> SResult<Vec<(String, User)>, StorageError>;
+
+    /// Replaces the name, email and keys of the user, the unique address never changes.
+    async fn update_user(&self, user_id: &str, user: User) -> SResult<(), StorageError>;
+
+    /// Marks the user closed. The user stays behind as a tombstone, keeping the unique address
+    /// taken so that it never points to someone else.
+    async fn close_user(&self, user_id: &str, closed_at: u64) -> SResult<(), StorageError>;
+
     async fn get_account_interface(
         &self,
         user_id: &str,
@@ -34,6 +96,8 @@ pub trait UserInterface {
     ) -> SResult<Box<dyn AccountInterface + Send + Sync>, StorageError>;
 
     async fn is_valid_ua_addr(&self, ua_addr: &str) -> SResult<bool, StorageError>;
+
+    async fn get_user_id_by_ua(&self, ua_addr: &str) -> SResult<String, StorageError>;
 }
 
 #[async_trait::async_trait]
@@ -51,6 +115,27 @@ pub trait TokenManagerInterface {
 
     async fn list_token_manager(&self) -> SResult<Vec<TokenManagerInfo>, StorageError>;
 
+    /// Replaces the name, keys and sync endpoint of the token manager.
+    async fn update_token_manager(
+        &self,
+        token_manager_id: &str,
+        token_manager: TokenManager,
+    ) -> SResult<(), StorageError>;
+
+    /// Marks the token manager deactivated, it stays behind along with its supported assets.
+    async fn deactivate_token_manager(
+        &self,
+        token_manager_id: &str,
+        deactivated_at: u64,
+    ) -> SResult<(), StorageError>;
+
+    /// Page of the token managers matching the filter.
+    async fn query_token_managers(
+        &self,
+        query: &PageQuery<TokenManagerSort>,
+        filter: &TokenManagerFilter,
+    ) -> SResult<Page<TokenManager>, StorageError>;
+
     async fn get_supported_asset_interface(
         &self,
         token_manager_id: &str,
@@ -64,12 +149,37 @@ pub trait SupportedAssetInterface {
         asset: types::SupportedAsset,
     ) -> SResult<String, StorageError>;
 
+    /// Supported asset along with its id and creation time.
     async fn get_supported_asset(
         &self,
         supported_asset_id: &str,
-    ) -> SResult<types::SupportedAsset, StorageError>;
+    ) -> SResult<Entry<types::SupportedAsset>, StorageError>;
+
+    /// Replaces the contract references and custody of the supported asset, its asset type never
+    /// changes as accounts are opened for it.
+    async fn update_supported_asset(
+        &self,
+        supported_asset_id: &str,
+        asset: types::SupportedAsset,
+    ) -> SResult<(), StorageError>;
+
+    /// Marks the supported asset deprecated.
+    async fn deprecate_supported_asset(
+        &self,
+        supported_asset_id: &str,
+        deprecated_at: u64,
+    ) -> SResult<(), StorageError>;
+
+    async fn list_supported_assets(
+        &self,
+    ) -> SResult<Vec<(String, types::SupportedAsset)>, StorageError>;
 
-    async fn list_supported_assets(&self) -> SResult<Vec<types::SupportedAsset>, StorageError>;
+    /// Page of the supported assets matching the filter.
+    async fn query_supported_assets(
+        &self,
+        query: &PageQuery<SupportedAssetSort>,
+        filter: &SupportedAssetFilter,
+    ) -> SResult<Page<types::SupportedAsset>, StorageError>;
 }
 
 #[async_trait::async_trait]
@@ -78,6 +188,22 @@ pub trait AccountInterface {
 
     async fn get_account(&self, account_id: &str) -> SResult<(Account, TotalAssets), StorageError>;
 
+    async fn list_accounts(&self) -> SResult<Vec<(String, Account)>, StorageError>;
+
+    /// Page of the accounts matching the filter.
+    async fn query_accounts(
+        &self,
+        query: &PageQuery<AccountSort>,
+        filter: &AccountFilter,
+    ) -> SResult<Page<Account>, StorageError>;
+
+    /// Takes the name and the token manager reference from `acc`. The token manager and the asset
+    /// type an account is opened with never change.
+    async fn update_account(&self, account_id: &str, acc: Account) -> SResult<(), StorageError>;
+
+    /// Removes the account, which is refused while it still holds assets.
+    async fn delete_account(&self, account_id: &str) -> SResult<Account, StorageError>;
+
     async fn get_asset_interface(
         &self,
         account_id: &str,
@@ -88,7 +214,374 @@ pub trait AccountInterface {
 pub trait AssetInterface {
     async fn create_asset(&self, asset: types::AssetInfo) -> SResult<String, StorageError>;
     async fn delete_asset(&self, asset_id: &str) -> SResult<types::AssetInfo, StorageError>;
-    async fn list_assets(&self) -> SResult<Vec<types::AssetInfo>, StorageError>;
+
+    /// Puts back an asset under the id it was deleted with, when undoing the deletion.
+    async fn restore_asset(
+        &self,
+        asset_id: &str,
+        asset: types::AssetInfo,
+    ) -> SResult<(), StorageError>;
+    async fn list_assets(&self) -> SResult<Vec<(String, AssetInfo)>, StorageError>;
+
+    /// Page of the assets matching the filter.
+    async fn query_assets(
+        &self,
+        query: &PageQuery<AssetSort>,
+        filter: &AssetFilter,
+    ) -> SResult<Page<AssetInfo>, StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait WebhookInterface {
+    // Webhook -> webhook_id
+    async fn create_webhook(&self, webhook: Webhook) -> SResult<String, StorageError>;
+
+    async fn get_webhook(
+        &self,
+        token_manager_id: &str,
+        webhook_id: &str,
+    ) -> SResult<Webhook, StorageError>;
+
+    async fn list_webhooks(
+        &self,
+        token_manager_id: &str,
+    ) -> SResult<Vec<(String, Webhook)>, StorageError>;
+
+    async fn delete_webhook(
+        &self,
+        token_manager_id: &str,
+        webhook_id: &str,
+    ) -> SResult<Webhook, StorageError>;
+
+    // Delivery -> delivery_id
+    async fn enqueue_delivery(&self, delivery: Delivery) -> SResult<String, StorageError>;
+
+    async fn get_delivery(&self, delivery_id: &str) -> SResult<Delivery, StorageError>;
+
+    async fn list_deliveries(&self, webhook_id: &str) -> SResult<Vec<Delivery>, StorageError>;
+
+    /// Pending deliveries whose next attempt is due at or before `now`, oldest first.
+    async fn due_deliveries(&self, now: u64, limit: usize) -> SResult<Vec<Delivery>, StorageError>;
+
+    async fn update_delivery(&self, delivery: Delivery) -> SResult<(), StorageError>;
+}
+
+/// Ordered outbox of changes to custodial assets, kept per token manager.
+#[async_trait::async_trait]
+pub trait SyncInterface {
+    // SyncPayload -> sequence
+    async fn append(
+        &self,
+        token_manager_id: &str,
+        payload: SyncPayload,
+    ) -> SResult<u64, StorageError>;
+
+    /// Records that are yet to be acknowledged, in sequence order.
+    async fn pending(
+        &self,
+        token_manager_id: &str,
+        limit: usize,
+    ) -> SResult<Vec<SyncRecord>, StorageError>;
+
+    /// Marks every record up to and including `sequence` as delivered.
+    async fn acknowledge(&self, token_manager_id: &str, sequence: u64)
+        -> SResult<(), StorageError>;
+
+    async fn pending_token_managers(&self) -> SResult<Vec<String>, StorageError>;
+
+    async fn status(&self, token_manager_id: &str) -> SResult<SyncStatus, StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait WorkflowInterface {
+    // Workflow -> workflow_id
+    async fn create_workflow(&self, workflow: Workflow) -> SResult<String, StorageError>;
+
+    async fn get_workflow(&self, workflow_id: &str) -> SResult<Workflow, StorageError>;
+
+    async fn list_workflows(&self) -> SResult<Vec<(String, Workflow)>, StorageError>;
+}
+
+/// Purpose-bound handles handed out by `intend`.
+#[async_trait::async_trait]
+pub trait DescriptorInterface {
+    // Descriptor -> descriptor_id
+    async fn create_descriptor(&self, descriptor: Descriptor) -> SResult<String, StorageError>;
+
+    async fn get_descriptor(&self, descriptor_id: &str) -> SResult<Descriptor, StorageError>;
+
+    async fn delete_descriptor(&self, descriptor_id: &str) -> SResult<Descriptor, StorageError>;
+
+    async fn list_descriptors(&self) -> SResult<Vec<(String, Descriptor)>, StorageError>;
+}
+
+/// Exclusive locks on ledger resources, a lock lapses on its own once it expires.
+#[async_trait::async_trait]
+pub trait LockInterface {
+    /// Takes the lock, or extends it when it is already held by `holder`.
+    async fn acquire(
+        &self,
+        resource: &str,
+        holder: &str,
+        expires_at: u64,
+    ) -> SResult<(), StorageError>;
+
+    /// Releases the lock if it is held by `holder`.
+    async fn release(&self, resource: &str, holder: &str) -> SResult<(), StorageError>;
+
+    /// The lock currently held on the resource, if it hasn't expired.
+    async fn get_lock(&self, resource: &str) -> SResult<Option<Lock>, StorageError>;
+}
+
+/// Mount table of the namespace, keyed by the normalized path prefix.
+#[async_trait::async_trait]
+pub trait MountInterface {
+    async fn mount(&self, path: &str, mount: Mount) -> SResult<(), StorageError>;
+
+    async fn unmount(&self, path: &str) -> SResult<Mount, StorageError>;
+
+    async fn list_mounts(&self) -> SResult<Vec<(String, Mount)>, StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait SagaInterface {
+    // SagaDefinition -> saga_id
+    async fn create_saga(&self, saga: SagaDefinition) -> SResult<String, StorageError>;
+
+    async fn get_saga(&self, saga_id: &str) -> SResult<SagaDefinition, StorageError>;
+
+    async fn list_sagas(&self) -> SResult<Vec<(String, SagaDefinition)>, StorageError>;
+
+    // SagaInstance -> instance_id
+    async fn create_instance(&self, instance: SagaInstance) -> SResult<String, StorageError>;
+
+    async fn get_instance(&self, instance_id: &str) -> SResult<SagaInstance, StorageError>;
+
+    async fn list_instances(
+        &self,
+        saga_id: &str,
+    ) -> SResult<Vec<(String, SagaInstance)>, StorageError>;
+
+    async fn update_instance(
+        &self,
+        instance_id: &str,
+        instance: SagaInstance,
+    ) -> SResult<(), StorageError>;
+
+    /// Instances that are running or compensating, oldest first.
+    async fn active_instances(
+        &self,
+        limit: usize,
+    ) -> SResult<Vec<(String, SagaInstance)>, StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait SwapInterface {
+    // Swap -> swap_id
+    async fn create_swap(&self, swap: Swap) -> SResult<String, StorageError>;
+
+    async fn get_swap(&self, swap_id: &str) -> SResult<Swap, StorageError>;
+
+    async fn list_swaps(&self) -> SResult<Vec<(String, Swap)>, StorageError>;
+
+    async fn update_swap(&self, swap_id: &str, swap: Swap) -> SResult<(), StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait ScheduleInterface {
+    // Schedule -> schedule_id
+    async fn create_schedule(&self, schedule: Schedule) -> SResult<String, StorageError>;
+
+    async fn get_schedule(&self, schedule_id: &str) -> SResult<Schedule, StorageError>;
+
+    async fn list_schedules(&self, user_id: &str)
+        -> SResult<Vec<(String, Schedule)>, StorageError>;
+
+    async fn update_schedule(
+        &self,
+        schedule_id: &str,
+        schedule: Schedule,
+    ) -> SResult<(), StorageError>;
+
+    async fn delete_schedule(&self, schedule_id: &str) -> SResult<Schedule, StorageError>;
+
+    /// Active schedules whose next run is at or before `now`, earliest first.
+    async fn due_schedules(
+        &self,
+        now: u64,
+        limit: usize,
+    ) -> SResult<Vec<(String, Schedule)>, StorageError>;
+}
+
+/// Nonces of signed requests, remembered until the signature would be stale anyway.
+#[async_trait::async_trait]
+pub trait NonceInterface {
+    /// Records the nonce, failing when it was already used and hasn't expired yet.
+    async fn consume(&self, nonce: &str, expires_at: u64) -> SResult<(), StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait SessionInterface {
+    // Challenge -> challenge_id
+    async fn create_challenge(&self, challenge: Challenge) -> SResult<String, StorageError>;
+
+    /// Removes the challenge, so that every challenge is answered at most once.
+    async fn take_challenge(&self, challenge_id: &str) -> SResult<Challenge, StorageError>;
+
+    // Session -> session_id
+    async fn create_session(&self, session: Session) -> SResult<String, StorageError>;
+
+    async fn get_session(&self, session_id: &str) -> SResult<Session, StorageError>;
+
+    async fn delete_session(&self, session_id: &str) -> SResult<Session, StorageError>;
+
+    /// Ends every session of the subject.
+    async fn revoke_sessions(&self, subject: &str) -> SResult<(), StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait RoleInterface {
+    /// RoleGrant -> grant_id, granting a role the subject already holds returns the existing grant.
+    async fn grant_role(&self, grant: RoleGrant) -> SResult<String, StorageError>;
+
+    async fn revoke_role(&self, grant_id: &str) -> SResult<RoleGrant, StorageError>;
+
+    /// Grants held by the subject, or every grant when no subject is given.
+    async fn list_roles(
+        &self,
+        subject: Option<&str>,
+    ) -> SResult<Vec<(String, RoleGrant)>, StorageError>;
+}
+
+/// Immutable Proof Store, an append-only log where every entry commits to the one before it.
+#[async_trait::async_trait]
+pub trait ProofInterface: DynClone {
+    async fn append(&self, event: Event) -> SResult<ProofEntry, StorageError>;
+
+    async fn get_proof(&self, transaction_id: &str) -> SResult<ProofEntry, StorageError>;
+
+    /// Every entry of the chain, starting from genesis.
+    async fn list_proofs(&self) -> SResult<Vec<ProofEntry>, StorageError>;
+
+    /// Index of the first entry recorded by this process. Entries before it were loaded from an
+    /// earlier run, whose ledger state is not kept across restarts.
+    fn session_start(&self) -> u64;
+}
+
+/// Audit trail of mutating requests, chained the same way as the proof store.
+#[async_trait::async_trait]
+pub trait AuditInterface: DynClone {
+    async fn append(&self, entry: AuditEntry) -> SResult<AuditRecord, StorageError>;
+
+    /// Every record of the trail, starting from genesis.
+    async fn list_records(&self) -> SResult<Vec<AuditRecord>, StorageError>;
 }
 
 dyn_clone::clone_trait_object!(StorageInterface);
+dyn_clone::clone_trait_object!(ProofInterface);
+dyn_clone::clone_trait_object!(AuditInterface);
+
+/// Permissions of accounts and assets, keyed by the resource they protect.
+#[async_trait::async_trait]
+pub trait AclInterface {
+    async fn get_acl(&self, resource: &str) -> SResult<Acl, StorageError>;
+
+    /// Sets the mode, creating the ACL without any grants when there is none yet.
+    async fn set_mode(&self, resource: &str, mode: Mode) -> SResult<Acl, StorageError>;
+
+    /// Grants to the UA address, replacing its previous grant. `mode` is used when the resource has
+    /// no ACL yet.
+    async fn put_grant(
+        &self,
+        resource: &str,
+        mode: Mode,
+        ua_addr: &str,
+        grant: AccessGrant,
+    ) -> SResult<Acl, StorageError>;
+
+    async fn delete_grant(
+        &self,
+        resource: &str,
+        ua_addr: &str,
+    ) -> SResult<AccessGrant, StorageError>;
+
+    /// Drops the ACL of the resource along with its grants, if it has one.
+    async fn delete_acl(&self, resource: &str) -> SResult<(), StorageError>;
+
+    /// Counts `amount` against the debit limit of the grant, failing when it would be exceeded.
+    async fn charge_grant(
+        &self,
+        resource: &str,
+        ua_addr: &str,
+        amount: u64,
+    ) -> SResult<(), StorageError>;
+
+    /// Gives back an amount that was charged for an operation which didn't go through.
+    async fn refund_grant(
+        &self,
+        resource: &str,
+        ua_addr: &str,
+        amount: u64,
+    ) -> SResult<(), StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait PolicyInterface {
+    // Policy -> policy_id
+    async fn create_policy(&self, policy: Policy) -> SResult<String, StorageError>;
+
+    async fn get_policy(&self, policy_id: &str) -> SResult<Policy, StorageError>;
+
+    async fn list_policies(&self) -> SResult<Vec<(String, Policy)>, StorageError>;
+
+    async fn delete_policy(&self, policy_id: &str) -> SResult<Policy, StorageError>;
+
+    /// Records an operation counted by the policy on the resource, forgetting those before
+    /// `keep_since`.
+    async fn record_usage(
+        &self,
+        policy_id: &str,
+        resource: &str,
+        usage: PolicyUsage,
+        keep_since: u64,
+    ) -> SResult<(), StorageError>;
+
+    /// Drops an operation that was counted when it was admitted but didn't go through.
+    async fn release_usage(
+        &self,
+        policy_id: &str,
+        resource: &str,
+        usage_id: &str,
+    ) -> SResult<(), StorageError>;
+
+    /// Operations counted by the policy on the resource since `since`.
+    async fn usage(
+        &self,
+        policy_id: &str,
+        resource: &str,
+        since: u64,
+    ) -> SResult<Vec<PolicyUsage>, StorageError>;
+}
+
+#[async_trait::async_trait]
+pub trait MultisigInterface {
+    /// Signers of the account, `None` when transfers out of it need no approval.
+    async fn get_multisig(&self, resource: &str) -> SResult<Option<Multisig>, StorageError>;
+
+    async fn set_multisig(&self, resource: &str, multisig: Multisig) -> SResult<(), StorageError>;
+
+    async fn delete_multisig(&self, resource: &str) -> SResult<Multisig, StorageError>;
+
+    // Approval -> approval_id
+    async fn create_approval(&self, approval: Approval) -> SResult<String, StorageError>;
+
+    async fn get_approval(&self, approval_id: &str) -> SResult<Approval, StorageError>;
+
+    async fn list_approvals(&self) -> SResult<Vec<(String, Approval)>, StorageError>;
+
+    async fn update_approval(
+        &self,
+        approval_id: &str,
+        approval: Approval,
+    ) -> SResult<(), StorageError>;
+}
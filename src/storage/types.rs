@@ -1,27 +1,31 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct TokenManagerRef {
     pub id: String,
     pub token_manager_name: String,
     pub internal_addr: String, // better name required
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
 pub enum AssetInfo {
     Cash { currency: Currency, amount: u64 },
     // Property {}
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema)]
 // TODO: Use macros to generate the following enums
 #[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum AssetType {
     Cash,
     // Property
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Currency {
     USD,
@@ -32,11 +36,59 @@ pub struct User {
     pub name: String,
     pub public_key: String,
     pub ua_addr: String,
+    /// Jurisdiction the user is registered in, as checked by jurisdiction policies.
+    pub jurisdiction: Option<String>,
+    /// Keys the user rotated away from, oldest first.
+    pub previous_keys: Vec<RetiredKey>,
+    pub status: UserStatus,
+    pub closed_at: Option<u64>,
+}
+
+impl User {
+    /// Key the user signed with at `at`, so that records signed before a rotation still verify.
+    pub fn key_at(&self, at: u64) -> &str {
+        self.previous_keys
+            .iter()
+            .find(|key| at < key.retired_at)
+            .map_or(&self.public_key, |key| &key.public_key)
+    }
+}
+
+/// Public key of a user or token manager that was replaced by a newer one.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct RetiredKey {
+    pub public_key: String,
+    /// Time the key was replaced, it signed until then.
+    pub retired_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    /// Closed along with all of their accounts, kept so that the unique address isn't reused.
+    Closed,
 }
 
 pub struct TokenManager {
     pub token_manager_name: String,
     pub public_key: String,
+    /// Endpoint of the token manager driver that receives custodial sync records.
+    pub sync_url: Option<String>,
+    /// Keys the token manager rotated away from, oldest first.
+    pub previous_keys: Vec<RetiredKey>,
+    pub status: TokenManagerStatus,
+    pub deactivated_at: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenManagerStatus {
+    #[default]
+    Active,
+    /// Takes no new accounts nor mints, what its accounts already hold stays readable.
+    Deactivated,
 }
 
 pub struct TokenManagerInfo {
@@ -44,7 +96,7 @@ pub struct TokenManagerInfo {
     pub token_manager_name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Account {
     pub account_name: String,
     pub token_manager_id: String,
@@ -52,12 +104,12 @@ pub struct Account {
     pub token_manager_ref: TokenManagerRef,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TotalAssets {
     pub money: Money,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Money {
     pub currency: Currency,
     pub amount: u64,
@@ -80,8 +132,841 @@ impl Default for TotalAssets {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetCustody {
+    /// The ledger is the source of truth, the token manager only observes transactions.
+    #[default]
+    Native,
+    /// The token manager is the source of truth, the ledger only facilitates transactions.
+    Proxy,
+    /// The ledger holds the asset while the token manager keeps a read-only mirror in sync.
+    Custodial,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
 pub struct SupportedAsset {
     pub asset_type: AssetType,
-    pub smart_contract_refs: Vec<u8>,
+    pub smart_contract_refs: Vec<ContractRef>,
+    #[serde(default)]
+    pub custody: AssetCustody,
+    #[serde(default)]
+    pub status: SupportedAssetStatus,
+    #[serde(default)]
+    pub deprecated_at: Option<u64>,
+}
+
+/// Smart contract implementing a supported asset on some chain or ledger.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+pub struct ContractRef {
+    /// CAIP-2 identifier of the chain or ledger, e.g. `eip155:1`.
+    pub chain_id: String,
+    /// Address of the contract on that chain, `0x` prefixed hex on EVM chains.
+    pub address: String,
+    /// ABI or interface the contract implements, e.g. `erc20` or an ERC-165 interface id.
+    pub interface_id: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportedAssetStatus {
+    #[default]
+    Active,
+    /// No new accounts are opened for the asset type, existing ones keep working.
+    Deprecated,
+}
+
+/// Direction a listing runs along its sort key.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Page of a listing to fetch: at most `limit` entries ordered by `sort`, ties broken by id,
+/// following the entry `cursor` was handed out for.
+#[derive(Clone, Debug)]
+pub struct PageQuery<S> {
+    pub cursor: Option<String>,
+    pub limit: usize,
+    pub sort: S,
+    pub order: SortOrder,
+}
+
+/// Entries of a page along with the cursor of the next one, which is missing on the last page.
+#[derive(Clone, Debug)]
+pub struct Page<T> {
+    pub items: Vec<Entry<T>>,
+    pub next_cursor: Option<String>,
+}
+
+/// Item of a listing with its id and when it was created.
+#[derive(Clone, Debug)]
+pub struct Entry<T> {
+    pub id: String,
+    pub created_at: u64,
+    pub item: T,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AccountSort {
+    #[default]
+    CreatedAt,
+    Name,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AccountFilter {
+    pub asset_type: Option<AssetType>,
+    pub token_manager_id: Option<String>,
+    pub created_after: Option<u64>,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AssetSort {
+    #[default]
+    CreatedAt,
+    Amount,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AssetFilter {
+    pub asset_type: Option<AssetType>,
+    pub currency: Option<Currency>,
+    /// Smallest amount held, inclusive.
+    pub min_amount: Option<u64>,
+    /// Largest amount held, inclusive.
+    pub max_amount: Option<u64>,
+    pub created_after: Option<u64>,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TokenManagerSort {
+    #[default]
+    CreatedAt,
+    Name,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TokenManagerFilter {
+    pub created_after: Option<u64>,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SupportedAssetSort {
+    #[default]
+    CreatedAt,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SupportedAssetFilter {
+    pub asset_type: Option<AssetType>,
+    pub custody: Option<AssetCustody>,
+    pub status: Option<SupportedAssetStatus>,
+    pub created_after: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, strum::Display, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum EventType {
+    Mint,
+    Burn,
+    Transfer,
+    Lock,
+    Unlock,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Counterparty {
+    pub ua_addr: String,
+    pub account_id: String,
+    pub asset_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Event {
+    pub event_id: String,
+    pub event_type: EventType,
+    pub token_manager_id: String,
+    pub user_id: String,
+    pub ua_addr: String,
+    pub account_id: String,
+    pub asset_id: String,
+    pub asset_info: AssetInfo,
+    pub counterparty: Option<Counterparty>,
+    pub created_at: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Webhook {
+    pub token_manager_id: String,
+    pub url: String,
+    pub secret: String,
+    /// Event types this webhook is subscribed to, an empty list subscribes to every event.
+    pub events: Vec<EventType>,
+    pub created_at: u64,
+}
+
+impl Webhook {
+    pub fn accepts(&self, event_type: EventType) -> bool {
+        self.events.is_empty() || self.events.contains(&event_type)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Delivery {
+    pub delivery_id: String,
+    pub webhook_id: String,
+    pub token_manager_id: String,
+    pub event: Event,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+    pub last_attempt_at: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Holding {
+    pub user_id: String,
+    pub ua_addr: String,
+    pub account_id: String,
+    pub token_manager_id: String,
+    pub asset_type: AssetType,
+    pub asset_id: String,
+    pub asset_info: AssetInfo,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum SyncPayload {
+    Change(Box<Event>),
+    /// Full state of every custodial holding managed by the token manager.
+    Snapshot(Vec<Holding>),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct SyncRecord {
+    pub sequence: u64,
+    pub token_manager_id: String,
+    pub payload: SyncPayload,
+    pub created_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct SyncStatus {
+    pub last_sequence: u64,
+    pub acknowledged_sequence: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct ProofEntry {
+    pub index: u64,
+    pub transaction_id: String,
+    pub previous_hash: String,
+    pub hash: String,
+    pub recorded_at: u64,
+    pub event: Event,
+}
+
+/// Compiled WebAssembly module run by the workflow runtime.
+#[derive(Clone, Debug)]
+pub struct Workflow {
+    pub name: String,
+    pub module: Vec<u8>,
+    pub created_at: u64,
+}
+
+/// Operations a descriptor may be used for, named after the flags of `intend`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum Purpose {
+    #[serde(rename = "O_DEBIT")]
+    Debit,
+    #[serde(rename = "O_CREDIT")]
+    Credit,
+    #[serde(rename = "O_READ")]
+    Read,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Descriptor {
+    pub user_id: String,
+    pub account_id: String,
+    pub purposes: Vec<Purpose>,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+impl Descriptor {
+    pub fn allows(&self, purpose: Purpose) -> bool {
+        self.purposes.contains(&purpose)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Lock {
+    pub holder: String,
+    pub expires_at: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Mount {
+    pub token_manager_id: String,
+    pub created_at: u64,
+}
+
+/// Reference to an account, either through its ids or through a path in the namespace.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum AccountRef {
+    Path { path: String },
+    Ids { user_id: String, account_id: String },
+}
+
+/// Single step of a saga, each undone by its inverse when a later step fails.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum SagaStep {
+    Debit {
+        account: AccountRef,
+        amount: u64,
+    },
+    Credit {
+        account: AccountRef,
+        amount: u64,
+    },
+    Transfer {
+        from: AccountRef,
+        to: AccountRef,
+        amount: u64,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct SagaDefinition {
+    pub name: String,
+    /// Subject that defined the saga, as `users/<id>` or `token_managers/<id>`, unset when it was
+    /// defined without authentication.
+    pub owner: Option<String>,
+    pub steps: Vec<SagaStep>,
+    pub created_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SagaStatus {
+    Running,
+    /// A step failed or cancellation was requested, completed steps are being undone.
+    Compensating,
+    Completed,
+    /// Every completed step was undone after a failure.
+    Compensated,
+    Cancelled,
+    /// Compensation itself failed, the instance needs a retry or manual intervention.
+    Failed,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Completed,
+    Failed,
+    Compensated,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct StepState {
+    pub status: StepStatus,
+    /// Ids of the assets created by the step.
+    pub asset_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl Default for StepState {
+    fn default() -> Self {
+        Self {
+            status: StepStatus::Pending,
+            asset_ids: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct SagaInstance {
+    pub saga_id: String,
+    pub status: SagaStatus,
+    pub steps: Vec<StepState>,
+    pub cancel_requested: bool,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// What a party commits to a swap.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SwapOffer {
+    Asset { asset_id: String },
+    Cash { amount: u64 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct SwapLeg {
+    pub user_id: String,
+    pub ua_addr: String,
+    /// Account the offer is taken from.
+    pub account_id: String,
+    /// Account of the same user receiving the other leg.
+    pub receive_account_id: String,
+    pub offer: SwapOffer,
+    pub funded: bool,
+    /// Assets locked for the swap once the leg is funded.
+    pub asset_ids: Vec<String>,
+    /// Assets the leg delivered to the other party, moved back when settlement is rolled back.
+    #[serde(default)]
+    pub delivered_asset_ids: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapStatus {
+    /// Waiting for both legs to be funded.
+    Open,
+    Settled,
+    Cancelled,
+    /// Not funded by both parties before the deadline.
+    Expired,
+    /// Settlement was attempted and rolled back.
+    Failed,
+    /// Settlement failed and some delivered assets couldn't be moved back yet, the rollback is
+    /// retried in the background until it completes.
+    RollbackFailed,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Swap {
+    pub maker: SwapLeg,
+    pub taker: SwapLeg,
+    pub status: SwapStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub settled_at: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cadence {
+    Once,
+    Daily,
+    Weekly,
+    /// Same day of every month, or its last day for shorter months.
+    Monthly,
+}
+
+impl Cadence {
+    /// Time of the `occurrence`-th run of a schedule starting at `start_at`, `None` once a
+    /// one-off schedule has run.
+    pub fn occurrence(&self, start_at: u64, occurrence: u32) -> Option<u64> {
+        const DAY: u64 = 86400;
+
+        match self {
+            Self::Once => (occurrence == 0).then_some(start_at),
+            Self::Daily => Some(start_at + u64::from(occurrence) * DAY),
+            Self::Weekly => Some(start_at + u64::from(occurrence) * 7 * DAY),
+            Self::Monthly => Some(crate::utils::add_months(start_at, occurrence)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleStatus {
+    Active,
+    Paused,
+    /// Every occurrence has run.
+    Completed,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct ScheduleRun {
+    pub scheduled_for: u64,
+    pub executed_at: u64,
+    /// Ids of the assets created on the peer side when the transfer went through.
+    pub peer_asset_ids: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Standing instruction to transfer cash to a peer at set times.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Schedule {
+    pub user_id: String,
+    pub account_id: String,
+    pub peer_ua_addr: String,
+    pub peer_account_id: String,
+    pub amount: u64,
+    pub cadence: Cadence,
+    pub start_at: u64,
+    /// Index of the next occurrence counted from `start_at`.
+    pub occurrence: u32,
+    pub next_run_at: Option<u64>,
+    pub status: ScheduleStatus,
+    pub failures: u32,
+    /// Most recent runs, oldest first.
+    pub history: Vec<ScheduleRun>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// Random value a principal signs to prove possession of its key when logging in.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Challenge {
+    /// Principal logging in, as `users/<id>` or `token_managers/<id>`.
+    pub subject: String,
+    pub nonce: String,
+    pub expires_at: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Session {
+    pub subject: String,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+/// Role held by a principal, see `docs/asset-scheme/json-ld/role.ld.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "role", rename_all = "snake_case")]
+pub enum Role {
+    /// Acts on every resource, and manages the roles of others.
+    PlatformAdmin,
+    /// Acts for the token manager, as the token manager itself does.
+    TokenManagerOperator { token_manager_id: String },
+    /// Acts on its own resources. Granted on registration, revoking it suspends the user.
+    User,
+    /// Reads every resource, without acting on any.
+    Auditor,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct RoleGrant {
+    /// Principal holding the role, as `users/<id>` or `token_managers/<id>`.
+    pub subject: String,
+    pub role: Role,
+    /// Principal that granted the role, `None` for grants made by the ledger itself.
+    pub granted_by: Option<String>,
+    pub granted_at: u64,
+}
+
+/// Operation on an account or asset that permission bits are given for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    View,
+    Debit,
+    Credit,
+    Transfer,
+}
+
+impl Permission {
+    pub const ALL: [Self; 4] = [Self::View, Self::Debit, Self::Credit, Self::Transfer];
+
+    pub fn bit(self) -> u8 {
+        match self {
+            Self::View => 0b1000,
+            Self::Debit => 0b0100,
+            Self::Credit => 0b0010,
+            Self::Transfer => 0b0001,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            Self::View => 'v',
+            Self::Debit => 'd',
+            Self::Credit => 'c',
+            Self::Transfer => 't',
+        }
+    }
+}
+
+/// Permission bits of an account or asset for its owner, its group and everyone else, in the
+/// manner of an inode mode. The group is the token manager behind the account with its operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub struct Mode {
+    pub owner: u8,
+    pub group: u8,
+    pub other: u8,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self {
+            owner: 0b1111,
+            group: Permission::View.bit() | Permission::Credit.bit(),
+            other: 0,
+        }
+    }
+}
+
+/// Permissions delegated to a UA address on top of the mode.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct AccessGrant {
+    pub permissions: u8,
+    /// Total value the grantee may take out through debits and transfers, unlimited when `None`.
+    pub debit_limit: Option<u64>,
+    pub debited: u64,
+    pub expires_at: Option<u64>,
+    pub granted_at: u64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct Acl {
+    pub mode: Mode,
+    /// Grants by the UA address of the grantee.
+    pub grants: HashMap<String, AccessGrant>,
+}
+
+/// Operation policies are evaluated before.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, strum::Display, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PolicyOperation {
+    Mint,
+    Transfer,
+    Verb,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    pub const ALL: [Self; 7] = [
+        Self::Mon,
+        Self::Tue,
+        Self::Wed,
+        Self::Thu,
+        Self::Fri,
+        Self::Sat,
+        Self::Sun,
+    ];
+
+    pub fn workdays() -> Vec<Self> {
+        Self::ALL[..5].to_vec()
+    }
+}
+
+/// Declarative rule of a policy, see `docs/asset-scheme/json-ld/policy.ld.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, strum::IntoStaticStr, ToSchema)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PolicyRule {
+    /// Largest amount a single operation may move.
+    MaxAmount { max_amount: u64 },
+    /// Jurisdictions the counterparty has to be registered in. The counterparty of a mint is the
+    /// holder receiving it.
+    Jurisdictions { allowed: Vec<String> },
+    /// Hours of the day, `start_hour` included and `end_hour` excluded, and days of the week
+    /// operations are accepted on, in the time zone at `utc_offset_minutes`.
+    BusinessHours {
+        start_hour: u8,
+        end_hour: u8,
+        #[serde(default = "Weekday::workdays")]
+        days: Vec<Weekday>,
+        #[serde(default)]
+        utc_offset_minutes: i32,
+    },
+    /// Total amount and number of operations accepted over any window of `window_secs`.
+    Velocity {
+        window_secs: u64,
+        max_amount: Option<u64>,
+        max_count: Option<u64>,
+    },
+}
+
+/// What a policy is attached to. Policies of a supported asset apply to every account holding
+/// that asset type with the token manager.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PolicyTarget {
+    SupportedAsset {
+        token_manager_id: String,
+        supported_asset_id: String,
+        asset_type: AssetType,
+    },
+    Account {
+        user_id: String,
+        account_id: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Policy {
+    pub name: String,
+    pub target: PolicyTarget,
+    pub rule: PolicyRule,
+    /// Operations the policy is evaluated before, every operation when empty.
+    pub operations: Vec<PolicyOperation>,
+    pub created_by: Option<String>,
+    pub created_at: u64,
+}
+
+/// Operation counted by a velocity policy, reserved when it is admitted and dropped again when it
+/// doesn't go through.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct PolicyUsage {
+    pub usage_id: String,
+    pub at: u64,
+    pub amount: u64,
+}
+
+/// Amount counted against the debit limit of an access grant, given back when the operation it
+/// was charged for doesn't go through.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct GrantCharge {
+    pub resource: String,
+    pub ua_addr: String,
+    pub amount: u64,
+}
+
+/// Signers of a treasury account. Transfers out of it above `threshold` wait for `required` of
+/// the `signers`, given by their universal addresses, to approve them.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Multisig {
+    pub threshold: u64,
+    pub required: u32,
+    pub signers: Vec<String>,
+    pub updated_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    /// Waiting for the signers.
+    Pending,
+    Executed,
+    /// Rejected by enough signers that the quorum can no longer be reached.
+    Rejected,
+    /// Not approved by enough signers before the deadline.
+    Expired,
+    /// Approved, but the action couldn't be carried out.
+    Failed,
+    /// Dropped along with the account it was requested for.
+    Cancelled,
+}
+
+/// What an approval carries out once enough signers approve it.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApprovalAction {
+    /// Transfer out of the account, above its threshold.
+    Transfer {
+        asset_id: String,
+        amount: u64,
+        peer_ua_addr: String,
+        peer_account_id: String,
+        /// Id of the asset on the peer side, once the transfer went through.
+        peer_asset_id: Option<String>,
+    },
+    /// Change loosening the signers of the account, or dropping them when `multisig` is `None`.
+    Setup { multisig: Option<Multisig> },
+}
+
+/// Transfer out of a treasury account, or change to its signers, held back until enough of the
+/// signers approve it.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct Approval {
+    pub user_id: String,
+    pub account_id: String,
+    pub action: ApprovalAction,
+    pub requested_by: Option<String>,
+    /// Signers and quorum of the account at the time of the request.
+    pub signers: Vec<String>,
+    pub required: u32,
+    pub approvals: Vec<String>,
+    pub rejections: Vec<String>,
+    /// Charged to the grant of the requester, given back unless the transfer goes through.
+    pub charge: Option<GrantCharge>,
+    pub status: ApprovalStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub decided_at: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// Mutating request as recorded in the audit trail.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct AuditEntry {
+    /// `None` for anonymous callers and those whose credentials were turned away.
+    pub principal: Option<String>,
+    pub method: String,
+    /// Route the request matched, with its parameters left as placeholders.
+    pub route: String,
+    pub path: String,
+    /// Hex encoded sha256 of the request body.
+    pub request_digest: String,
+    pub status: u16,
+    pub outcome: AuditOutcome,
+    pub error: Option<String>,
+    /// Ids of the entities the request touched, by the name of the route parameter or response
+    /// field holding them.
+    pub entity_ids: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct AuditRecord {
+    pub index: u64,
+    pub previous_hash: String,
+    pub hash: String,
+    pub recorded_at: u64,
+    pub entry: AuditEntry,
 }
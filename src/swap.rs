@@ -0,0 +1,415 @@
+//! Delivery-versus-payment swaps between two parties.
+//!
+//! Each party funds its leg by locking the assets it offers on behalf of the swap. Once both legs
+//! are funded the assets are exchanged in one go; when the second leg can't be delivered the first
+//! one is moved back, so that either both parties receive or neither does. A rollback that fails
+//! midway is kept on the swap and retried by the [`worker`] until every asset is back.
+
+use std::time::Duration;
+
+use error_stack::{ensure, ResultExt};
+use nanoid::nanoid;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::error::{LedgerError, SResult, SwapError};
+use crate::ledger;
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{AssetInfo, Swap, SwapLeg, SwapOffer, SwapStatus};
+use crate::storage::SwapInterface;
+use crate::utils::unix_timestamp;
+
+/// How long a request may hold a swap exclusively before others can step in.
+const GUARD_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[schema(as = SwapSide)]
+pub enum Side {
+    Maker,
+    Taker,
+}
+
+/// Fetches the swap, expiring it first when its deadline passed while it was still open.
+pub async fn get(app_state: &AppState, swap_id: &str) -> SResult<Swap, SwapError> {
+    let swap = interface(app_state)
+        .await?
+        .get_swap(swap_id)
+        .await
+        .change_context(SwapError::NotFound)?;
+
+    refresh(app_state, swap_id, swap).await
+}
+
+pub async fn refresh(
+    app_state: &AppState,
+    swap_id: &str,
+    mut swap: Swap,
+) -> SResult<Swap, SwapError> {
+    if swap.status == SwapStatus::Open && swap.expires_at <= unix_timestamp() {
+        release(app_state, swap_id, &swap).await?;
+
+        swap.status = SwapStatus::Expired;
+        update(app_state, swap_id, &swap).await?;
+    }
+
+    Ok(swap)
+}
+
+/// Funds one leg of the swap, settling it when the other leg is already funded.
+pub async fn fund(app_state: &AppState, swap_id: &str, side: Side) -> SResult<Swap, SwapError> {
+    let guard = enter(app_state, swap_id).await?;
+    let output = fund_leg(app_state, swap_id, side).await;
+    leave(app_state, swap_id, &guard).await?;
+
+    output
+}
+
+/// Cancels a swap that hasn't settled yet, handing the funded assets back to their owners.
+pub async fn cancel(app_state: &AppState, swap_id: &str) -> SResult<Swap, SwapError> {
+    let guard = enter(app_state, swap_id).await?;
+    let output = cancel_open(app_state, swap_id).await;
+    leave(app_state, swap_id, &guard).await?;
+
+    output
+}
+
+async fn fund_leg(app_state: &AppState, swap_id: &str, side: Side) -> SResult<Swap, SwapError> {
+    let mut swap = get(app_state, swap_id).await?;
+
+    ensure!(swap.status == SwapStatus::Open, SwapError::NotOpen);
+
+    let expires_at = swap.expires_at;
+    let leg = match side {
+        Side::Maker => &mut swap.maker,
+        Side::Taker => &mut swap.taker,
+    };
+
+    ensure!(!leg.funded, SwapError::AlreadyFunded);
+
+    leg.asset_ids = match &leg.offer {
+        SwapOffer::Asset { asset_id } => {
+            let (_, AssetInfo::Cash { amount, .. }) =
+                ledger::find_asset(app_state, &leg.user_id, &leg.account_id, asset_id)
+                    .await
+                    .change_context(SwapError::FundingFailed)?
+                    .ok_or(SwapError::FundingFailed)?;
+
+            ledger::ensure_within_threshold(app_state, &leg.user_id, &leg.account_id, amount)
+                .await
+                .change_context(SwapError::FundingFailed)?;
+
+            ledger::lock_asset(
+                app_state,
+                &leg.user_id,
+                &leg.account_id,
+                asset_id,
+                swap_id,
+                expires_at,
+            )
+            .await
+            .change_context(SwapError::FundingFailed)?;
+
+            vec![asset_id.clone()]
+        }
+        SwapOffer::Cash { amount } => ledger::earmark(
+            app_state,
+            &leg.user_id,
+            &leg.account_id,
+            *amount,
+            swap_id,
+            expires_at,
+        )
+        .await
+        .change_context(SwapError::FundingFailed)?,
+    };
+    leg.funded = true;
+
+    if !(swap.maker.funded && swap.taker.funded) {
+        update(app_state, swap_id, &swap).await?;
+
+        return Ok(swap);
+    }
+
+    let settlement = settle(app_state, swap_id, &mut swap).await;
+
+    match settlement {
+        Ok(()) => {
+            release(app_state, swap_id, &swap).await?;
+
+            swap.status = SwapStatus::Settled;
+            swap.settled_at = Some(unix_timestamp());
+            update(app_state, swap_id, &swap).await?;
+
+            Ok(swap)
+        }
+        Err(error) => {
+            swap.last_error = Some(error.current_context().to_string());
+
+            let rollback = roll_back(app_state, &mut swap).await;
+
+            release(app_state, swap_id, &swap).await?;
+
+            match rollback {
+                Ok(()) => {
+                    swap.status = SwapStatus::Failed;
+                    update(app_state, swap_id, &swap).await?;
+
+                    Err(error.change_context(SwapError::SettlementFailed))
+                }
+                Err(rollback_error) => {
+                    error!(
+                        ?rollback_error,
+                        swap_id, "Failed while rolling back the swap"
+                    );
+
+                    swap.status = SwapStatus::RollbackFailed;
+                    update(app_state, swap_id, &swap).await?;
+
+                    Err(rollback_error.change_context(SwapError::RollbackFailed))
+                }
+            }
+        }
+    }
+}
+
+/// Polls for swaps whose rollback didn't complete and retries moving the remaining assets back.
+pub async fn worker(app_state: AppState) {
+    let settings = app_state.config.swaps.clone();
+
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(settings.retry_interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = retry_rollbacks(&app_state).await {
+            error!(?error, "Failed while retrying swap rollbacks");
+        }
+    }
+}
+
+async fn retry_rollbacks(app_state: &AppState) -> SResult<(), SwapError> {
+    let stuck = interface(app_state)
+        .await?
+        .list_swaps()
+        .await
+        .change_context(SwapError::StorageError)?
+        .into_iter()
+        .filter(|(_, swap)| swap.status == SwapStatus::RollbackFailed);
+
+    for (swap_id, _) in stuck {
+        // the swap is being worked on, it's picked up again on the next poll
+        let Ok(guard) = enter(app_state, &swap_id).await else {
+            continue;
+        };
+
+        let output = resume_rollback(app_state, &swap_id).await;
+        leave(app_state, &swap_id, &guard).await?;
+
+        if let Err(error) = output {
+            warn!(?error, swap_id, "Rollback of the swap failed again");
+        }
+    }
+
+    Ok(())
+}
+
+async fn resume_rollback(app_state: &AppState, swap_id: &str) -> SResult<(), SwapError> {
+    let mut swap = get(app_state, swap_id).await?;
+
+    if swap.status != SwapStatus::RollbackFailed {
+        return Ok(());
+    }
+
+    let rollback = roll_back(app_state, &mut swap).await;
+
+    if rollback.is_ok() {
+        swap.status = SwapStatus::Failed;
+    }
+
+    // whatever was moved back is dropped from the swap, also when the rest failed again
+    update(app_state, swap_id, &swap).await?;
+
+    rollback.change_context(SwapError::RollbackFailed)
+}
+
+async fn cancel_open(app_state: &AppState, swap_id: &str) -> SResult<Swap, SwapError> {
+    let mut swap = get(app_state, swap_id).await?;
+
+    ensure!(swap.status == SwapStatus::Open, SwapError::NotOpen);
+
+    release(app_state, swap_id, &swap).await?;
+
+    swap.status = SwapStatus::Cancelled;
+    update(app_state, swap_id, &swap).await?;
+
+    Ok(swap)
+}
+
+/// Exchanges the funded legs, recording what was delivered on the legs so that it can be rolled
+/// back when any move fails.
+async fn settle(app_state: &AppState, swap_id: &str, swap: &mut Swap) -> SResult<(), LedgerError> {
+    // both legs are admitted before anything moves, so that a rejection leaves nothing to undo
+    let mut admissions = Vec::new();
+
+    for (from, to) in [(&swap.maker, &swap.taker), (&swap.taker, &swap.maker)] {
+        let admission = ledger::admit_move(
+            app_state,
+            &from.user_id,
+            &from.account_id,
+            &from.asset_ids,
+            &to.ua_addr,
+            &to.receive_account_id,
+        )
+        .await;
+
+        match admission {
+            Ok(admission) => admissions.push(admission),
+            Err(error) => {
+                for admission in admissions {
+                    admission.release(app_state).await;
+                }
+
+                return Err(error);
+            }
+        }
+    }
+
+    let delivered = async {
+        deliver(app_state, swap_id, &mut swap.maker, &swap.taker).await?;
+        deliver(app_state, swap_id, &mut swap.taker, &swap.maker).await
+    }
+    .await;
+
+    for admission in admissions {
+        admission.conclude(app_state, &delivered).await;
+    }
+
+    delivered
+}
+
+/// Moves the assets of `from` into the receiving account of `to`, recording the new asset ids.
+async fn deliver(
+    app_state: &AppState,
+    swap_id: &str,
+    from: &mut SwapLeg,
+    to: &SwapLeg,
+) -> SResult<(), LedgerError> {
+    for asset_id in &from.asset_ids {
+        from.delivered_asset_ids.push(
+            ledger::move_asset(
+                app_state,
+                &from.user_id,
+                &from.account_id,
+                asset_id,
+                &to.ua_addr,
+                &to.receive_account_id,
+                Some(swap_id),
+            )
+            .await?,
+        );
+    }
+
+    Ok(())
+}
+
+/// Moves everything delivered back to where it came from. Both legs are attempted even when one
+/// fails, and every asset that made it back is dropped from the leg.
+async fn roll_back(app_state: &AppState, swap: &mut Swap) -> SResult<(), LedgerError> {
+    let to_maker = undo(app_state, &mut swap.taker, &swap.maker).await;
+    let to_taker = undo(app_state, &mut swap.maker, &swap.taker).await;
+
+    match (to_maker, to_taker) {
+        (Err(mut error), Err(other)) => {
+            error.extend_one(other);
+
+            Err(error)
+        }
+        (Err(error), Ok(())) | (Ok(()), Err(error)) => Err(error),
+        (Ok(()), Ok(())) => Ok(()),
+    }
+}
+
+async fn undo(app_state: &AppState, from: &mut SwapLeg, to: &SwapLeg) -> SResult<(), LedgerError> {
+    while let Some(asset_id) = from.delivered_asset_ids.first() {
+        ledger::move_asset(
+            app_state,
+            &to.user_id,
+            &to.receive_account_id,
+            asset_id,
+            &from.ua_addr,
+            &from.account_id,
+            None,
+        )
+        .await?;
+
+        from.delivered_asset_ids.remove(0);
+    }
+
+    Ok(())
+}
+
+/// Releases the locks the swap holds on the assets of both legs.
+async fn release(app_state: &AppState, swap_id: &str, swap: &Swap) -> SResult<(), SwapError> {
+    for leg in [&swap.maker, &swap.taker] {
+        for asset_id in &leg.asset_ids {
+            ledger::unlock_asset(app_state, &leg.user_id, &leg.account_id, asset_id, swap_id)
+                .await
+                .change_context(SwapError::StorageError)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes the swap for the duration of a request, so that concurrent requests can't settle it
+/// twice.
+async fn enter(app_state: &AppState, swap_id: &str) -> SResult<String, SwapError> {
+    let guard = nanoid!();
+
+    app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(SwapError::StorageError)?
+        .acquire(
+            &format!("swaps/{swap_id}"),
+            &guard,
+            unix_timestamp() + GUARD_SECS,
+        )
+        .await
+        .change_context(SwapError::Busy)?;
+
+    Ok(guard)
+}
+
+async fn leave(app_state: &AppState, swap_id: &str, guard: &str) -> SResult<(), SwapError> {
+    app_state
+        .storage
+        .get_lock_interface()
+        .await
+        .change_context(SwapError::StorageError)?
+        .release(&format!("swaps/{swap_id}"), guard)
+        .await
+        .change_context(SwapError::StorageError)
+}
+
+async fn update(app_state: &AppState, swap_id: &str, swap: &Swap) -> SResult<(), SwapError> {
+    interface(app_state)
+        .await?
+        .update_swap(swap_id, swap.clone())
+        .await
+        .change_context(SwapError::StorageError)
+}
+
+async fn interface(
+    app_state: &AppState,
+) -> SResult<Box<dyn SwapInterface + Send + Sync>, SwapError> {
+    app_state
+        .storage
+        .get_swap_interface()
+        .await
+        .change_context(SwapError::StorageError)
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use crate::config::SyncSettings;
+use crate::error::{SResult, StorageError};
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{
+    AssetCustody, AssetType, Holding, SyncPayload, SyncRecord, TokenManager,
+};
+
+pub const SEQUENCE_HEADER: &str = "x-finternet-sequence";
+
+/// Pushes outbox records to the mirror maintained by a token manager.
+#[async_trait::async_trait]
+pub trait TokenManagerDriver: Send + Sync {
+    async fn push(&self, token_manager: &TokenManager, record: &SyncRecord) -> Result<(), String>;
+}
+
+/// Driver that posts every record to the `sync_url` registered by the token manager.
+pub struct HttpDriver {
+    client: reqwest::Client,
+}
+
+impl HttpDriver {
+    pub fn new(settings: &SyncSettings) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.request_timeout_secs))
+            .build()?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait::async_trait]
+impl TokenManagerDriver for HttpDriver {
+    async fn push(&self, token_manager: &TokenManager, record: &SyncRecord) -> Result<(), String> {
+        let sync_url = token_manager
+            .sync_url
+            .as_ref()
+            .ok_or("token manager has no sync url registered")?;
+
+        let response = self
+            .client
+            .post(sync_url)
+            .header(SEQUENCE_HEADER, record.sequence.to_string())
+            .json(record)
+            .send()
+            .await
+            .map_err(|error| error.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("driver responded with {}", response.status()))
+        }
+    }
+}
+
+/// Custody model the token manager registered for the asset type, assets that aren't registered
+/// are treated as native.
+pub async fn custody_of(
+    app_state: &AppState,
+    token_manager_id: &str,
+    asset_type: &AssetType,
+) -> SResult<AssetCustody, StorageError> {
+    let supported_assets = app_state
+        .storage
+        .get_token_manager_interface()
+        .await?
+        .get_supported_asset_interface(token_manager_id)
+        .await?
+        .list_supported_assets()
+        .await?;
+
+    Ok(supported_assets
+        .into_iter()
+        .find(|(_, asset)| &asset.asset_type == asset_type)
+        .map(|(_, asset)| asset.custody)
+        .unwrap_or_default())
+}
+
+/// Current state of every custodial asset managed by the token manager.
+pub async fn snapshot(
+    app_state: &AppState,
+    token_manager_id: &str,
+) -> SResult<Vec<Holding>, StorageError> {
+    let custodial = app_state
+        .storage
+        .get_token_manager_interface()
+        .await?
+        .get_supported_asset_interface(token_manager_id)
+        .await?
+        .list_supported_assets()
+        .await?
+        .into_iter()
+        .filter(|(_, asset)| asset.custody == AssetCustody::Custodial)
+        .map(|(_, asset)| asset.asset_type)
+        .collect::<Vec<_>>();
+
+    Ok(crate::ledger::holdings(app_state)
+        .await?
+        .into_iter()
+        .filter(|holding| {
+            holding.token_manager_id == token_manager_id && custodial.contains(&holding.asset_type)
+        })
+        .collect())
+}
+
+/// Queues a full-state snapshot behind the pending changes, returning its sequence number.
+pub async fn resync(app_state: &AppState, token_manager_id: &str) -> SResult<u64, StorageError> {
+    let holdings = snapshot(app_state, token_manager_id).await?;
+
+    app_state
+        .storage
+        .get_sync_interface()
+        .await?
+        .append(token_manager_id, SyncPayload::Snapshot(holdings))
+        .await
+}
+
+/// Drains the outbox of every token manager in sequence order.
+///
+/// A record is only acknowledged once the driver accepts it, so records are delivered at least
+/// once and a failure holds back every later record of that token manager.
+pub async fn worker(app_state: AppState) {
+    let settings = app_state.config.sync.clone();
+
+    let driver = match HttpDriver::new(&settings) {
+        Ok(driver) => driver,
+        Err(error) => {
+            error!(?error, "Failed while building the sync driver");
+            return;
+        }
+    };
+
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(settings.poll_interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = drain(&app_state, &driver, &settings).await {
+            error!(?error, "Failed while draining the sync outbox");
+        }
+    }
+}
+
+async fn drain(
+    app_state: &AppState,
+    driver: &dyn TokenManagerDriver,
+    settings: &SyncSettings,
+) -> SResult<(), StorageError> {
+    let sync_interface = app_state.storage.get_sync_interface().await?;
+    let token_manager_interface = app_state.storage.get_token_manager_interface().await?;
+
+    for token_manager_id in sync_interface.pending_token_managers().await? {
+        let token_manager = token_manager_interface
+            .get_token_manager(&token_manager_id)
+            .await?;
+
+        for record in sync_interface
+            .pending(&token_manager_id, settings.batch_size)
+            .await?
+        {
+            if let Err(reason) = driver.push(&token_manager, &record).await {
+                warn!(
+                    token_manager_id,
+                    sequence = record.sequence,
+                    "Sync delivery failed: {reason}"
+                );
+                break;
+            }
+
+            sync_interface
+                .acknowledge(&token_manager_id, record.sequence)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
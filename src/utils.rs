@@ -0,0 +1,101 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds elapsed since the unix epoch.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Days since the unix epoch of the given civil date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Civil date of the given number of days since the unix epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+
+    (days_from_civil(next_year, next_month, 1) - days_from_civil(year, month, 1)) as u32
+}
+
+/// Moves the timestamp by whole calendar months, keeping the time of day and clamping the day to
+/// the length of the target month, e.g. Jan 31 + 1 month is Feb 28 (or 29).
+pub fn add_months(timestamp: u64, months: u32) -> u64 {
+    const DAY: u64 = 86400;
+
+    let (year, month, day) = civil_from_days((timestamp / DAY) as i64);
+
+    let index = year * 12 + i64::from(month - 1) + i64::from(months);
+    let (year, month) = (index.div_euclid(12), index.rem_euclid(12) as u32 + 1);
+    let day = day.min(days_in_month(year, month));
+
+    days_from_civil(year, month, day) as u64 * DAY + timestamp % DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-31 12:30 UTC
+    const JAN_31_2024: u64 = 1_706_704_200;
+
+    #[test]
+    fn clamps_to_the_end_of_a_leap_february() {
+        // 2024-02-29 12:30 UTC
+        assert_eq!(add_months(JAN_31_2024, 1), 1_709_209_800);
+    }
+
+    #[test]
+    fn clamps_to_the_end_of_a_common_february() {
+        // 2023-01-31 and 2023-02-28, midnight UTC
+        assert_eq!(add_months(1_675_123_200, 1), 1_677_542_400);
+    }
+
+    #[test]
+    fn crosses_year_boundaries() {
+        // 2023-12-31 08:00 UTC to 2024-02-29 08:00 UTC
+        assert_eq!(add_months(1_704_009_600, 2), 1_709_193_600);
+        // 2024-05-31 to 2025-05-31, midnight UTC
+        assert_eq!(add_months(1_717_113_600, 12), 1_748_649_600);
+    }
+
+    #[test]
+    fn keeps_the_day_when_the_month_is_long_enough() {
+        // 2023-12-31 08:00 UTC to 2024-03-31 08:00 UTC
+        assert_eq!(add_months(1_704_009_600, 3), 1_711_872_000);
+        assert_eq!(add_months(JAN_31_2024, 0), JAN_31_2024);
+    }
+}
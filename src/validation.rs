@@ -0,0 +1,138 @@
+//! Validation of request bodies.
+//!
+//! [`ValidJson`] takes the place of [`axum::Json`] for bodies that are checked beyond their shape.
+//! Deserialization failures name the field they occurred at, and every rule the body breaks is
+//! reported at once rather than only the first one.
+
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::header;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::ApiError;
+
+/// Field the body breaks a rule at and the rule it breaks. The field is a path such as
+/// `token_manager_ref.id`, `.` standing for the body as a whole.
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    pub field: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Violations(Vec<Violation>);
+
+impl Violations {
+    /// Records a violation of `field` unless `valid` holds.
+    pub fn check(&mut self, valid: bool, field: &str, reason: &str) {
+        if !valid {
+            self.0.push(Violation {
+                field: field.to_string(),
+                reason: reason.to_string(),
+            });
+        }
+    }
+
+    pub fn non_empty(&mut self, value: &str, field: &str) {
+        self.check(!value.trim().is_empty(), field, "must not be empty");
+    }
+
+    pub fn into_result(self) -> Result<(), ApiError> {
+        match self.0.is_empty() {
+            true => Ok(()),
+            false => Err(ApiError::InvalidRequestError(self.0)),
+        }
+    }
+}
+
+/// Rules a request body has to follow once it is deserialized.
+pub trait Validate {
+    fn validate(&self, violations: &mut Violations);
+}
+
+/// Json body that is deserialized and then validated, rejecting the request with the field paths
+/// and reasons of what is wrong with it.
+pub struct ValidJson<T>(pub T);
+
+#[axum::async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_json = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+
+        let mut violations = Violations::default();
+        violations.check(
+            is_json,
+            ".",
+            "request must be sent with `Content-Type: application/json`",
+        );
+        violations.into_result()?;
+
+        let body = Bytes::from_request(request, state)
+            .await
+            .map_err(|_| ApiError::RequestBodyError)?;
+
+        let mut deserializer = serde_json::Deserializer::from_slice(&body);
+
+        let value: T = serde_path_to_error::deserialize(&mut deserializer).map_err(|error| {
+            ApiError::InvalidRequestError(vec![Violation {
+                field: error.path().to_string(),
+                reason: error.into_inner().to_string(),
+            }])
+        })?;
+
+        deserializer.end().map_err(|error| {
+            ApiError::InvalidRequestError(vec![Violation {
+                field: ".".to_string(),
+                reason: error.to_string(),
+            }])
+        })?;
+
+        let mut violations = Violations::default();
+        value.validate(&mut violations);
+        violations.into_result()?;
+
+        Ok(Self(value))
+    }
+}
+
+/// Loose check of an email address: a local part and a domain around a single `@`.
+pub fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && !label.starts_with('-') && !label.ends_with('-'))
+        && !value.chars().any(char::is_whitespace)
+}
+
+/// CAIP-2 chain id: a namespace of 3 to 8 lowercase letters, digits or dashes, and a reference of
+/// 1 to 32 letters, digits, dashes or underscores, e.g. `eip155:1`.
+pub fn is_chain_id(value: &str) -> bool {
+    let Some((namespace, reference)) = value.split_once(':') else {
+        return false;
+    };
+
+    (3..=8).contains(&namespace.len())
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && (1..=32).contains(&reference.len())
+        && reference
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
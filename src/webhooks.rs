@@ -0,0 +1,314 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use sha2::Sha256;
+
+use crate::config::WebhookSettings;
+use crate::error::{SResult, StorageError};
+use crate::logging::prelude::*;
+use crate::state::AppState;
+use crate::storage::types::{Delivery, DeliveryStatus, Webhook};
+use crate::utils::unix_timestamp;
+
+pub const SIGNATURE_HEADER: &str = "x-finternet-signature";
+pub const TIMESTAMP_HEADER: &str = "x-finternet-timestamp";
+pub const EVENT_HEADER: &str = "x-finternet-event";
+pub const DELIVERY_HEADER: &str = "x-finternet-delivery";
+
+/// Signs `{timestamp}.{payload}` with the webhook secret, receivers recompute the digest to verify
+/// that the event originated from this server.
+pub fn sign(secret: &str, timestamp: u64, payload: &[u8]) -> String {
+    // Safety: HMAC accepts keys of any length.
+    #[allow(clippy::expect_used)]
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take a key of any size");
+
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether the address may be reached by a webhook. Loopback, private, link-local (including the
+/// cloud metadata endpoint at 169.254.169.254) and other non-global ranges are refused so that
+/// endpoints can't be pointed at services on the internal network.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0b1100_0000) == 64)
+        // IETF protocol assignments and benchmarking, 192.0.0.0/24 and 198.18.0.0/15
+        || (a == 192 && b == 0 && ip.octets()[2] == 0)
+        || (a == 198 && (b & 0b1111_1110) == 18)
+        // reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local, fc00::/7, which includes the fd00:ec2::254 metadata endpoint
+        || (first & 0xfe00) == 0xfc00
+        // link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // documentation, 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// Resolves the host of the url and ensures that every address it points at is public.
+pub async fn ensure_public(url: &Url) -> Result<(), String> {
+    let port = url.port_or_known_default().unwrap_or(443);
+
+    // ipv6 literals keep their brackets in the url
+    let host = url
+        .host_str()
+        .ok_or("url has no host")?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|error| format!("failed to resolve {host}: {error}"))?
+        .collect();
+
+    if addrs.is_empty() {
+        return Err("host doesn't resolve to any address".to_string());
+    }
+
+    match addrs.iter().find(|addr| !is_public(addr.ip())) {
+        Some(addr) => Err(format!(
+            "host resolves to the non-public address {}",
+            addr.ip()
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Resolver of the delivery client, it drops non-public addresses from every lookup so that a host
+/// that passed the check at registration can't be rebound to an internal address afterwards.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(
+                    format!("{} doesn't resolve to a public address", name.as_str()).into(),
+                );
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Delay before the next attempt, doubling with every failed attempt.
+pub fn backoff(settings: &WebhookSettings, attempts: u32) -> u64 {
+    let factor = 1_u64
+        .checked_shl(attempts.saturating_sub(1))
+        .unwrap_or(u64::MAX);
+
+    settings
+        .base_backoff_secs
+        .saturating_mul(factor)
+        .min(settings.max_backoff_secs)
+}
+
+/// Polls the delivery queue and pushes due events to the registered webhook endpoints.
+pub async fn worker(app_state: AppState) {
+    let settings = app_state.config.webhooks.clone();
+
+    // redirects are not followed, they could lead the request to an address that wasn't checked
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(settings.request_timeout_secs))
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+    {
+        Ok(client) => client,
+        Err(error) => {
+            error!(?error, "Failed while building the webhook client");
+            return;
+        }
+    };
+
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(settings.poll_interval_secs.max(1)));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(error) = process_due(&app_state, &client, &settings).await {
+            error!(?error, "Failed while processing webhook deliveries");
+        }
+    }
+}
+
+async fn process_due(
+    app_state: &AppState,
+    client: &reqwest::Client,
+    settings: &WebhookSettings,
+) -> SResult<(), StorageError> {
+    let webhook_interface = app_state.storage.get_webhook_interface().await?;
+
+    let due = webhook_interface
+        .due_deliveries(unix_timestamp(), settings.batch_size)
+        .await?;
+
+    for mut delivery in due {
+        let outcome = match webhook_interface
+            .get_webhook(&delivery.token_manager_id, &delivery.webhook_id)
+            .await
+        {
+            Ok(webhook) => send(client, &webhook, &delivery).await,
+            Err(_) => Err("webhook has been removed".to_string()),
+        };
+
+        record_attempt(&mut delivery, outcome, unix_timestamp(), settings);
+
+        webhook_interface.update_delivery(delivery).await?;
+    }
+
+    Ok(())
+}
+
+async fn send(
+    client: &reqwest::Client,
+    webhook: &Webhook,
+    delivery: &Delivery,
+) -> Result<(), String> {
+    let url = Url::parse(&webhook.url).map_err(|error| error.to_string())?;
+
+    // addresses given as literals never reach the resolver of the client
+    ensure_public(&url).await?;
+
+    let payload = serde_json::to_vec(&delivery.event).map_err(|error| error.to_string())?;
+    let timestamp = unix_timestamp();
+
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, sign(&webhook.secret, timestamp, &payload))
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(EVENT_HEADER, delivery.event.event_type.to_string())
+        .header(DELIVERY_HEADER, &delivery.delivery_id)
+        .body(payload)
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("endpoint responded with {}", response.status()))
+    }
+}
+
+fn record_attempt(
+    delivery: &mut Delivery,
+    outcome: Result<(), String>,
+    now: u64,
+    settings: &WebhookSettings,
+) {
+    delivery.attempts += 1;
+    delivery.last_attempt_at = Some(now);
+
+    match outcome {
+        Ok(()) => {
+            delivery.status = DeliveryStatus::Delivered;
+            delivery.last_error = None;
+        }
+        Err(reason) => {
+            warn!(
+                delivery_id = delivery.delivery_id,
+                attempts = delivery.attempts,
+                "Webhook delivery failed: {reason}"
+            );
+
+            delivery.last_error = Some(reason);
+
+            if delivery.attempts >= settings.max_attempts {
+                delivery.status = DeliveryStatus::Failed;
+            } else {
+                delivery.next_attempt_at = now + backoff(settings, delivery.attempts);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fd00:ec2::254",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip} passed as public");
+        }
+    }
+
+    #[test]
+    fn accepts_public_addresses() {
+        for ip in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip} refused as internal");
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_urls_of_internal_hosts() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://localhost/hook",
+        ] {
+            assert!(
+                ensure_public(&Url::parse(url).unwrap()).await.is_err(),
+                "{url} passed"
+            );
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! The specification served at `/openapi.json` is generated from the handlers, this keeps the
+//! copy checked in at `docs/openapi.json` in step with it. Changes to the routes or the types they
+//! take and answer show up here first: review the difference, then rerun with `UPDATE_OPENAPI=1`
+//! to accept it.
+
+use std::path::PathBuf;
+
+#[test]
+fn openapi_spec_matches_snapshot() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("docs/openapi.json");
+    let generated = finternet_app_api::app::openapi()
+        .to_pretty_json()
+        .expect("specification should serialize")
+        + "\n";
+
+    if std::env::var_os("UPDATE_OPENAPI").is_some() {
+        std::fs::write(&path, &generated).expect("snapshot should be writable");
+        return;
+    }
+
+    let snapshot = std::fs::read_to_string(&path).unwrap_or_default();
+
+    assert!(
+        snapshot == generated,
+        "generated specification differs from {}, rerun with UPDATE_OPENAPI=1 to accept it",
+        path.display()
+    );
+}